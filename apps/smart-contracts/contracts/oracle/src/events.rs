@@ -0,0 +1,95 @@
+use soroban_sdk::{contractevent, Address};
+
+use crate::types::{Asset, ComplianceStatus, Role};
+
+/// Published whenever a new price record is stored for an asset.
+#[contractevent(topics = ["price"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceUpdated {
+    #[topic]
+    pub asset: Asset,
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// Published when the owner grants a role to an address.
+#[contractevent(topics = ["role_granted"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleGranted {
+    #[topic]
+    pub role: Role,
+    pub address: Address,
+}
+
+/// Published when the owner revokes a role from an address.
+#[contractevent(topics = ["role_revoked"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleRevoked {
+    #[topic]
+    pub role: Role,
+    pub address: Address,
+}
+
+/// Published when an asset is registered for price feeds.
+#[contractevent(topics = ["asset_added"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssetAdded {
+    #[topic]
+    pub asset: Asset,
+}
+
+/// Published when an asset enters its deprecation wind-down.
+#[contractevent(topics = ["asset_removed"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssetRemoved {
+    #[topic]
+    pub asset: Asset,
+}
+
+/// Published when an alias is mapped to a canonical asset.
+#[contractevent(topics = ["alias_set"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AliasSet {
+    #[topic]
+    pub alias: Asset,
+    pub canonical: Asset,
+}
+
+/// Published when an alias is removed.
+#[contractevent(topics = ["alias_removed"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AliasRemoved {
+    #[topic]
+    pub alias: Asset,
+}
+
+/// Published when an emergency price override is proposed (or an
+/// existing proposal is superseded) and still awaits a second signer.
+#[contractevent(topics = ["emergency"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmergencyPriceProposed {
+    #[topic]
+    pub asset: Asset,
+    pub proposer: Address,
+    pub price: i128,
+}
+
+/// Published when `refresh_compliance` downgrades a record whose
+/// license expiry has passed.
+#[contractevent(topics = ["compliance"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ComplianceDowngraded {
+    #[topic]
+    pub asset: Asset,
+    pub old_status: ComplianceStatus,
+    pub new_status: ComplianceStatus,
+    pub license_expiry: u64,
+}
+
+/// Published when an RWA metadata record is created or replaced.
+#[contractevent(topics = ["metadata"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RwaMetadataSet {
+    #[topic]
+    pub asset: Asset,
+}
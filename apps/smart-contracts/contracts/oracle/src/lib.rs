@@ -0,0 +1,979 @@
+//! SEP-40 compliant price oracle for real-world assets (RWAs).
+//!
+//! Serves price feeds for registered assets together with a registry of
+//! descriptive and regulatory metadata per RWA. Prices are pushed by the
+//! admin; consumers read `lastprice` / `price` and the metadata views.
+#![no_std]
+
+mod events;
+mod storage;
+mod types;
+
+#[cfg(test)]
+mod test;
+
+pub use crate::storage::RWAOracleStorage;
+pub use crate::types::{
+    Asset, AssetType, BidAsk, ComplianceStatus, PriceData, RWAMetadata, RegulatoryInfo, Role,
+};
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, Address, BytesN, Env, IntoVal, String, Symbol, Val, Vec,
+};
+
+use crate::events::{
+    AliasRemoved, AliasSet, AssetAdded, AssetRemoved, ComplianceDowngraded,
+    EmergencyPriceProposed, PriceUpdated, RoleGranted, RoleRevoked, RwaMetadataSet,
+};
+use crate::storage::MAX_PRICE_HISTORY;
+
+/// Largest number of entries a single paginated query will return.
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+/// Subscriber callbacks delivered inline with a price write; remaining
+/// subscribers are drained through `notify_subscribers`.
+pub const MAX_NOTIFY_PER_CALL: u32 = 5;
+
+/// Seconds in a (non-leap) year, used for coupon accrual.
+const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+/// Current contract (and storage schema) version; bump alongside any
+/// change to [`RWAOracleStorage`] and teach [`RWAOracle::migrate`] the
+/// corresponding transform.
+pub const CONTRACT_VERSION: u32 = 1;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    Unauthorized = 1,
+    AssetNotFound = 2,
+    AssetAlreadyExists = 3,
+    InvalidTimestamp = 4,
+    InvalidPrice = 5,
+    MetadataNotFound = 6,
+    QuoteUnavailable = 7,
+    UpdateTooFrequent = 8,
+    TooManySubscribers = 9,
+    AssetDeprecated = 10,
+}
+
+#[contract]
+pub struct RWAOracle;
+
+#[contractimpl]
+impl RWAOracle {
+    pub fn __constructor(env: Env, admin: Address, base: Asset, decimals: u32, resolution: u32) {
+        storage::set_admin(&env, &admin);
+        storage::set_schema_version(&env, CONTRACT_VERSION);
+        storage::set_state(
+            &env,
+            &RWAOracleStorage {
+                base,
+                decimals,
+                resolution,
+                last_timestamp: 0,
+            },
+        );
+    }
+
+    // --- SEP-40 views ---------------------------------------------------
+
+    pub fn base(env: Env) -> Asset {
+        storage::get_state(&env).base
+    }
+
+    pub fn decimals(env: Env) -> u32 {
+        storage::get_state(&env).decimals
+    }
+
+    pub fn resolution(env: Env) -> u32 {
+        storage::get_state(&env).resolution
+    }
+
+    pub fn assets(env: Env) -> Vec<Asset> {
+        let mut assets = Vec::new(&env);
+        for i in 0..storage::asset_count(&env) {
+            if let Some(asset) = storage::asset_at(&env, i) {
+                assets.push_back(asset);
+            }
+        }
+        assets
+    }
+
+    /// Timestamp of the most recent record stored across all assets;
+    /// zero before the first update.
+    pub fn last_timestamp(env: Env) -> u64 {
+        storage::get_state(&env).last_timestamp
+    }
+
+    /// Timestamp of the asset's latest record, or `None` if it has never
+    /// been priced. Cheaper than fetching the record for staleness
+    /// checks.
+    pub fn last_timestamp_for(env: Env, asset: Asset) -> Option<u64> {
+        let asset = Self::resolve(&env, asset);
+        storage::get_prices(&env, &asset)
+            .last()
+            .map(|record| record.timestamp)
+    }
+
+    /// Feed freshness for every registered asset: `(asset, age)` where
+    /// `age` is seconds since its latest update, or `u64::MAX` when the
+    /// asset has never been priced. One call replaces a `lastprice` read
+    /// per asset when monitoring checks for stale feeds.
+    pub fn health(env: Env) -> Vec<(Asset, u64)> {
+        let now = env.ledger().timestamp();
+        let mut out = Vec::new(&env);
+        for asset in Self::assets(env.clone()).iter() {
+            let age = match storage::get_prices(&env, &asset).last() {
+                Some(record) => now.saturating_sub(record.timestamp),
+                None => u64::MAX,
+            };
+            out.push_back((asset, age));
+        }
+        out
+    }
+
+    pub fn lastprice(env: Env, asset: Asset) -> Option<PriceData> {
+        let asset = Self::resolve(&env, asset);
+        if Self::is_sunset(&env, &asset) {
+            return None;
+        }
+        storage::get_prices(&env, &asset).last()
+    }
+
+    /// Returns the latest price converted into `quote`, deriving the
+    /// conversion through the base when the stored record is quoted in a
+    /// different currency.
+    pub fn lastprice_in(env: Env, asset: Asset, quote: Asset) -> Result<PriceData, Error> {
+        let asset = Self::resolve(&env, asset);
+        Self::check_not_sunset(&env, &asset)?;
+        let record = storage::get_prices(&env, &asset)
+            .last()
+            .ok_or(Error::AssetNotFound)?;
+        let quote = Self::resolve(&env, quote);
+        if record.quote == quote {
+            return Ok(record);
+        }
+        let state = storage::get_state(&env);
+        let from_rate = Self::base_rate(&env, &state, &record.quote)?;
+        let to_rate = Self::base_rate(&env, &state, &quote)?;
+        Ok(PriceData {
+            price: record.price * from_rate / to_rate,
+            timestamp: record.timestamp,
+            quote,
+        })
+    }
+
+    /// Re-expresses an asset's latest price in `alternate_base`, deriving
+    /// the conversion through stored feeds (e.g. a USD-based TREASURY
+    /// feed priced in XLM), so consumers collateralizing in a different
+    /// currency don't need a second oracle. The alternate base must be
+    /// the configured base or carry a base-quoted feed.
+    pub fn price_in_base(env: Env, asset: Asset, alternate_base: Asset) -> Result<PriceData, Error> {
+        Self::lastprice_in(env, asset, alternate_base)
+    }
+
+    /// Returns the record with exactly the given timestamp, if stored.
+    pub fn price(env: Env, asset: Asset, timestamp: u64) -> Option<PriceData> {
+        let asset = Self::resolve(&env, asset);
+        if Self::is_sunset(&env, &asset) {
+            return None;
+        }
+        storage::get_prices(&env, &asset)
+            .iter()
+            .find(|p| p.timestamp == timestamp)
+    }
+
+    /// Returns the latest record not newer than `timestamp`, for
+    /// consumers that know a ledger close time rather than the exact
+    /// feeder timestamp.
+    pub fn price_at_or_before(env: Env, asset: Asset, timestamp: u64) -> Option<PriceData> {
+        let asset = Self::resolve(&env, asset);
+        if Self::is_sunset(&env, &asset) {
+            return None;
+        }
+        storage::get_prices(&env, &asset)
+            .iter()
+            .rev()
+            .find(|p| p.timestamp <= timestamp)
+    }
+
+    /// Returns up to `records` most recent price records, newest last.
+    pub fn prices(env: Env, asset: Asset, records: u32) -> Option<Vec<PriceData>> {
+        let asset = Self::resolve(&env, asset);
+        if Self::is_sunset(&env, &asset) {
+            return None;
+        }
+        let all = storage::get_prices(&env, &asset);
+        if all.is_empty() {
+            return None;
+        }
+        let skip = all.len().saturating_sub(records.min(MAX_PRICE_HISTORY));
+        let mut out = Vec::new(&env);
+        for i in skip..all.len() {
+            out.push_back(all.get_unchecked(i));
+        }
+        Some(out)
+    }
+
+    /// Latest price plus coupon interest accrued since the last coupon
+    /// date, so bond values grow smoothly between clean-price updates.
+    /// Coupon dates are anchored backwards from the metadata's maturity;
+    /// accrual runs on the clean price as a face-value proxy. Assets
+    /// without coupon metadata (or past maturity) return the clean price.
+    pub fn dirty_price(env: Env, asset: Asset) -> Result<PriceData, Error> {
+        let asset = Self::resolve(&env, asset);
+        Self::check_not_sunset(&env, &asset)?;
+        let mut record = storage::get_prices(&env, &asset)
+            .last()
+            .ok_or(Error::AssetNotFound)?;
+        let meta = match storage::get_metadata(&env, &asset) {
+            Some(meta) => meta,
+            None => return Ok(record),
+        };
+        // A frequency above once per second truncates the coupon
+        // period to zero; treat it like missing coupon metadata rather
+        // than dividing by it below.
+        if meta.asset_type != AssetType::Bond
+            || meta.coupon_rate_bps == 0
+            || meta.coupon_frequency == 0
+            || meta.coupon_frequency as u64 > SECONDS_PER_YEAR
+        {
+            return Ok(record);
+        }
+        let now = env.ledger().timestamp();
+        if now >= meta.maturity {
+            return Ok(record);
+        }
+        let period = SECONDS_PER_YEAR / meta.coupon_frequency as u64;
+        let to_maturity = meta.maturity - now;
+        let last_coupon = meta.maturity - (to_maturity / period + 1) * period;
+        let accrued_time = now.saturating_sub(last_coupon);
+        record.price += record.price * meta.coupon_rate_bps as i128 * accrued_time as i128
+            / (10_000 * SECONDS_PER_YEAR as i128);
+        Ok(record)
+    }
+
+    /// Standard deviation of record-over-record returns across the most
+    /// recent `window` price records, scaled to the oracle's decimals
+    /// (so `0_0100000` at 7 decimals is a 1% deviation). Returns `None`
+    /// until at least two records exist. Consumers use this to drive
+    /// risk parameters, e.g. a dynamic collateralization floor.
+    pub fn volatility(env: Env, asset: Asset, window: u32) -> Option<i128> {
+        let records = Self::prices(env.clone(), asset, window)?;
+        if records.len() < 2 {
+            return None;
+        }
+        let scale = 10i128.pow(storage::get_state(&env).decimals);
+        let n = (records.len() - 1) as i128;
+        let mut returns_sum = 0i128;
+        for i in 1..records.len() {
+            let prev = records.get_unchecked(i - 1).price;
+            let cur = records.get_unchecked(i).price;
+            returns_sum += (cur - prev) * scale / prev;
+        }
+        let mean = returns_sum / n;
+        let mut variance_sum = 0i128;
+        for i in 1..records.len() {
+            let prev = records.get_unchecked(i - 1).price;
+            let cur = records.get_unchecked(i).price;
+            let diff = (cur - prev) * scale / prev - mean;
+            variance_sum += diff * diff;
+        }
+        Some(isqrt(variance_sum / n))
+    }
+
+    // --- Feed management ------------------------------------------------
+
+    pub fn add_assets(env: Env, assets: Vec<Asset>) -> Result<(), Error> {
+        Self::require_admin(&env);
+        for asset in assets.iter() {
+            if storage::has_asset(&env, &asset) {
+                return Err(Error::AssetAlreadyExists);
+            }
+            storage::push_asset(&env, &asset);
+            AssetAdded { asset }.publish(&env);
+        }
+        Ok(())
+    }
+
+    /// Schedules an asset's wind-down: updates and queries keep working
+    /// until `sunset`, after which both fail with `AssetDeprecated` and
+    /// `replacement_of` points consumers at the successor feed. Owner
+    /// only. Matured bonds and redeemed tokens retire this way rather
+    /// than by a silent stop of updates.
+    pub fn deprecate_asset(
+        env: Env,
+        asset: Asset,
+        sunset: u64,
+        replacement: Option<Asset>,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env);
+        let asset = Self::resolve(&env, asset);
+        if !storage::has_asset(&env, &asset) {
+            return Err(Error::AssetNotFound);
+        }
+        if let Some(replacement) = replacement {
+            if replacement == asset || !storage::has_asset(&env, &replacement) {
+                return Err(Error::AssetNotFound);
+            }
+            storage::set_replacement(&env, &asset, &replacement);
+        }
+        storage::set_sunset(&env, &asset, sunset);
+        AssetRemoved { asset }.publish(&env);
+        Ok(())
+    }
+
+    /// The sunset timestamp of a deprecated asset, if any.
+    pub fn sunset_of(env: Env, asset: Asset) -> Option<u64> {
+        let asset = Self::resolve(&env, asset);
+        storage::get_sunset(&env, &asset)
+    }
+
+    /// The successor feed of a deprecated asset. Consumers hitting
+    /// `AssetDeprecated` look up where to migrate here.
+    pub fn replacement_of(env: Env, asset: Asset) -> Option<Asset> {
+        let asset = Self::resolve(&env, asset);
+        storage::get_replacement(&env, &asset)
+    }
+
+    /// Stores a price quoted in the oracle's base asset. `feeder` must
+    /// hold the PriceFeeder role (or be the owner).
+    pub fn set_asset_price(
+        env: Env,
+        feeder: Address,
+        asset: Asset,
+        price: i128,
+        timestamp: u64,
+    ) -> Result<(), Error> {
+        let base = storage::get_state(&env).base;
+        Self::set_asset_price_in(env, feeder, asset, price, timestamp, base)
+    }
+
+    /// Stores a price quoted in an explicit quote asset, which must be the
+    /// base or another registered asset (so conversion stays possible).
+    pub fn set_asset_price_in(
+        env: Env,
+        feeder: Address,
+        asset: Asset,
+        price: i128,
+        timestamp: u64,
+        quote: Asset,
+    ) -> Result<(), Error> {
+        Self::require_role(&env, &feeder, &Role::PriceFeeder)?;
+        if price <= 0 {
+            return Err(Error::InvalidPrice);
+        }
+        // Feeders may keep pushing under an aliased name after a rename.
+        let asset = Self::resolve(&env, asset);
+        let quote = Self::resolve(&env, quote);
+        if !storage::has_asset(&env, &asset) {
+            return Err(Error::AssetNotFound);
+        }
+        Self::check_not_sunset(&env, &asset)?;
+        let state = storage::get_state(&env);
+        if quote != state.base && !storage::has_asset(&env, &quote) {
+            return Err(Error::QuoteUnavailable);
+        }
+        let mut prices = storage::get_prices(&env, &asset);
+        if let Some(last) = prices.last() {
+            if timestamp <= last.timestamp {
+                return Err(Error::InvalidTimestamp);
+            }
+            // Throttle to the configured resolution so rapid tiny
+            // updates cannot bloat the stored history.
+            if timestamp < last.timestamp + state.resolution as u64 {
+                return Err(Error::UpdateTooFrequent);
+            }
+        }
+        prices.push_back(PriceData {
+            price,
+            timestamp,
+            quote,
+        });
+        storage::set_prices(&env, &asset, &prices);
+
+        let mut state = storage::get_state(&env);
+        if timestamp > state.last_timestamp {
+            state.last_timestamp = timestamp;
+            storage::set_state(&env, &state);
+        }
+        PriceUpdated {
+            asset: asset.clone(),
+            price,
+            timestamp,
+        }
+        .publish(&env);
+        Self::push_price_update(&env, &asset, price, timestamp);
+        Ok(())
+    }
+
+    /// Proposes or confirms an emergency price override. The first call
+    /// from an EmergencySigner records a proposal; a later call from a
+    /// *different* signer naming the same price applies it immediately,
+    /// bypassing the update throttle. A call naming a different price
+    /// supersedes the pending proposal instead, so no single key can pin
+    /// a price unilaterally. Returns whether the override took effect.
+    pub fn emergency_set_price(
+        env: Env,
+        signer: Address,
+        asset: Asset,
+        price: i128,
+    ) -> Result<bool, Error> {
+        Self::require_role(&env, &signer, &Role::EmergencySigner)?;
+        if price <= 0 {
+            return Err(Error::InvalidPrice);
+        }
+        let asset = Self::resolve(&env, asset);
+        if !storage::has_asset(&env, &asset) {
+            return Err(Error::AssetNotFound);
+        }
+        Self::check_not_sunset(&env, &asset)?;
+        match storage::get_emergency_proposal(&env, &asset) {
+            Some(proposal) if proposal.proposer != signer && proposal.price == price => {
+                storage::remove_emergency_proposal(&env, &asset);
+                let mut prices = storage::get_prices(&env, &asset);
+                // The override must become the newest record even when
+                // the feed carries future-dated timestamps.
+                let timestamp = match prices.last() {
+                    Some(last) => env.ledger().timestamp().max(last.timestamp + 1),
+                    None => env.ledger().timestamp(),
+                };
+                let mut state = storage::get_state(&env);
+                prices.push_back(PriceData {
+                    price,
+                    timestamp,
+                    quote: state.base.clone(),
+                });
+                storage::set_prices(&env, &asset, &prices);
+                if timestamp > state.last_timestamp {
+                    state.last_timestamp = timestamp;
+                    storage::set_state(&env, &state);
+                }
+                PriceUpdated {
+                    asset: asset.clone(),
+                    price,
+                    timestamp,
+                }
+                .publish(&env);
+                Self::push_price_update(&env, &asset, price, timestamp);
+                Ok(true)
+            }
+            _ => {
+                storage::set_emergency_proposal(
+                    &env,
+                    &asset,
+                    &storage::EmergencyProposal {
+                        proposer: signer.clone(),
+                        price,
+                    },
+                );
+                EmergencyPriceProposed {
+                    asset,
+                    proposer: signer,
+                    price,
+                }
+                .publish(&env);
+                Ok(false)
+            }
+        }
+    }
+
+    /// Stores a two-way quote, recording the mid as the asset's price so
+    /// `lastprice` and every derived view serve it transparently. The raw
+    /// sides stay queryable via `bid_ask` and `spread` for consumers that
+    /// weigh thin markets differently. Base-quoted; same role and
+    /// throttling rules as `set_asset_price`.
+    pub fn set_quote(
+        env: Env,
+        feeder: Address,
+        asset: Asset,
+        bid: i128,
+        ask: i128,
+        timestamp: u64,
+    ) -> Result<(), Error> {
+        if bid <= 0 || ask < bid {
+            return Err(Error::InvalidPrice);
+        }
+        let mid = (bid + ask) / 2;
+        let resolved = Self::resolve(&env, asset.clone());
+        let base = storage::get_state(&env).base;
+        Self::set_asset_price_in(env.clone(), feeder, asset, mid, timestamp, base)?;
+        storage::set_bid_ask(&env, &resolved, &BidAsk { bid, ask, timestamp });
+        Ok(())
+    }
+
+    /// Raw sides of the most recent quote, if the latest record for the
+    /// asset came from `set_quote` rather than an outright price.
+    pub fn bid_ask(env: Env, asset: Asset) -> Option<BidAsk> {
+        let asset = Self::resolve(&env, asset);
+        if Self::is_sunset(&env, &asset) {
+            return None;
+        }
+        let quote = storage::get_bid_ask(&env, &asset)?;
+        let last = storage::get_prices(&env, &asset).last()?;
+        if last.timestamp != quote.timestamp {
+            return None;
+        }
+        Some(quote)
+    }
+
+    /// Bid/ask spread of the most recent quote, in basis points of the
+    /// mid. `None` when the latest record was an outright price.
+    pub fn spread(env: Env, asset: Asset) -> Option<u32> {
+        let quote = Self::bid_ask(env, asset)?;
+        let mid = (quote.bid + quote.ask) / 2;
+        Some(((quote.ask - quote.bid) * 10_000 / mid) as u32)
+    }
+
+    /// Earliest timestamp the next record for `asset` may carry; zero
+    /// when the feed has no history yet.
+    pub fn next_allowed_update(env: Env, asset: Asset) -> u64 {
+        let asset = Self::resolve(&env, asset);
+        match storage::get_prices(&env, &asset).last() {
+            Some(last) => last.timestamp + storage::get_state(&env).resolution as u64,
+            None => 0,
+        }
+    }
+
+    // --- Subscriptions --------------------------------------------------
+
+    /// Enrolls a consumer contract to receive an
+    /// `on_price_update(asset, price, timestamp)` invocation whenever a
+    /// new record lands for `asset`. The consumer must authorize its own
+    /// enrollment. Idempotent for an already subscribed consumer.
+    pub fn subscribe(env: Env, consumer: Address, asset: Asset) -> Result<(), Error> {
+        consumer.require_auth();
+        let asset = Self::resolve(&env, asset);
+        if !storage::has_asset(&env, &asset) {
+            return Err(Error::AssetNotFound);
+        }
+        let mut subscribers = storage::get_subscribers(&env, &asset);
+        if subscribers.contains(&consumer) {
+            return Ok(());
+        }
+        if subscribers.len() >= storage::MAX_SUBSCRIBERS {
+            return Err(Error::TooManySubscribers);
+        }
+        subscribers.push_back(consumer);
+        storage::set_subscribers(&env, &asset, &subscribers);
+        Ok(())
+    }
+
+    /// Drops a consumer from an asset's subscription list.
+    pub fn unsubscribe(env: Env, consumer: Address, asset: Asset) {
+        consumer.require_auth();
+        let asset = Self::resolve(&env, asset);
+        let mut subscribers = storage::get_subscribers(&env, &asset);
+        if let Some(index) = subscribers.first_index_of(&consumer) {
+            subscribers.remove(index);
+            storage::set_subscribers(&env, &asset, &subscribers);
+        }
+    }
+
+    pub fn subscribers(env: Env, asset: Asset) -> Vec<Address> {
+        let asset = Self::resolve(&env, asset);
+        storage::get_subscribers(&env, &asset)
+    }
+
+    /// Delivers the next batch of pending subscriber callbacks for
+    /// `asset` after a price write notified only the first
+    /// [`MAX_NOTIFY_PER_CALL`]. Permissionless, so keepers can drain
+    /// large lists. Returns the number of subscribers still waiting.
+    pub fn notify_subscribers(env: Env, asset: Asset) -> u32 {
+        let asset = Self::resolve(&env, asset);
+        let Some(cursor) = storage::get_notify_cursor(&env, &asset) else {
+            return 0;
+        };
+        let subscribers = storage::get_subscribers(&env, &asset);
+        let next = Self::notify_range(
+            &env,
+            &asset,
+            &subscribers,
+            cursor.next,
+            cursor.price,
+            cursor.timestamp,
+        );
+        if next < subscribers.len() {
+            storage::set_notify_cursor(&env, &asset, &storage::NotifyCursor { next, ..cursor });
+            subscribers.len() - next
+        } else {
+            storage::remove_notify_cursor(&env, &asset);
+            0
+        }
+    }
+
+    // --- Aliases --------------------------------------------------------
+
+    /// Maps `alias` to a registered canonical asset so the same feed can
+    /// be queried under several names (tickers, vintages, or a Stellar
+    /// token address). Owner only. One level deep: an alias must not
+    /// shadow a registered asset, and cannot point at another alias.
+    pub fn set_alias(env: Env, alias: Asset, canonical: Asset) -> Result<(), Error> {
+        Self::require_admin(&env);
+        if storage::has_asset(&env, &alias) {
+            return Err(Error::AssetAlreadyExists);
+        }
+        if !storage::has_asset(&env, &canonical) {
+            return Err(Error::AssetNotFound);
+        }
+        storage::set_alias(&env, &alias, &canonical);
+        AliasSet { alias, canonical }.publish(&env);
+        Ok(())
+    }
+
+    /// Removes an alias. Owner only.
+    pub fn remove_alias(env: Env, alias: Asset) {
+        Self::require_admin(&env);
+        storage::remove_alias(&env, &alias);
+        AliasRemoved { alias }.publish(&env);
+    }
+
+    /// The canonical asset an alias resolves to, if any.
+    pub fn alias_of(env: Env, alias: Asset) -> Option<Asset> {
+        storage::get_alias(&env, &alias)
+    }
+
+    // --- TTL maintenance ------------------------------------------------
+
+    /// Extends the TTL of one asset's price history so the feed cannot
+    /// silently archive. Callable by anyone.
+    pub fn bump_asset_ttl(env: Env, asset: Asset) -> Result<(), Error> {
+        if !storage::bump_prices(&env, &Self::resolve(&env, asset)) {
+            return Err(Error::AssetNotFound);
+        }
+        Ok(())
+    }
+
+    /// Extends the TTL of several feeds (and the contract instance) in
+    /// one call. Unknown assets are skipped so keepers can pass a fixed
+    /// list. Callable by anyone.
+    pub fn bump_all(env: Env, assets: Vec<Asset>) {
+        for asset in assets.iter() {
+            storage::bump_prices(&env, &Self::resolve(&env, asset));
+        }
+        env.storage().instance().extend_ttl(
+            storage::PERSISTENT_LIFETIME_THRESHOLD,
+            storage::PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
+    // --- RWA metadata registry ------------------------------------------
+
+    /// Creates or replaces an RWA metadata record. `manager` must hold
+    /// the MetadataManager role (or be the owner).
+    pub fn set_rwa_metadata(env: Env, manager: Address, metadata: RWAMetadata) -> Result<(), Error> {
+        Self::require_role(&env, &manager, &Role::MetadataManager)?;
+        storage::set_metadata(&env, &metadata);
+        RwaMetadataSet {
+            asset: metadata.asset,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn get_rwa_metadata(env: Env, asset: Asset) -> Option<RWAMetadata> {
+        storage::get_metadata(&env, &asset)
+    }
+
+    /// Downgrades a record to Suspended once its license expiry has
+    /// passed. Permissionless, so anyone can surface a lapse the
+    /// operator has not yet attested; Suspended and Revoked records are
+    /// left untouched, and a fresh attestation via `set_rwa_metadata`
+    /// restores standing. Returns the record's current status.
+    pub fn refresh_compliance(env: Env, asset: Asset) -> Result<ComplianceStatus, Error> {
+        let mut metadata = storage::get_metadata(&env, &asset).ok_or(Error::MetadataNotFound)?;
+        let expiry = match metadata.regulatory_info.license_expiry {
+            Some(expiry) if expiry <= env.ledger().timestamp() => expiry,
+            _ => return Ok(metadata.regulatory_info.compliance_status),
+        };
+        let old = metadata.regulatory_info.compliance_status.clone();
+        if old == ComplianceStatus::Suspended || old == ComplianceStatus::Revoked {
+            return Ok(old);
+        }
+        metadata.regulatory_info.compliance_status = ComplianceStatus::Suspended;
+        storage::set_metadata(&env, &metadata);
+        ComplianceDowngraded {
+            asset: metadata.asset,
+            old_status: old,
+            new_status: ComplianceStatus::Suspended,
+            license_expiry: expiry,
+        }
+        .publish(&env);
+        Ok(ComplianceStatus::Suspended)
+    }
+
+    /// Inserts or replaces one entry of an asset's free-form metadata
+    /// map, so large records (prospectus links, CUSIPs, custodian IDs)
+    /// can be amended without resubmitting the whole record. `manager`
+    /// must hold the MetadataManager role (or be the owner).
+    pub fn set_metadata_entry(
+        env: Env,
+        manager: Address,
+        asset: Asset,
+        key: Symbol,
+        value: String,
+    ) -> Result<(), Error> {
+        Self::require_role(&env, &manager, &Role::MetadataManager)?;
+        let mut metadata = storage::get_metadata(&env, &asset).ok_or(Error::MetadataNotFound)?;
+        match Self::metadata_entry_index(&metadata, &key) {
+            Some(index) => metadata.metadata.set(index, (key, value)),
+            None => metadata.metadata.push_back((key, value)),
+        }
+        storage::set_metadata(&env, &metadata);
+        RwaMetadataSet {
+            asset: metadata.asset,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Removes one entry of an asset's free-form metadata map; absent
+    /// keys are a no-op. Same role requirements as `set_metadata_entry`.
+    pub fn remove_metadata_entry(
+        env: Env,
+        manager: Address,
+        asset: Asset,
+        key: Symbol,
+    ) -> Result<(), Error> {
+        Self::require_role(&env, &manager, &Role::MetadataManager)?;
+        let mut metadata = storage::get_metadata(&env, &asset).ok_or(Error::MetadataNotFound)?;
+        if let Some(index) = Self::metadata_entry_index(&metadata, &key) {
+            metadata.metadata.remove(index);
+            storage::set_metadata(&env, &metadata);
+            RwaMetadataSet {
+                asset: metadata.asset,
+            }
+            .publish(&env);
+        }
+        Ok(())
+    }
+
+    /// Returns every registered RWA asset. Prefer `get_rwa_assets_page`
+    /// once the registry grows; this walks the full index.
+    pub fn get_all_rwa_assets(env: Env) -> Vec<Asset> {
+        Self::get_rwa_assets_page(env, 0, u32::MAX)
+    }
+
+    /// Returns a page of registered RWA assets, ordered by registration.
+    /// `limit` is clamped to [`MAX_PAGE_SIZE`] except when the full-listing
+    /// compatibility path requests everything.
+    pub fn get_rwa_assets_page(env: Env, offset: u32, limit: u32) -> Vec<Asset> {
+        let count = storage::meta_count(&env);
+        let limit = if limit == u32::MAX {
+            count
+        } else {
+            limit.min(MAX_PAGE_SIZE)
+        };
+        let end = offset.saturating_add(limit).min(count);
+        let mut out = Vec::new(&env);
+        for i in offset..end {
+            if let Some(asset) = storage::meta_asset_at(&env, i) {
+                out.push_back(asset);
+            }
+        }
+        out
+    }
+
+    /// Returns a page of full metadata records, ordered by registration.
+    pub fn get_rwa_metadata_page(env: Env, offset: u32, limit: u32) -> Vec<RWAMetadata> {
+        let count = storage::meta_count(&env);
+        let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+        let mut out = Vec::new(&env);
+        for i in offset..end {
+            if let Some(asset) = storage::meta_asset_at(&env, i) {
+                if let Some(meta) = storage::get_metadata(&env, &asset) {
+                    out.push_back(meta);
+                }
+            }
+        }
+        out
+    }
+
+    /// Number of registered RWA metadata records, for paging clients.
+    pub fn rwa_asset_count(env: Env) -> u32 {
+        storage::meta_count(&env)
+    }
+
+    // --- Administration -------------------------------------------------
+
+    pub fn admin(env: Env) -> Address {
+        storage::get_admin(&env)
+    }
+
+    /// Grants a role. Owner only.
+    pub fn grant_role(env: Env, role: Role, who: Address) {
+        Self::require_admin(&env);
+        storage::set_role(&env, &role, &who, true);
+        RoleGranted { role, address: who }.publish(&env);
+    }
+
+    /// Revokes a role. Owner only.
+    pub fn revoke_role(env: Env, role: Role, who: Address) {
+        Self::require_admin(&env);
+        storage::set_role(&env, &role, &who, false);
+        RoleRevoked { role, address: who }.publish(&env);
+    }
+
+    pub fn has_role(env: Env, role: Role, who: Address) -> bool {
+        storage::has_role(&env, &role, &who)
+    }
+
+    pub fn set_admin(env: Env, new_admin: Address) {
+        Self::require_admin(&env);
+        storage::set_admin(&env, &new_admin);
+    }
+
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        Self::require_admin(&env);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    pub fn version(_env: Env) -> u32 {
+        CONTRACT_VERSION
+    }
+
+    /// Build provenance baked in at compile time: `(crate_version,
+    /// git_sha, build_timestamp, soroban_sdk_version)`. The last three
+    /// come from the `GIT_SHA`, `BUILD_TIMESTAMP`, and
+    /// `SOROBAN_SDK_VERSION` env vars the release pipeline sets, and
+    /// read "unknown" in local builds.
+    pub fn build_info(env: Env) -> (String, String, String, String) {
+        (
+            String::from_str(&env, env!("CARGO_PKG_VERSION")),
+            String::from_str(&env, option_env!("GIT_SHA").unwrap_or("unknown")),
+            String::from_str(&env, option_env!("BUILD_TIMESTAMP").unwrap_or("unknown")),
+            String::from_str(&env, option_env!("SOROBAN_SDK_VERSION").unwrap_or("unknown")),
+        )
+    }
+
+    /// Brings the storage schema up to [`CONTRACT_VERSION`]. Invoke once
+    /// after `upgrade()`; without this, a layout change to
+    /// [`RWAOracleStorage`] would leave `get_state` unable to decode the
+    /// stored value. Idempotent, so re-invoking is harmless.
+    pub fn migrate(env: Env) {
+        Self::require_admin(&env);
+        let mut from = storage::schema_version(&env);
+        while from < CONTRACT_VERSION {
+            // One arm per schema step: read the version-`from` layout,
+            // rewrite it in the `from + 1` shape. Nothing to do yet at v1.
+            from += 1;
+        }
+        storage::set_schema_version(&env, CONTRACT_VERSION);
+    }
+
+    /// Value of one unit of `asset` in the base, in oracle decimals. The
+    /// base itself is the identity; anything else needs a base-quoted feed.
+    fn base_rate(env: &Env, state: &RWAOracleStorage, asset: &Asset) -> Result<i128, Error> {
+        if *asset == state.base {
+            return Ok(10i128.pow(state.decimals));
+        }
+        match storage::get_prices(env, asset).last() {
+            Some(record) if record.quote == state.base => Ok(record.price),
+            _ => Err(Error::QuoteUnavailable),
+        }
+    }
+
+    /// Delivers a fresh record to the first batch of subscribers and
+    /// leaves a cursor behind for `notify_subscribers` to drain the rest.
+    fn push_price_update(env: &Env, asset: &Asset, price: i128, timestamp: u64) {
+        let subscribers = storage::get_subscribers(env, asset);
+        if subscribers.is_empty() {
+            return;
+        }
+        let next = Self::notify_range(env, asset, &subscribers, 0, price, timestamp);
+        if next < subscribers.len() {
+            storage::set_notify_cursor(
+                env,
+                asset,
+                &storage::NotifyCursor {
+                    next,
+                    price,
+                    timestamp,
+                },
+            );
+        } else {
+            storage::remove_notify_cursor(env, asset);
+        }
+    }
+
+    /// Invokes `on_price_update` on up to [`MAX_NOTIFY_PER_CALL`]
+    /// subscribers starting at `start`, returning the index after the
+    /// last one attempted. Failing consumers are skipped so one broken
+    /// contract cannot block the feed.
+    fn notify_range(
+        env: &Env,
+        asset: &Asset,
+        subscribers: &Vec<Address>,
+        start: u32,
+        price: i128,
+        timestamp: u64,
+    ) -> u32 {
+        let end = start.saturating_add(MAX_NOTIFY_PER_CALL).min(subscribers.len());
+        let function = Symbol::new(env, "on_price_update");
+        for i in start..end {
+            let consumer = subscribers.get_unchecked(i);
+            let args = (asset.clone(), price, timestamp).into_val(env);
+            let _ = env.try_invoke_contract::<Val, Val>(&consumer, &function, args);
+        }
+        end
+    }
+
+    /// Position of `key` in a metadata record's free-form map, if set.
+    fn metadata_entry_index(metadata: &RWAMetadata, key: &Symbol) -> Option<u32> {
+        (0..metadata.metadata.len()).find(|&i| metadata.metadata.get_unchecked(i).0 == *key)
+    }
+
+    /// Whether `asset` (already canonical) has passed its sunset.
+    fn is_sunset(env: &Env, asset: &Asset) -> bool {
+        matches!(
+            storage::get_sunset(env, asset),
+            Some(sunset) if env.ledger().timestamp() >= sunset
+        )
+    }
+
+    fn check_not_sunset(env: &Env, asset: &Asset) -> Result<(), Error> {
+        if Self::is_sunset(env, asset) {
+            return Err(Error::AssetDeprecated);
+        }
+        Ok(())
+    }
+
+    /// Follows the alias table, returning the canonical asset for aliases
+    /// and the input unchanged otherwise.
+    fn resolve(env: &Env, asset: Asset) -> Asset {
+        storage::get_alias(env, &asset).unwrap_or(asset)
+    }
+
+    fn require_admin(env: &Env) -> Address {
+        let admin = storage::get_admin(env);
+        admin.require_auth();
+        admin
+    }
+
+    /// Authenticates `who` and checks they hold `role`. The owner passes
+    /// every role check.
+    fn require_role(env: &Env, who: &Address, role: &Role) -> Result<(), Error> {
+        who.require_auth();
+        if *who == storage::get_admin(env) || storage::has_role(env, role, who) {
+            Ok(())
+        } else {
+            Err(Error::Unauthorized)
+        }
+    }
+}
+
+/// Integer square root (Newton's method), rounding down.
+fn isqrt(value: i128) -> i128 {
+    if value <= 0 {
+        return 0;
+    }
+    let mut guess = value;
+    let mut next = (guess + 1) / 2;
+    while next < guess {
+        guess = next;
+        next = (guess + value / guess) / 2;
+    }
+    guess
+}
@@ -0,0 +1,363 @@
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
+
+use crate::types::{Asset, BidAsk, PriceData, RWAMetadata, Role};
+
+/// Instance key holding the admin address.
+pub(crate) const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+
+/// Maximum price records retained per asset; older records are dropped.
+pub(crate) const MAX_PRICE_HISTORY: u32 = 100;
+
+/// Maximum consumer contracts that may subscribe to one asset's feed.
+pub(crate) const MAX_SUBSCRIBERS: u32 = 50;
+
+pub(crate) const DAY_IN_LEDGERS: u32 = 17_280;
+
+/// Target TTL for persistent entries when written or bumped.
+pub(crate) const PERSISTENT_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+
+/// Entries are only re-extended once their TTL dips below this.
+pub(crate) const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+/// Instance-level oracle configuration and bookkeeping.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RWAOracleStorage {
+    pub base: Asset,
+    pub decimals: u32,
+    pub resolution: u32,
+    pub last_timestamp: u64,
+}
+
+/// Storage keys. Assets and metadata records are held behind per-entry
+/// persistent keys with `u32` position indexes so listings can be served
+/// page by page instead of materializing one large map.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    State,
+    /// Persistent: price history for an asset, newest record last.
+    Prices(Asset),
+    /// Persistent: asset registered at this index position.
+    AssetIndex(u32),
+    /// Instance: number of registered price assets.
+    AssetCount,
+    /// Persistent: metadata record for an RWA asset.
+    Metadata(Asset),
+    /// Persistent: RWA asset registered at this index position.
+    MetaIndex(u32),
+    /// Instance: number of registered RWA metadata records.
+    MetaCount,
+    /// Persistent: marker that an address holds a role.
+    Role(Role, Address),
+    /// Persistent: alias asset resolving to a canonical registered asset.
+    Alias(Asset),
+    /// Instance: storage schema version, advanced by `migrate`.
+    SchemaVersion,
+    /// Persistent: raw bid/ask behind the latest quote for an asset.
+    BidAsk(Asset),
+    /// Persistent: consumer contracts subscribed to an asset's updates.
+    Subscribers(Asset),
+    /// Persistent: progress of partially delivered subscriber callbacks.
+    NotifyCursor(Asset),
+    /// Persistent: sunset timestamp of a deprecated asset.
+    Sunset(Asset),
+    /// Persistent: successor feed of a deprecated asset.
+    Replacement(Asset),
+    /// Persistent: emergency price override awaiting a second signer.
+    EmergencyProposal(Asset),
+}
+
+/// Where delivery of a price update to subscribers stopped, plus the
+/// record being delivered, so `notify_subscribers` can resume.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NotifyCursor {
+    pub next: u32,
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// An emergency price override proposed by one signer, pending
+/// confirmation by a different one.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmergencyProposal {
+    pub proposer: Address,
+    pub price: i128,
+}
+
+pub(crate) fn get_state(env: &Env) -> RWAOracleStorage {
+    env.storage().instance().get(&DataKey::State).unwrap()
+}
+
+pub(crate) fn set_state(env: &Env, state: &RWAOracleStorage) {
+    env.storage().instance().set(&DataKey::State, state);
+}
+
+/// Contracts deployed before versioning have no entry; they are schema 1.
+pub(crate) fn schema_version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::SchemaVersion)
+        .unwrap_or(1)
+}
+
+pub(crate) fn set_schema_version(env: &Env, version: u32) {
+    env.storage()
+        .instance()
+        .set(&DataKey::SchemaVersion, &version);
+}
+
+pub(crate) fn get_admin(env: &Env) -> Address {
+    env.storage().instance().get(&ADMIN_KEY).unwrap()
+}
+
+pub(crate) fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&ADMIN_KEY, admin);
+}
+
+pub(crate) fn get_prices(env: &Env, asset: &Asset) -> Vec<PriceData> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Prices(asset.clone()))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+pub(crate) fn set_prices(env: &Env, asset: &Asset, prices: &Vec<PriceData>) {
+    let mut prices = prices.clone();
+    while prices.len() > MAX_PRICE_HISTORY {
+        prices.remove(0);
+    }
+    let key = DataKey::Prices(asset.clone());
+    env.storage().persistent().set(&key, &prices);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+/// Extends the TTL of an asset's price history, if it has one. Returns
+/// whether an entry existed to bump.
+pub(crate) fn bump_prices(env: &Env, asset: &Asset) -> bool {
+    let key = DataKey::Prices(asset.clone());
+    if !env.storage().persistent().has(&key) {
+        return false;
+    }
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+    true
+}
+
+pub(crate) fn get_bid_ask(env: &Env, asset: &Asset) -> Option<BidAsk> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::BidAsk(asset.clone()))
+}
+
+pub(crate) fn set_bid_ask(env: &Env, asset: &Asset, quote: &BidAsk) {
+    let key = DataKey::BidAsk(asset.clone());
+    env.storage().persistent().set(&key, quote);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+pub(crate) fn asset_count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::AssetCount)
+        .unwrap_or(0)
+}
+
+pub(crate) fn asset_at(env: &Env, index: u32) -> Option<Asset> {
+    env.storage().persistent().get(&DataKey::AssetIndex(index))
+}
+
+pub(crate) fn push_asset(env: &Env, asset: &Asset) {
+    let count = asset_count(env);
+    env.storage()
+        .persistent()
+        .set(&DataKey::AssetIndex(count), asset);
+    env.storage()
+        .instance()
+        .set(&DataKey::AssetCount, &(count + 1));
+}
+
+pub(crate) fn has_asset(env: &Env, asset: &Asset) -> bool {
+    let count = asset_count(env);
+    for i in 0..count {
+        if asset_at(env, i).as_ref() == Some(asset) {
+            return true;
+        }
+    }
+    false
+}
+
+pub(crate) fn get_metadata(env: &Env, asset: &Asset) -> Option<RWAMetadata> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Metadata(asset.clone()))
+}
+
+pub(crate) fn set_metadata(env: &Env, metadata: &RWAMetadata) {
+    let key = DataKey::Metadata(metadata.asset.clone());
+    if !env.storage().persistent().has(&key) {
+        let count = meta_count(env);
+        env.storage()
+            .persistent()
+            .set(&DataKey::MetaIndex(count), &metadata.asset);
+        env.storage()
+            .instance()
+            .set(&DataKey::MetaCount, &(count + 1));
+    }
+    env.storage().persistent().set(&key, metadata);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+pub(crate) fn meta_count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::MetaCount)
+        .unwrap_or(0)
+}
+
+pub(crate) fn meta_asset_at(env: &Env, index: u32) -> Option<Asset> {
+    env.storage().persistent().get(&DataKey::MetaIndex(index))
+}
+
+pub(crate) fn get_alias(env: &Env, alias: &Asset) -> Option<Asset> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Alias(alias.clone()))
+}
+
+pub(crate) fn set_alias(env: &Env, alias: &Asset, canonical: &Asset) {
+    let key = DataKey::Alias(alias.clone());
+    env.storage().persistent().set(&key, canonical);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+pub(crate) fn remove_alias(env: &Env, alias: &Asset) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Alias(alias.clone()));
+}
+
+pub(crate) fn get_subscribers(env: &Env, asset: &Asset) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Subscribers(asset.clone()))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+pub(crate) fn set_subscribers(env: &Env, asset: &Asset, subscribers: &Vec<Address>) {
+    let key = DataKey::Subscribers(asset.clone());
+    env.storage().persistent().set(&key, subscribers);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+pub(crate) fn get_notify_cursor(env: &Env, asset: &Asset) -> Option<NotifyCursor> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::NotifyCursor(asset.clone()))
+}
+
+pub(crate) fn set_notify_cursor(env: &Env, asset: &Asset, cursor: &NotifyCursor) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::NotifyCursor(asset.clone()), cursor);
+}
+
+pub(crate) fn remove_notify_cursor(env: &Env, asset: &Asset) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::NotifyCursor(asset.clone()));
+}
+
+pub(crate) fn get_sunset(env: &Env, asset: &Asset) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Sunset(asset.clone()))
+}
+
+pub(crate) fn set_sunset(env: &Env, asset: &Asset, sunset: u64) {
+    let key = DataKey::Sunset(asset.clone());
+    env.storage().persistent().set(&key, &sunset);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+pub(crate) fn get_replacement(env: &Env, asset: &Asset) -> Option<Asset> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Replacement(asset.clone()))
+}
+
+pub(crate) fn set_replacement(env: &Env, asset: &Asset, replacement: &Asset) {
+    let key = DataKey::Replacement(asset.clone());
+    env.storage().persistent().set(&key, replacement);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+pub(crate) fn get_emergency_proposal(env: &Env, asset: &Asset) -> Option<EmergencyProposal> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::EmergencyProposal(asset.clone()))
+}
+
+pub(crate) fn set_emergency_proposal(env: &Env, asset: &Asset, proposal: &EmergencyProposal) {
+    let key = DataKey::EmergencyProposal(asset.clone());
+    env.storage().persistent().set(&key, proposal);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+pub(crate) fn remove_emergency_proposal(env: &Env, asset: &Asset) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::EmergencyProposal(asset.clone()));
+}
+
+pub(crate) fn has_role(env: &Env, role: &Role, who: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::Role(role.clone(), who.clone()))
+}
+
+pub(crate) fn set_role(env: &Env, role: &Role, who: &Address, granted: bool) {
+    let key = DataKey::Role(role.clone(), who.clone());
+    if granted {
+        env.storage().persistent().set(&key, &());
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
@@ -0,0 +1,718 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Events, Ledger},
+    vec,
+    xdr::{ContractEventBody, ScVal},
+    Address, Env, String, Vec,
+};
+
+use crate::{
+    Asset, AssetType, ComplianceStatus, Error, RWAMetadata, RWAOracle, RWAOracleClient,
+    RegulatoryInfo, Role,
+};
+
+fn setup(env: &Env) -> (RWAOracleClient<'_>, Address) {
+    let admin = Address::generate(env);
+    let base = Asset::Other(symbol_short!("USD"));
+    let id = env.register(RWAOracle, (&admin, base, 7u32, 300u32));
+    (RWAOracleClient::new(env, &id), admin)
+}
+
+fn sample_metadata(env: &Env, asset: Asset) -> RWAMetadata {
+    RWAMetadata {
+        asset,
+        name: String::from_str(env, "US Treasury Bond 2030"),
+        asset_type: AssetType::Bond,
+        coupon_rate_bps: 0,
+        coupon_frequency: 0,
+        maturity: 0,
+        issuer: String::from_str(env, "US Treasury"),
+        custodian: String::from_str(env, "Example Custody LLC"),
+        regulatory_info: RegulatoryInfo {
+            jurisdiction: String::from_str(env, "US"),
+            license_id: String::from_str(env, "SEC-123"),
+            license_expiry: None,
+            is_regulated: true,
+            compliance_status: ComplianceStatus::Approved,
+        },
+        metadata: Vec::new(env),
+    }
+}
+
+#[test]
+fn constructor_sets_config() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    assert_eq!(client.base(), Asset::Other(symbol_short!("USD")));
+    assert_eq!(client.decimals(), 7);
+    assert_eq!(client.resolution(), 300);
+}
+
+#[test]
+fn add_assets_emits_one_event_per_asset() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin) = setup(&env);
+    let assets = vec![
+        &env,
+        Asset::Other(symbol_short!("TBOND")),
+        Asset::Other(symbol_short!("GOLD")),
+    ];
+    client.add_assets(&assets);
+    let events = env.events().all();
+    assert_eq!(events.events().len(), 2);
+    for event in events.events().iter() {
+        let ContractEventBody::V0(body) = &event.body;
+        assert!(matches!(
+            body.topics.first(),
+            Some(ScVal::Symbol(sym)) if sym.0.as_slice() == b"asset_added"
+        ));
+    }
+}
+
+#[test]
+fn health_reports_feed_ages() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    env.ledger().with_mut(|l| l.timestamp = 1_000_000);
+    let tbond = Asset::Other(symbol_short!("TBOND"));
+    let gold = Asset::Other(symbol_short!("GOLD"));
+    client.add_assets(&vec![&env, tbond.clone(), gold.clone()]);
+    client.set_asset_price(&admin, &tbond, &2_0000000, &999_000);
+    assert_eq!(client.last_timestamp(), 999_000);
+    assert_eq!(client.last_timestamp_for(&tbond), Some(999_000));
+    assert_eq!(client.last_timestamp_for(&gold), None);
+    // TBOND's latest record is 1000 seconds old; GOLD has never priced.
+    assert_eq!(
+        client.health(),
+        vec![&env, (tbond, 1_000u64), (gold, u64::MAX)]
+    );
+}
+
+#[test]
+fn version_and_migrate() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin) = setup(&env);
+    assert_eq!(client.version(), 1);
+    // Nothing to transform at v1, but the call must succeed and be
+    // repeatable so operators can run it after every upgrade.
+    client.migrate();
+    client.migrate();
+    assert_eq!(client.version(), 1);
+}
+
+#[test]
+fn set_and_read_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let asset = Asset::Other(symbol_short!("TBOND"));
+    client.add_assets(&vec![&env, asset.clone()]);
+    client.set_asset_price(&admin, &asset, &1_0000000, &100);
+    client.set_asset_price(&admin, &asset, &1_0050000, &400);
+
+    let last = client.lastprice(&asset).unwrap();
+    assert_eq!(last.price, 1_0050000);
+    assert_eq!(last.timestamp, 400);
+    assert_eq!(client.price(&asset, &100).unwrap().price, 1_0000000);
+    assert_eq!(client.prices(&asset, &10).unwrap().len(), 2);
+
+    // Nearest lookup returns the latest record at or before the time.
+    assert_eq!(client.price_at_or_before(&asset, &399).unwrap().price, 1_0000000);
+    assert_eq!(client.price_at_or_before(&asset, &400).unwrap().price, 1_0050000);
+    assert_eq!(client.price_at_or_before(&asset, &9_999).unwrap().price, 1_0050000);
+    assert_eq!(client.price_at_or_before(&asset, &99), None);
+}
+
+#[test]
+fn quote_serves_mid_and_exposes_spread() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let asset = Asset::Other(symbol_short!("TBOND"));
+    client.add_assets(&vec![&env, asset.clone()]);
+
+    assert!(client.try_set_quote(&admin, &asset, &0, &101_0000000, &100).is_err());
+    assert!(client.try_set_quote(&admin, &asset, &101_0000000, &99_0000000, &100).is_err());
+
+    client.set_quote(&admin, &asset, &99_0000000, &101_0000000, &100);
+    assert_eq!(client.lastprice(&asset).unwrap().price, 100_0000000);
+    let quote = client.bid_ask(&asset).unwrap();
+    assert_eq!((quote.bid, quote.ask), (99_0000000, 101_0000000));
+    // 2 XLM wide on a 100 XLM mid: 200 bps.
+    assert_eq!(client.spread(&asset), Some(200));
+
+    // An outright price supersedes the quote; the stale sides disappear.
+    client.set_asset_price(&admin, &asset, &100_5000000, &400);
+    assert_eq!(client.bid_ask(&asset), None);
+    assert_eq!(client.spread(&asset), None);
+}
+
+#[test]
+fn deprecated_asset_sunsets_queries_and_updates() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let (client, admin) = setup(&env);
+    let bond = Asset::Other(symbol_short!("TBOND"));
+    let note = Asset::Other(symbol_short!("TNOTE"));
+    client.add_assets(&vec![&env, bond.clone(), note.clone()]);
+    client.set_asset_price(&admin, &bond, &100_0000000, &100);
+
+    // The replacement must be a different registered asset.
+    assert!(client.try_deprecate_asset(&bond, &2_000, &Some(bond.clone())).is_err());
+    client.deprecate_asset(&bond, &2_000, &Some(note.clone()));
+
+    // Until sunset the feed behaves normally.
+    assert_eq!(client.lastprice(&bond).unwrap().price, 100_0000000);
+    client.set_asset_price(&admin, &bond, &100_5000000, &400);
+
+    env.ledger().with_mut(|l| l.timestamp = 2_000);
+    assert_eq!(client.lastprice(&bond), None);
+    assert_eq!(client.prices(&bond, &10), None);
+    assert_eq!(
+        client.try_lastprice_in(&bond, &client.base()).err().unwrap().unwrap(),
+        Error::AssetDeprecated
+    );
+    assert_eq!(
+        client
+            .try_set_asset_price(&admin, &bond, &101_0000000, &700)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::AssetDeprecated
+    );
+    // Consumers find the successor through the wind-down records.
+    assert_eq!(client.sunset_of(&bond), Some(2_000));
+    assert_eq!(client.replacement_of(&bond), Some(note));
+}
+
+#[test]
+fn rejects_stale_and_nonpositive_prices() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let asset = Asset::Other(symbol_short!("TBOND"));
+    client.add_assets(&vec![&env, asset.clone()]);
+    client.set_asset_price(&admin, &asset, &1_0000000, &100);
+    assert!(client.try_set_asset_price(&admin, &asset, &1_0000000, &100).is_err());
+    assert!(client.try_set_asset_price(&admin, &asset, &0, &200).is_err());
+}
+
+#[test]
+fn updates_throttled_to_resolution() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let asset = Asset::Other(symbol_short!("TBOND"));
+    client.add_assets(&vec![&env, asset.clone()]);
+    assert_eq!(client.next_allowed_update(&asset), 0);
+
+    client.set_asset_price(&admin, &asset, &1_0000000, &100);
+    assert_eq!(client.next_allowed_update(&asset), 400);
+    // One second short of the 300s resolution window is rejected.
+    assert_eq!(
+        client
+            .try_set_asset_price(&admin, &asset, &1_0100000, &399)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::UpdateTooFrequent
+    );
+    client.set_asset_price(&admin, &asset, &1_0100000, &400);
+    assert_eq!(client.prices(&asset, &10).unwrap().len(), 2);
+}
+
+#[test]
+fn lastprice_in_converts_through_base() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let bond = Asset::Other(symbol_short!("TBOND"));
+    let eur = Asset::Other(symbol_short!("EUR"));
+    client.add_assets(&vec![&env, bond.clone(), eur.clone()]);
+    // EUR at 1.25 USD; bond quoted directly in EUR at 80 EUR.
+    client.set_asset_price(&admin, &eur, &1_2500000, &100);
+    client.set_asset_price_in(&admin, &bond, &80_0000000, &100, &eur);
+
+    // Records default to the base quote unless set explicitly.
+    assert_eq!(client.lastprice(&eur).unwrap().quote, client.base());
+    assert_eq!(client.lastprice(&bond).unwrap().quote, eur);
+
+    // 80 EUR * 1.25 = 100 USD.
+    let in_usd = client.lastprice_in(&bond, &client.base());
+    assert_eq!(in_usd.price, 100_0000000);
+    // Identity conversion returns the record untouched.
+    assert_eq!(client.lastprice_in(&bond, &eur).price, 80_0000000);
+    // A base-quoted series re-expressed in itself is the identity price.
+    let eur_in_eur = client.lastprice_in(&eur, &eur);
+    assert_eq!(eur_in_eur.price, 1_0000000);
+
+    // Quoting against an asset with no feed fails.
+    assert!(client
+        .try_lastprice_in(&bond, &Asset::Other(symbol_short!("GBP")))
+        .is_err());
+
+    // `price_in_base` is the same conversion under the consumer-facing
+    // name: the USD-quoted record re-expressed in EUR.
+    let in_eur = client.price_in_base(&eur, &eur);
+    assert_eq!(in_eur.price, 1_0000000);
+    assert_eq!(client.price_in_base(&bond, &client.base()).price, 100_0000000);
+}
+
+#[test]
+fn rejects_unregistered_quote_on_write() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let bond = Asset::Other(symbol_short!("TBOND"));
+    client.add_assets(&vec![&env, bond.clone()]);
+    assert!(client
+        .try_set_asset_price_in(&admin, &bond, &1_0000000, &100, &Asset::Other(symbol_short!("EUR")))
+        .is_err());
+}
+
+#[test]
+fn roles_gate_price_and_metadata_writes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let asset = Asset::Other(symbol_short!("TBOND"));
+    client.add_assets(&vec![&env, asset.clone()]);
+
+    let feeder = Address::generate(&env);
+    let manager = Address::generate(&env);
+
+    // Without a role, neither address can write anything.
+    assert!(client
+        .try_set_asset_price(&feeder, &asset, &1_0000000, &100)
+        .is_err());
+    assert!(client
+        .try_set_rwa_metadata(&manager, &sample_metadata(&env, asset.clone()))
+        .is_err());
+
+    client.grant_role(&Role::PriceFeeder, &feeder);
+    client.grant_role(&Role::MetadataManager, &manager);
+    assert!(client.has_role(&Role::PriceFeeder, &feeder));
+
+    client.set_asset_price(&feeder, &asset, &1_0000000, &100);
+    client.set_rwa_metadata(&manager, &sample_metadata(&env, asset.clone()));
+
+    // A feeder key cannot rewrite compliance data, and vice versa.
+    assert!(client
+        .try_set_rwa_metadata(&feeder, &sample_metadata(&env, asset.clone()))
+        .is_err());
+    assert!(client
+        .try_set_asset_price(&manager, &asset, &2_0000000, &400)
+        .is_err());
+
+    client.revoke_role(&Role::PriceFeeder, &feeder);
+    assert!(!client.has_role(&Role::PriceFeeder, &feeder));
+    assert!(client
+        .try_set_asset_price(&feeder, &asset, &2_0000000, &400)
+        .is_err());
+
+    // The owner keeps working throughout.
+    client.set_asset_price(&admin, &asset, &2_0000000, &400);
+}
+
+#[test]
+fn emergency_override_requires_two_signers() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin) = setup(&env);
+    let asset = Asset::Other(symbol_short!("TBOND"));
+    client.add_assets(&vec![&env, asset.clone()]);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    client.grant_role(&Role::EmergencySigner, &a);
+    client.grant_role(&Role::EmergencySigner, &b);
+
+    assert!(client
+        .try_emergency_set_price(&outsider, &asset, &3_0000000)
+        .is_err());
+
+    // One signer alone only records a proposal, however often they call.
+    assert!(!client.emergency_set_price(&a, &asset, &3_0000000));
+    assert!(!client.emergency_set_price(&a, &asset, &3_0000000));
+    assert_eq!(client.lastprice(&asset), None);
+
+    // A second signer naming a different price supersedes rather than
+    // confirms, so the pair must agree on the exact value.
+    assert!(!client.emergency_set_price(&b, &asset, &4_0000000));
+    assert!(!client.emergency_set_price(&a, &asset, &3_0000000));
+
+    // Agreement from two distinct keys pins the price immediately.
+    assert!(client.emergency_set_price(&b, &asset, &3_0000000));
+    assert_eq!(client.lastprice(&asset).unwrap().price, 3_0000000);
+}
+
+#[test]
+fn rejects_duplicate_assets() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin) = setup(&env);
+    let asset = Asset::Other(symbol_short!("TBOND"));
+    client.add_assets(&vec![&env, asset.clone()]);
+    assert!(client.try_add_assets(&vec![&env, asset]).is_err());
+}
+
+#[test]
+fn dirty_price_accrues_coupon_between_payments() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let bond = Asset::Other(symbol_short!("TBOND"));
+    client.add_assets(&vec![&env, bond.clone()]);
+    client.set_asset_price(&admin, &bond, &100_0000000, &100);
+
+    // Without coupon metadata the dirty price is the clean price.
+    assert_eq!(client.dirty_price(&bond).price, 100_0000000);
+
+    // 5% semiannual coupon; the last coupon fell half a period (an
+    // eighth of a year) before now, so 1.25 has accrued on a 100 clean.
+    let now = 50_000_000u64;
+    env.ledger().with_mut(|l| l.timestamp = now);
+    let period = 31_536_000 / 2;
+    let mut meta = sample_metadata(&env, bond.clone());
+    meta.coupon_rate_bps = 500;
+    meta.coupon_frequency = 2;
+    meta.maturity = now + period / 2;
+    client.set_rwa_metadata(&admin, &meta);
+    assert_eq!(client.dirty_price(&bond).price, 101_2500000);
+
+    // Past maturity nothing accrues.
+    env.ledger().with_mut(|l| l.timestamp = meta.maturity);
+    assert_eq!(client.dirty_price(&bond).price, 100_0000000);
+}
+
+#[test]
+fn volatility_over_stored_records() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let asset = Asset::Other(symbol_short!("TBOND"));
+    client.add_assets(&vec![&env, asset.clone()]);
+
+    // Needs at least two records to derive a return.
+    assert_eq!(client.volatility(&asset, &10), None);
+    client.set_asset_price(&admin, &asset, &100_0000000, &100);
+    assert_eq!(client.volatility(&asset, &10), None);
+
+    // +10% then -10%: mean return 0, both deviations exactly 10%.
+    client.set_asset_price(&admin, &asset, &110_0000000, &400);
+    client.set_asset_price(&admin, &asset, &99_0000000, &700);
+    assert_eq!(client.volatility(&asset, &10), Some(1000000));
+
+    // A flat series has zero volatility.
+    client.set_asset_price(&admin, &asset, &99_0000000, &1000);
+    assert_eq!(client.volatility(&asset, &2), Some(0));
+}
+
+#[test]
+fn alias_resolves_reads_and_writes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let canonical = Asset::Other(symbol_short!("USDT"));
+    let vintage = Asset::Other(symbol_short!("USDT2024"));
+    let stellar = Asset::Stellar(Address::generate(&env));
+    client.add_assets(&vec![&env, canonical.clone()]);
+    client.set_alias(&vintage, &canonical);
+    client.set_alias(&stellar, &canonical);
+    assert_eq!(client.alias_of(&vintage), Some(canonical.clone()));
+
+    client.set_asset_price(&admin, &canonical, &1_0000000, &100);
+    // All three names read the same series.
+    assert_eq!(client.lastprice(&vintage).unwrap().price, 1_0000000);
+    assert_eq!(client.lastprice(&stellar).unwrap().price, 1_0000000);
+
+    // Writes under the alias land on the canonical feed too.
+    client.set_asset_price(&admin, &vintage, &1_0100000, &400);
+    assert_eq!(client.lastprice(&canonical).unwrap().price, 1_0100000);
+    assert_eq!(client.prices(&canonical, &10).unwrap().len(), 2);
+
+    client.remove_alias(&vintage);
+    assert_eq!(client.alias_of(&vintage), None);
+    assert_eq!(client.lastprice(&vintage), None);
+
+    // An alias cannot shadow a registered asset or target an unknown one.
+    assert!(client.try_set_alias(&canonical, &canonical).is_err());
+    assert!(client
+        .try_set_alias(&vintage, &Asset::Other(symbol_short!("NOPE")))
+        .is_err());
+}
+
+#[test]
+fn ttl_bumps() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let asset = Asset::Other(symbol_short!("TBOND"));
+    client.add_assets(&vec![&env, asset.clone()]);
+    client.set_asset_price(&admin, &asset, &1_0000000, &100);
+
+    client.bump_asset_ttl(&asset);
+    // A feed with no history cannot be bumped...
+    assert!(client
+        .try_bump_asset_ttl(&Asset::Other(symbol_short!("NOPE")))
+        .is_err());
+    // ...but bump_all skips unknowns so keeper lists stay simple.
+    client.bump_all(&vec![
+        &env,
+        asset,
+        Asset::Other(symbol_short!("NOPE")),
+    ]);
+}
+
+#[test]
+fn metadata_roundtrip() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let bond = Asset::Other(symbol_short!("TBOND"));
+    let meta = sample_metadata(&env, bond.clone());
+    client.set_rwa_metadata(&admin, &meta);
+    assert_eq!(client.get_rwa_metadata(&bond), Some(meta));
+    assert_eq!(
+        client.get_rwa_metadata(&Asset::Other(symbol_short!("NOPE"))),
+        None
+    );
+
+    // Stellar-address assets carry metadata records too.
+    let stellar = Asset::Stellar(Address::generate(&env));
+    let meta = sample_metadata(&env, stellar.clone());
+    client.set_rwa_metadata(&admin, &meta);
+    assert_eq!(client.get_rwa_metadata(&stellar), Some(meta));
+    assert_eq!(client.rwa_asset_count(), 2);
+}
+
+#[test]
+fn paginated_asset_listing() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let assets = [
+        Asset::Other(symbol_short!("RWA0")),
+        Asset::Other(symbol_short!("RWA1")),
+        Asset::Other(symbol_short!("RWA2")),
+        Asset::Other(symbol_short!("RWA3")),
+        Asset::Other(symbol_short!("RWA4")),
+    ];
+    for asset in assets.iter() {
+        client.set_rwa_metadata(&admin, &sample_metadata(&env, asset.clone()));
+    }
+    assert_eq!(client.rwa_asset_count(), 5);
+    assert_eq!(client.get_all_rwa_assets().len(), 5);
+
+    let page = client.get_rwa_assets_page(&1, &2);
+    assert_eq!(page, vec![&env, assets[1].clone(), assets[2].clone()]);
+    // Offset past the end yields an empty page rather than an error.
+    assert_eq!(client.get_rwa_assets_page(&10, &2).len(), 0);
+
+    let metas = client.get_rwa_metadata_page(&3, &10);
+    assert_eq!(metas.len(), 2);
+    assert_eq!(metas.get_unchecked(0).asset, assets[3]);
+}
+
+#[test]
+fn lapsed_license_downgrades_compliance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    env.ledger().with_mut(|l| l.timestamp = 1_000_000);
+    let asset = Asset::Other(symbol_short!("TBOND"));
+    let mut metadata = sample_metadata(&env, asset.clone());
+    metadata.regulatory_info.license_expiry = Some(2_000_000);
+    client.set_rwa_metadata(&admin, &metadata);
+
+    // Before expiry the attested status stands.
+    assert_eq!(client.refresh_compliance(&asset), ComplianceStatus::Approved);
+
+    env.ledger().with_mut(|l| l.timestamp = 2_000_000);
+    assert_eq!(client.refresh_compliance(&asset), ComplianceStatus::Suspended);
+    let events = env.events().all();
+    let last = events.events().last().unwrap();
+    let ContractEventBody::V0(body) = &last.body;
+    assert!(matches!(
+        body.topics.first(),
+        Some(ScVal::Symbol(sym)) if sym.0.as_slice() == b"compliance"
+    ));
+    assert_eq!(
+        client
+            .get_rwa_metadata(&asset)
+            .unwrap()
+            .regulatory_info
+            .compliance_status,
+        ComplianceStatus::Suspended
+    );
+    // Repeat calls are a no-op; the downgrade never escalates further.
+    assert_eq!(client.refresh_compliance(&asset), ComplianceStatus::Suspended);
+}
+
+#[test]
+fn metadata_entries_amend_incrementally() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let bond = Asset::Other(symbol_short!("TBOND"));
+
+    // Entries can only amend an existing record.
+    assert_eq!(
+        client
+            .try_set_metadata_entry(
+                &admin,
+                &bond,
+                &symbol_short!("cusip"),
+                &String::from_str(&env, "912828XG8"),
+            )
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::MetadataNotFound
+    );
+
+    client.set_rwa_metadata(&admin, &sample_metadata(&env, bond.clone()));
+    client.set_metadata_entry(
+        &admin,
+        &bond,
+        &symbol_short!("cusip"),
+        &String::from_str(&env, "912828XG8"),
+    );
+    client.set_metadata_entry(
+        &admin,
+        &bond,
+        &symbol_short!("custodian"),
+        &String::from_str(&env, "CUST-42"),
+    );
+    // Re-setting a key replaces its value in place.
+    client.set_metadata_entry(
+        &admin,
+        &bond,
+        &symbol_short!("cusip"),
+        &String::from_str(&env, "912828XH6"),
+    );
+    let meta = client.get_rwa_metadata(&bond).unwrap();
+    assert_eq!(meta.metadata.len(), 2);
+    assert_eq!(
+        meta.metadata.get_unchecked(0),
+        (symbol_short!("cusip"), String::from_str(&env, "912828XH6"))
+    );
+
+    client.remove_metadata_entry(&admin, &bond, &symbol_short!("cusip"));
+    // Removing an absent key is a no-op.
+    client.remove_metadata_entry(&admin, &bond, &symbol_short!("cusip"));
+    let meta = client.get_rwa_metadata(&bond).unwrap();
+    assert_eq!(meta.metadata.len(), 1);
+    assert_eq!(meta.metadata.get_unchecked(0).0, symbol_short!("custodian"));
+}
+
+#[test]
+fn re_register_same_symbol_updates_in_place() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let bond = Asset::Other(symbol_short!("TBOND"));
+    let mut meta = sample_metadata(&env, bond.clone());
+    client.set_rwa_metadata(&admin, &meta);
+    meta.name = String::from_str(&env, "Renamed");
+    client.set_rwa_metadata(&admin, &meta);
+    assert_eq!(client.rwa_asset_count(), 1);
+    assert_eq!(
+        client.get_rwa_metadata(&bond).unwrap().name,
+        String::from_str(&env, "Renamed")
+    );
+}
+
+mod subscriptions {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl};
+
+    #[contract]
+    pub struct Consumer;
+
+    #[contractimpl]
+    impl Consumer {
+        pub fn on_price_update(env: Env, asset: Asset, price: i128, timestamp: u64) {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("last"), &(asset, price, timestamp));
+        }
+
+        pub fn last(env: Env) -> Option<(Asset, i128, u64)> {
+            env.storage().instance().get(&symbol_short!("last"))
+        }
+    }
+
+    /// A consumer that always fails; the feed must keep flowing.
+    #[contract]
+    pub struct BrokenConsumer;
+
+    #[contractimpl]
+    impl BrokenConsumer {
+        pub fn on_price_update(_env: Env, _asset: Asset, _price: i128, _timestamp: u64) {
+            panic!("consumer down");
+        }
+    }
+
+    #[test]
+    fn subscribers_receive_price_callbacks() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, admin) = setup(&env);
+        let asset = Asset::Other(symbol_short!("TBOND"));
+        client.add_assets(&vec![&env, asset.clone()]);
+
+        let consumer_id = env.register(Consumer, ());
+        let consumer = ConsumerClient::new(&env, &consumer_id);
+        let broken_id = env.register(BrokenConsumer, ());
+        client.subscribe(&broken_id, &asset);
+        client.subscribe(&consumer_id, &asset);
+        // Re-subscribing is a no-op.
+        client.subscribe(&consumer_id, &asset);
+        assert_eq!(client.subscribers(&asset).len(), 2);
+
+        client.set_asset_price(&admin, &asset, &2_0000000, &100);
+        assert_eq!(
+            consumer.last(),
+            Some((asset.clone(), 2_0000000, 100))
+        );
+
+        client.unsubscribe(&consumer_id, &asset);
+        client.set_asset_price(&admin, &asset, &3_0000000, &400);
+        // Only the broken consumer remained; the earlier delivery sticks.
+        assert_eq!(consumer.last(), Some((asset, 2_0000000, 100)));
+    }
+
+    #[test]
+    fn large_subscriber_lists_drain_in_batches() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, admin) = setup(&env);
+        let asset = Asset::Other(symbol_short!("TBOND"));
+        client.add_assets(&vec![&env, asset.clone()]);
+
+        let mut ids: Vec<Address> = Vec::new(&env);
+        for _ in 0..7 {
+            let id = env.register(Consumer, ());
+            client.subscribe(&id, &asset);
+            ids.push_back(id);
+        }
+        let consumer = |i: u32| ConsumerClient::new(&env, &ids.get_unchecked(i));
+
+        client.set_asset_price(&admin, &asset, &2_0000000, &100);
+        // The first batch is notified inline, the overflow waits.
+        assert_eq!(consumer(4).last(), Some((asset.clone(), 2_0000000, 100)));
+        assert_eq!(consumer(5).last(), None);
+
+        assert_eq!(client.notify_subscribers(&asset), 0);
+        assert_eq!(consumer(6).last(), Some((asset.clone(), 2_0000000, 100)));
+        // Nothing left pending.
+        assert_eq!(client.notify_subscribers(&asset), 0);
+    }
+}
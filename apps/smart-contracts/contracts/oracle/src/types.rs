@@ -0,0 +1,105 @@
+use soroban_sdk::{contracttype, Address, String, Symbol, Vec};
+
+/// Asset identifier following the SEP-40 convention: either a Stellar
+/// (Soroban) token contract address or an off-chain symbol such as a ticker.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Asset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+/// A single price record, denominated in the oracle's configured decimals.
+/// `quote` is the currency the price is expressed in; feeds default to the
+/// oracle's base but individual series may be quoted differently (EUR real
+/// estate, USD bonds) within one deployment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+    pub quote: Asset,
+}
+
+/// Raw sides of the most recent two-way quote for an asset, base-quoted.
+/// Stored alongside the derived mid so spread stays observable.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BidAsk {
+    pub bid: i128,
+    pub ask: i128,
+    pub timestamp: u64,
+}
+
+/// Grantable permission roles. The owner (admin) implicitly holds every
+/// role and is the only one able to grant or revoke them.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Role {
+    /// May push price records.
+    PriceFeeder,
+    /// May write RWA metadata and regulatory info.
+    MetadataManager,
+    /// May propose or confirm emergency price overrides. Two distinct
+    /// signers are required for an override to take effect.
+    EmergencySigner,
+}
+
+/// Broad classification of the real-world asset backing a feed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AssetType {
+    Bond,
+    Equity,
+    RealEstate,
+    Commodity,
+    Other,
+}
+
+/// Compliance standing of a registered RWA as attested by the operator.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ComplianceStatus {
+    Pending,
+    Approved,
+    Suspended,
+    Revoked,
+}
+
+/// Regulatory attestation attached to an RWA's metadata record.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RegulatoryInfo {
+    pub jurisdiction: String,
+    pub license_id: String,
+    /// Unix timestamp the license lapses; `None` for licenses without a
+    /// recorded expiry. Once passed, `refresh_compliance` downgrades the
+    /// record's status.
+    pub license_expiry: Option<u64>,
+    pub is_regulated: bool,
+    pub compliance_status: ComplianceStatus,
+}
+
+/// Descriptive and regulatory metadata for a real-world asset served by
+/// this oracle. Keyed by the full [`Asset`] enum so tokenized RWAs issued
+/// as Soroban tokens carry records alongside symbol feeds. The free-form
+/// `metadata` vector carries extension fields (prospectus links, CUSIPs,
+/// custodian IDs, ...) keyed by short symbols.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RWAMetadata {
+    pub asset: Asset,
+    pub name: String,
+    pub asset_type: AssetType,
+    /// Annual coupon rate in basis points; zero for non-coupon assets.
+    pub coupon_rate_bps: u32,
+    /// Coupon payments per year; zero disables accrual.
+    pub coupon_frequency: u32,
+    /// Unix timestamp of the bond's maturity; coupon dates are anchored
+    /// backwards from it. Zero for non-bond assets.
+    pub maturity: u64,
+    pub issuer: String,
+    pub custodian: String,
+    pub regulatory_info: RegulatoryInfo,
+    pub metadata: Vec<(Symbol, String)>,
+}
@@ -0,0 +1,42 @@
+use soroban_sdk::{contractevent, Address, BytesN};
+
+/// Published when a new oracle is deployed and bootstrapped.
+#[contractevent(topics = ["oracle_deployed"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OracleDeployed {
+    #[topic]
+    pub oracle: Address,
+    pub wasm_hash: BytesN<32>,
+}
+
+/// Published when the admin grants the Operator role to an address.
+#[contractevent(topics = ["operator_added"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OperatorAdded {
+    #[topic]
+    pub address: Address,
+}
+
+/// Published when the admin revokes the Operator role from an address.
+#[contractevent(topics = ["operator_removed"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OperatorRemoved {
+    #[topic]
+    pub address: Address,
+}
+
+/// Published when a market's directory metadata is set or replaced.
+#[contractevent(topics = ["market_info"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketInfoSet {
+    #[topic]
+    pub oracle: Address,
+}
+
+/// Published after every deployed oracle was moved to a new wasm hash.
+#[contractevent(topics = ["fleet_upgraded"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FleetUpgraded {
+    pub wasm_hash: BytesN<32>,
+    pub oracles: u32,
+}
@@ -0,0 +1,321 @@
+//! Deployment orchestrator for the RWA market stack.
+//!
+//! Holds the wasm hashes of the protocol's contracts and stands up whole
+//! markets (oracle, token, pool wiring) from one admin transaction set.
+//! The orchestrator deploys itself as admin of the contracts it creates
+//! so the fleet can later be maintained as a unit.
+#![no_std]
+
+mod events;
+mod storage;
+
+#[cfg(test)]
+mod test;
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, symbol_short, Address, BytesN, Env, String, Symbol, Vec,
+};
+
+use oracle::{Asset, RWAOracleClient, Role};
+
+pub use crate::storage::MarketInfo;
+
+use crate::events::{FleetUpgraded, MarketInfoSet, OperatorAdded, OperatorRemoved, OracleDeployed};
+
+/// Minimum seconds between fleet upgrades unless reconfigured.
+pub const DEFAULT_UPGRADE_INTERVAL: u64 = 86_400;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    Unauthorized = 1,
+    WasmNotSet = 2,
+    SameWasmHash = 3,
+    CanaryNotPassed = 4,
+    UpgradeTooSoon = 5,
+    UnknownOracle = 6,
+}
+
+#[contract]
+pub struct Orchestrator;
+
+#[contractimpl]
+impl Orchestrator {
+    pub fn __constructor(env: Env, admin: Address) {
+        storage::set_admin(&env, &admin);
+    }
+
+    // --- Wasm management ------------------------------------------------
+
+    /// Stores the wasm hash used for subsequent oracle deployments. The
+    /// code must already be uploaded to the network.
+    pub fn set_oracle_wasm(env: Env, hash: BytesN<32>) {
+        Self::require_admin(&env);
+        storage::set_oracle_wasm(&env, &hash);
+    }
+
+    pub fn oracle_wasm(env: Env) -> Option<BytesN<32>> {
+        storage::get_oracle_wasm(&env)
+    }
+
+    /// Marks `hash` as canary-validated, arming the next `upgrade_fleet`
+    /// for that exact wasm. An automated pipeline sets this only after
+    /// the canary deployment passed its checks.
+    pub fn set_canary_passed(env: Env, hash: BytesN<32>) {
+        Self::require_admin(&env);
+        storage::set_canary_passed(&env, &hash);
+    }
+
+    pub fn canary_passed(env: Env) -> Option<BytesN<32>> {
+        storage::get_canary_passed(&env)
+    }
+
+    /// Minimum seconds that must pass between fleet upgrades.
+    pub fn set_upgrade_interval(env: Env, interval: u64) {
+        Self::require_admin(&env);
+        storage::set_upgrade_interval(&env, interval);
+    }
+
+    pub fn upgrade_interval(env: Env) -> u64 {
+        storage::get_upgrade_interval(&env)
+    }
+
+    /// Upgrades every deployed oracle to `new_wasm_hash` and makes it
+    /// the hash for future deployments. Guarded so a pipeline cannot
+    /// hammer the fleet: the hash must differ from the one in use, must
+    /// have passed canary validation, and upgrades are rate-limited to
+    /// the configured interval. The canary flag is consumed, so each
+    /// upgrade needs a fresh canary pass.
+    pub fn upgrade_fleet(env: Env, new_wasm_hash: BytesN<32>) -> Result<u32, Error> {
+        Self::require_admin(&env);
+        if storage::get_oracle_wasm(&env).as_ref() == Some(&new_wasm_hash) {
+            return Err(Error::SameWasmHash);
+        }
+        if storage::get_canary_passed(&env).as_ref() != Some(&new_wasm_hash) {
+            return Err(Error::CanaryNotPassed);
+        }
+        let now = env.ledger().timestamp();
+        let last = storage::get_last_fleet_upgrade(&env);
+        if last != 0 && now < last + storage::get_upgrade_interval(&env) {
+            return Err(Error::UpgradeTooSoon);
+        }
+        let count = storage::oracle_count(&env);
+        for i in 0..count {
+            if let Some(oracle) = storage::oracle_at(&env, i) {
+                RWAOracleClient::new(&env, &oracle).upgrade(&new_wasm_hash);
+            }
+        }
+        storage::set_oracle_wasm(&env, &new_wasm_hash);
+        storage::clear_canary_passed(&env);
+        storage::set_last_fleet_upgrade(&env, now);
+        FleetUpgraded {
+            wasm_hash: new_wasm_hash,
+            oracles: count,
+        }
+        .publish(&env);
+        Ok(count)
+    }
+
+    // --- Deployments ----------------------------------------------------
+
+    /// Deploys a new oracle from the stored wasm hash, registers its
+    /// initial assets, and grants the PriceFeeder role to each of
+    /// `feeders`. The orchestrator itself becomes the oracle's owner.
+    /// `caller` must be the admin or an Operator; the stored hash is the
+    /// approved template, so operators can launch markets but never
+    /// choose what code they run.
+    pub fn deploy_oracle(
+        env: Env,
+        caller: Address,
+        assets: Vec<Asset>,
+        base: Asset,
+        decimals: u32,
+        resolution: u32,
+        feeders: Vec<Address>,
+    ) -> Result<Address, Error> {
+        Self::require_admin_or_operator(&env, &caller)?;
+        let wasm_hash = storage::get_oracle_wasm(&env).ok_or(Error::WasmNotSet)?;
+        let deployed = env
+            .deployer()
+            .with_current_contract(Self::next_salt(&env))
+            .deploy_v2(
+                wasm_hash.clone(),
+                (env.current_contract_address(), base, decimals, resolution),
+            );
+        let client = RWAOracleClient::new(&env, &deployed);
+        client.add_assets(&assets);
+        for feeder in feeders.iter() {
+            client.grant_role(&Role::PriceFeeder, &feeder);
+        }
+        storage::push_oracle(&env, &deployed);
+        OracleDeployed {
+            oracle: deployed.clone(),
+            wasm_hash,
+        }
+        .publish(&env);
+        Ok(deployed)
+    }
+
+    /// Number of oracles deployed by this orchestrator.
+    pub fn oracle_count(env: Env) -> u32 {
+        storage::oracle_count(&env)
+    }
+
+    /// Address of the oracle deployed at `index`, in deployment order.
+    pub fn oracle_at(env: Env, index: u32) -> Option<Address> {
+        storage::oracle_at(&env, index)
+    }
+
+    /// Attaches directory metadata to a deployed oracle's market so the
+    /// registry doubles as a market directory for frontends. `caller`
+    /// must be the admin or an Operator; replaces any existing entry.
+    pub fn set_market_info(
+        env: Env,
+        caller: Address,
+        oracle: Address,
+        info: MarketInfo,
+    ) -> Result<(), Error> {
+        Self::require_admin_or_operator(&env, &caller)?;
+        if !Self::is_deployed_oracle(&env, &oracle) {
+            return Err(Error::UnknownOracle);
+        }
+        storage::set_market_info(&env, &oracle, &info);
+        MarketInfoSet { oracle }.publish(&env);
+        Ok(())
+    }
+
+    /// Directory metadata for a deployed oracle's market, if any was set.
+    pub fn market_info(env: Env, oracle: Address) -> Option<MarketInfo> {
+        storage::get_market_info(&env, &oracle)
+    }
+
+    /// Dry-runs a token market's deployment parameters against the live
+    /// fleet and returns the checks that failed; an empty list means the
+    /// parameters are deployable. Checks: `mcr_low` (`min_collat_ratio`
+    /// below 100%), `unknown` (the oracle was not deployed by this
+    /// orchestrator), `decimals` (the oracle's feed decimals differ from
+    /// `decimals`), `no_price` (the oracle holds no positive price for
+    /// the pegged asset), and `sym_clash` (another deployed oracle
+    /// already serves the pegged asset). Read-only, so CI can gate on a
+    /// simulation and the deployment path can call it as its gatekeeper.
+    pub fn validate_market_params(
+        env: Env,
+        oracle: Address,
+        pegged_asset: Asset,
+        min_collat_ratio: u32,
+        decimals: u32,
+    ) -> Vec<Symbol> {
+        let mut violations = Vec::new(&env);
+        if min_collat_ratio < 10_000 {
+            violations.push_back(symbol_short!("mcr_low"));
+        }
+        if !Self::is_deployed_oracle(&env, &oracle) {
+            violations.push_back(symbol_short!("unknown"));
+            // The remaining checks interrogate the oracle; an address
+            // outside the registry is not safe to call.
+            return violations;
+        }
+        let client = RWAOracleClient::new(&env, &oracle);
+        if client.decimals() != decimals {
+            violations.push_back(symbol_short!("decimals"));
+        }
+        match client.lastprice(&pegged_asset) {
+            Some(record) if record.price > 0 => {}
+            _ => violations.push_back(symbol_short!("no_price")),
+        }
+        for i in 0..storage::oracle_count(&env) {
+            let other = match storage::oracle_at(&env, i) {
+                Some(other) if other != oracle => other,
+                _ => continue,
+            };
+            if RWAOracleClient::new(&env, &other)
+                .assets()
+                .contains(&pegged_asset)
+            {
+                violations.push_back(symbol_short!("sym_clash"));
+                break;
+            }
+        }
+        violations
+    }
+
+    // --- Administration -------------------------------------------------
+
+    /// Grants the Operator role: day-to-day market launches and health
+    /// checks without the root admin key. Operators cannot change wasm
+    /// hashes, upgrade the fleet, or administer roles.
+    pub fn add_operator(env: Env, who: Address) {
+        Self::require_admin(&env);
+        storage::set_operator(&env, &who, true);
+        OperatorAdded { address: who }.publish(&env);
+    }
+
+    /// Revokes the Operator role.
+    pub fn remove_operator(env: Env, who: Address) {
+        Self::require_admin(&env);
+        storage::set_operator(&env, &who, false);
+        OperatorRemoved { address: who }.publish(&env);
+    }
+
+    pub fn is_operator(env: Env, who: Address) -> bool {
+        storage::is_operator(&env, &who)
+    }
+
+    pub fn admin(env: Env) -> Address {
+        storage::get_admin(&env)
+    }
+
+    pub fn set_admin(env: Env, new_admin: Address) {
+        Self::require_admin(&env);
+        storage::set_admin(&env, &new_admin);
+    }
+
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        Self::require_admin(&env);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    /// Identifies the deployed build after fleet upgrades:
+    /// `(crate_version, git_sha, build_timestamp, soroban_sdk_version)`.
+    /// The release pipeline supplies the `GIT_SHA`, `BUILD_TIMESTAMP`,
+    /// and `SOROBAN_SDK_VERSION` env vars at compile time; a local build
+    /// reads "unknown" for all three.
+    pub fn build_info(env: Env) -> (String, String, String, String) {
+        (
+            String::from_str(&env, env!("CARGO_PKG_VERSION")),
+            String::from_str(&env, option_env!("GIT_SHA").unwrap_or("unknown")),
+            String::from_str(&env, option_env!("BUILD_TIMESTAMP").unwrap_or("unknown")),
+            String::from_str(&env, option_env!("SOROBAN_SDK_VERSION").unwrap_or("unknown")),
+        )
+    }
+
+    fn require_admin(env: &Env) -> Address {
+        let admin = storage::get_admin(env);
+        admin.require_auth();
+        admin
+    }
+
+    /// Authenticates `caller`, who must be the admin or an Operator.
+    fn require_admin_or_operator(env: &Env, caller: &Address) -> Result<(), Error> {
+        caller.require_auth();
+        if *caller == storage::get_admin(env) || storage::is_operator(env, caller) {
+            Ok(())
+        } else {
+            Err(Error::Unauthorized)
+        }
+    }
+
+    /// Whether `oracle` was deployed by this orchestrator.
+    fn is_deployed_oracle(env: &Env, oracle: &Address) -> bool {
+        (0..storage::oracle_count(env)).any(|i| storage::oracle_at(env, i).as_ref() == Some(oracle))
+    }
+
+    /// Fresh deterministic salt per deployment, derived from the running
+    /// deployment count.
+    fn next_salt(env: &Env) -> BytesN<32> {
+        let mut salt = [0u8; 32];
+        salt[28..].copy_from_slice(&storage::oracle_count(env).to_be_bytes());
+        BytesN::from_array(env, &salt)
+    }
+}
@@ -0,0 +1,140 @@
+use soroban_sdk::{contracttype, Address, BytesN, Env, String};
+
+/// Human-readable directory entry for a deployed market, so frontends
+/// can discover and describe markets without a separate metadata
+/// service.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketInfo {
+    pub description: String,
+    pub issuer_url: String,
+    /// Hash of the market's offering documents.
+    pub docs_hash: BytesN<32>,
+}
+
+/// Storage keys. Deployed contracts are indexed by position so the fleet
+/// can be listed and, later, upgraded as a unit.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// Instance: orchestrator admin.
+    Admin,
+    /// Instance: wasm hash used for new oracle deployments.
+    OracleWasm,
+    /// Instance: number of oracles deployed so far.
+    OracleCount,
+    /// Persistent: oracle deployed at this index position.
+    OracleIndex(u32),
+    /// Instance: minimum seconds between fleet upgrades.
+    UpgradeInterval,
+    /// Instance: timestamp of the last fleet upgrade.
+    LastFleetUpgrade,
+    /// Instance: wasm hash marked as having passed canary validation.
+    CanaryPassed,
+    /// Persistent: marker that an address holds the Operator role.
+    Operator(Address),
+    /// Persistent: directory metadata for a deployed oracle's market.
+    MarketInfo(Address),
+}
+
+pub(crate) fn get_admin(env: &Env) -> Address {
+    env.storage().instance().get(&DataKey::Admin).unwrap()
+}
+
+pub(crate) fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&DataKey::Admin, admin);
+}
+
+pub(crate) fn get_oracle_wasm(env: &Env) -> Option<BytesN<32>> {
+    env.storage().instance().get(&DataKey::OracleWasm)
+}
+
+pub(crate) fn set_oracle_wasm(env: &Env, hash: &BytesN<32>) {
+    env.storage().instance().set(&DataKey::OracleWasm, hash);
+}
+
+pub(crate) fn get_upgrade_interval(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::UpgradeInterval)
+        .unwrap_or(crate::DEFAULT_UPGRADE_INTERVAL)
+}
+
+pub(crate) fn set_upgrade_interval(env: &Env, interval: u64) {
+    env.storage()
+        .instance()
+        .set(&DataKey::UpgradeInterval, &interval);
+}
+
+pub(crate) fn get_last_fleet_upgrade(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::LastFleetUpgrade)
+        .unwrap_or(0)
+}
+
+pub(crate) fn set_last_fleet_upgrade(env: &Env, timestamp: u64) {
+    env.storage()
+        .instance()
+        .set(&DataKey::LastFleetUpgrade, &timestamp);
+}
+
+pub(crate) fn get_canary_passed(env: &Env) -> Option<BytesN<32>> {
+    env.storage().instance().get(&DataKey::CanaryPassed)
+}
+
+pub(crate) fn set_canary_passed(env: &Env, hash: &BytesN<32>) {
+    env.storage().instance().set(&DataKey::CanaryPassed, hash);
+}
+
+pub(crate) fn clear_canary_passed(env: &Env) {
+    env.storage().instance().remove(&DataKey::CanaryPassed);
+}
+
+pub(crate) fn is_operator(env: &Env, who: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::Operator(who.clone()))
+}
+
+pub(crate) fn set_operator(env: &Env, who: &Address, granted: bool) {
+    let key = DataKey::Operator(who.clone());
+    if granted {
+        env.storage().persistent().set(&key, &());
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+pub(crate) fn get_market_info(env: &Env, oracle: &Address) -> Option<MarketInfo> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::MarketInfo(oracle.clone()))
+}
+
+pub(crate) fn set_market_info(env: &Env, oracle: &Address, info: &MarketInfo) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::MarketInfo(oracle.clone()), info);
+}
+
+pub(crate) fn oracle_count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::OracleCount)
+        .unwrap_or(0)
+}
+
+pub(crate) fn oracle_at(env: &Env, index: u32) -> Option<Address> {
+    env.storage().persistent().get(&DataKey::OracleIndex(index))
+}
+
+pub(crate) fn push_oracle(env: &Env, oracle: &Address) {
+    let count = oracle_count(env);
+    env.storage()
+        .persistent()
+        .set(&DataKey::OracleIndex(count), oracle);
+    env.storage()
+        .instance()
+        .set(&DataKey::OracleCount, &(count + 1));
+}
@@ -0,0 +1,235 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    vec, Address, BytesN, Env, String,
+};
+
+use oracle::{Asset, RWAOracle, RWAOracleClient};
+
+use crate::{storage, Error, MarketInfo, Orchestrator, OrchestratorClient};
+
+fn setup(env: &Env) -> (OrchestratorClient<'_>, Address) {
+    let admin = Address::generate(env);
+    let id = env.register(Orchestrator, (&admin,));
+    (OrchestratorClient::new(env, &id), admin)
+}
+
+#[test]
+fn constructor_sets_admin() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    assert_eq!(client.admin(), admin);
+    assert_eq!(client.oracle_count(), 0);
+    assert_eq!(client.oracle_wasm(), None);
+}
+
+#[test]
+fn wasm_hash_roundtrip() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin) = setup(&env);
+    let hash = BytesN::from_array(&env, &[7u8; 32]);
+    client.set_oracle_wasm(&hash);
+    assert_eq!(client.oracle_wasm(), Some(hash));
+}
+
+#[test]
+fn deploy_requires_stored_wasm() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    assert_eq!(
+        client
+            .try_deploy_oracle(
+                &admin,
+                &vec![&env, Asset::Other(symbol_short!("TBOND"))],
+                &Asset::Other(symbol_short!("USD")),
+                &7,
+                &300,
+                &vec![&env],
+            )
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::WasmNotSet
+    );
+}
+
+#[test]
+fn operators_deploy_but_cannot_manage() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin) = setup(&env);
+    let operator = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    let deploy_as = |caller: &Address| {
+        client.try_deploy_oracle(
+            caller,
+            &vec![&env, Asset::Other(symbol_short!("TBOND"))],
+            &Asset::Other(symbol_short!("USD")),
+            &7,
+            &300,
+            &vec![&env],
+        )
+    };
+
+    assert_eq!(
+        deploy_as(&outsider).err().unwrap().unwrap(),
+        Error::Unauthorized
+    );
+
+    client.add_operator(&operator);
+    assert!(client.is_operator(&operator));
+    // The operator clears authorization; the deploy then only stops at
+    // the missing template wasm, which operators cannot set themselves.
+    assert_eq!(
+        deploy_as(&operator).err().unwrap().unwrap(),
+        Error::WasmNotSet
+    );
+
+    client.remove_operator(&operator);
+    assert!(!client.is_operator(&operator));
+    assert_eq!(
+        deploy_as(&operator).err().unwrap().unwrap(),
+        Error::Unauthorized
+    );
+}
+
+#[test]
+fn market_info_only_for_deployed_oracles() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let info = MarketInfo {
+        description: String::from_str(&env, "Tokenized 10y treasury market"),
+        issuer_url: String::from_str(&env, "https://issuer.example"),
+        docs_hash: BytesN::from_array(&env, &[3u8; 32]),
+    };
+
+    // Metadata can only be attached to oracles this orchestrator
+    // deployed, and only by the admin or an operator.
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client
+            .try_set_market_info(&admin, &stranger, &info)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::UnknownOracle
+    );
+    assert_eq!(client.market_info(&stranger), None);
+    assert_eq!(
+        client
+            .try_set_market_info(&Address::generate(&env), &stranger, &info)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::Unauthorized
+    );
+}
+
+#[test]
+fn validate_market_params_reports_violations() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000_000);
+    let (client, admin) = setup(&env);
+    let tbond = Asset::Other(symbol_short!("TBOND"));
+
+    // Register an oracle directly and slot it into the registry, since
+    // the test environment has no uploaded wasm to deploy from.
+    let oracle_id = env.register(RWAOracle, (&admin, Asset::Other(symbol_short!("USD")), 7u32, 300u32));
+    let oracle = RWAOracleClient::new(&env, &oracle_id);
+    oracle.add_assets(&vec![&env, tbond.clone()]);
+    oracle.set_asset_price(&admin, &tbond, &2_0000000, &999_000);
+    env.as_contract(&client.address, || {
+        storage::push_oracle(&env, &oracle_id);
+    });
+
+    assert_eq!(
+        client.validate_market_params(&oracle_id, &tbond, &15_000, &7),
+        vec![&env]
+    );
+
+    // Each failed check is reported, and several can fail at once.
+    assert_eq!(
+        client.validate_market_params(&oracle_id, &tbond, &9_000, &9),
+        vec![&env, symbol_short!("mcr_low"), symbol_short!("decimals")]
+    );
+    assert_eq!(
+        client.validate_market_params(&oracle_id, &Asset::Other(symbol_short!("GILT")), &15_000, &7),
+        vec![&env, symbol_short!("no_price")]
+    );
+
+    // An address outside the registry is reported without being called.
+    assert_eq!(
+        client.validate_market_params(&Address::generate(&env), &tbond, &15_000, &7),
+        vec![&env, symbol_short!("unknown")]
+    );
+
+    // A second deployed oracle serving the same asset is a collision.
+    let rival = env.register(RWAOracle, (&admin, Asset::Other(symbol_short!("USD")), 7u32, 300u32));
+    RWAOracleClient::new(&env, &rival).add_assets(&vec![&env, tbond.clone()]);
+    env.as_contract(&client.address, || {
+        storage::push_oracle(&env, &rival);
+    });
+    assert_eq!(
+        client.validate_market_params(&oracle_id, &tbond, &15_000, &7),
+        vec![&env, symbol_short!("sym_clash")]
+    );
+}
+
+#[test]
+fn fleet_upgrade_guards() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000_000);
+    let (client, _admin) = setup(&env);
+    let current = BytesN::from_array(&env, &[7u8; 32]);
+    let next = BytesN::from_array(&env, &[8u8; 32]);
+    client.set_oracle_wasm(&current);
+
+    // No-op upgrades are rejected outright.
+    assert_eq!(
+        client.try_upgrade_fleet(&current).err().unwrap().unwrap(),
+        Error::SameWasmHash
+    );
+    // A hash without a canary pass cannot roll out.
+    assert_eq!(
+        client.try_upgrade_fleet(&next).err().unwrap().unwrap(),
+        Error::CanaryNotPassed
+    );
+
+    client.set_canary_passed(&next);
+    assert_eq!(client.canary_passed(), Some(next.clone()));
+    // Empty fleet: the rollout succeeds and rotates the stored hash.
+    assert_eq!(client.upgrade_fleet(&next), 0);
+    assert_eq!(client.oracle_wasm(), Some(next.clone()));
+    // The canary flag was consumed by the rollout.
+    assert_eq!(client.canary_passed(), None);
+
+    // A follow-up inside the rate-limit window is rejected even with a
+    // fresh canary pass.
+    let after = BytesN::from_array(&env, &[9u8; 32]);
+    client.set_canary_passed(&after);
+    assert_eq!(
+        client.try_upgrade_fleet(&after).err().unwrap().unwrap(),
+        Error::UpgradeTooSoon
+    );
+    let interval = client.upgrade_interval();
+    env.ledger().with_mut(|l| l.timestamp += interval);
+    assert_eq!(client.upgrade_fleet(&after), 0);
+}
+
+#[test]
+fn admin_gates_mutations() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    // No auth mocking: admin-only entrypoints must fail.
+    assert!(client
+        .try_set_oracle_wasm(&BytesN::from_array(&env, &[7u8; 32]))
+        .is_err());
+    assert!(client.try_set_admin(&Address::generate(&env)).is_err());
+}
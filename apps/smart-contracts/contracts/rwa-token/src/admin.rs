@@ -0,0 +1,852 @@
+//! Admin surface: risk parameter tuning, regulatory controls, upgrade.
+
+use soroban_sdk::{
+    contractimpl, symbol_short, token::TokenClient, Address, BytesN, Env, String, Symbol, Vec,
+};
+
+use crate::cdp::update_borrow_index;
+use crate::errors::Error;
+use crate::events::{
+    EmergencyModeChanged, FeeSweep, LiquidationSplitChanged, Paused, RateTiersChanged,
+    RiskParamsChanged, RoleGranted, RoleRevoked, SchemaMigrated, SupplyFactorChanged, Unpaused,
+};
+use crate::storage::{
+    self, LiquidationSplit, McrBand, RWATokenStorage, RateTier, RevenueBreakdown, Role,
+};
+use crate::{RWAToken, RWATokenArgs, RWATokenClient};
+
+use oracle::RWAOracleClient;
+
+#[contractimpl]
+impl RWAToken {
+    /// Callable by the admin or a [`Role::RiskManager`].
+    pub fn set_min_collat_ratio(env: Env, caller: Address, ratio: u32) -> Result<(), Error> {
+        require_role(&env, &caller, &Role::RiskManager)?;
+        let old = storage::get_state(&env);
+        if ratio < 10_000 {
+            return Err(Error::InvalidConfiguration);
+        }
+        // Mirror of the guard in `set_critical_collat_ratio`: the
+        // recovery threshold must never sit below the entry MCR.
+        if old.critical_collat_ratio != 0 && ratio > old.critical_collat_ratio {
+            return Err(Error::InvalidConfiguration);
+        }
+        let mut state = old.clone();
+        state.min_collat_ratio = ratio;
+        storage::set_state(&env, &state);
+        emit_risk_params(&env, &old, &state);
+        Ok(())
+    }
+
+    /// Configures (or, with `None`, removes) the volatility-linked
+    /// entry MCR band; see [`McrBand`]. The floor must sit at or above
+    /// full collateralization and the window needs at least the two
+    /// records volatility is defined over. Liquidation thresholds are
+    /// untouched — only opening, borrowing, and withdrawing feel the
+    /// band. Callable by the admin or a [`Role::RiskManager`].
+    pub fn set_mcr_band(env: Env, caller: Address, band: Option<McrBand>) -> Result<(), Error> {
+        require_role(&env, &caller, &Role::RiskManager)?;
+        if let Some(band) = &band {
+            if band.floor < 10_000 || band.ceiling < band.floor || band.window < 2
+                || band.slope_bps == 0
+            {
+                return Err(Error::InvalidConfiguration);
+            }
+        }
+        storage::set_mcr_band(&env, &band);
+        Ok(())
+    }
+
+    pub fn mcr_band(env: Env) -> Option<McrBand> {
+        storage::get_mcr_band(&env)
+    }
+
+    /// The MCR a position must clear right now to open, borrow, or
+    /// withdraw: the static `min_collat_ratio` unless an [`McrBand`] is
+    /// configured, in which case the collateral feed's volatility sets
+    /// it within the band.
+    pub fn effective_mcr(env: Env) -> u32 {
+        let state = storage::get_state(&env);
+        crate::cdp::entry_mcr(&env, &state)
+    }
+
+    /// Sets the total collateralization ratio below which the system
+    /// enters recovery mode: `borrow_rwa` and `withdraw_collateral` are
+    /// blocked and the liquidation threshold widens from the MCR to the
+    /// live TCR. Must sit at or above the MCR; zero disables the
+    /// mechanism.
+    pub fn set_critical_collat_ratio(env: Env, ratio: u32) -> Result<(), Error> {
+        let old = require_admin(&env)?;
+        if ratio != 0 && ratio < old.min_collat_ratio {
+            return Err(Error::InvalidConfiguration);
+        }
+        let mut state = old.clone();
+        state.critical_collat_ratio = ratio;
+        storage::set_state(&env, &state);
+        emit_risk_params(&env, &old, &state);
+        Ok(())
+    }
+
+    pub fn critical_collat_ratio(env: Env) -> u32 {
+        storage::get_state(&env).critical_collat_ratio
+    }
+
+    /// Caps the total supply mintable against CDPs; zero means uncapped.
+    /// Lowering the ceiling below the current supply blocks new minting
+    /// without affecting existing positions.
+    pub fn set_debt_ceiling(env: Env, ceiling: i128) -> Result<(), Error> {
+        let old = require_admin(&env)?;
+        if ceiling < 0 {
+            return Err(Error::InvalidConfiguration);
+        }
+        let mut state = old.clone();
+        state.debt_ceiling = ceiling;
+        storage::set_state(&env, &state);
+        emit_risk_params(&env, &old, &state);
+        Ok(())
+    }
+
+    pub fn debt_ceiling(env: Env) -> i128 {
+        storage::get_state(&env).debt_ceiling
+    }
+
+    /// Sets the smallest principal a CDP may carry. Below this, the
+    /// keeper bounty and transaction fees exceed what a liquidation
+    /// recovers, so dust positions are refused at open and after partial
+    /// repayment. Zero disables the floor; CDPs already under a raised
+    /// floor are unaffected until they repay.
+    pub fn set_min_debt(env: Env, min_debt: i128) -> Result<(), Error> {
+        require_admin(&env)?;
+        if min_debt < 0 {
+            return Err(Error::InvalidConfiguration);
+        }
+        let mut state = storage::get_state(&env);
+        state.min_debt = min_debt;
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    pub fn min_debt(env: Env) -> i128 {
+        storage::get_state(&env).min_debt
+    }
+
+    /// Callable by the admin or a [`Role::RateSetter`].
+    pub fn set_interest_rate(env: Env, caller: Address, rate: u32) -> Result<(), Error> {
+        require_role(&env, &caller, &Role::RateSetter)?;
+        let old = storage::get_state(&env);
+        let mut state = old.clone();
+        // Bank elapsed time at the outgoing rate; the new one only
+        // prices seconds from here on.
+        update_borrow_index(&env, &mut state);
+        state.annual_interest_rate = rate;
+        storage::set_state(&env, &state);
+        emit_risk_params(&env, &old, &state);
+        Ok(())
+    }
+
+    /// Configures how seized liquidation collateral is split between
+    /// pool stakers, the liquidation caller, and the treasury. Shares
+    /// must sum to exactly 100%.
+    pub fn set_liquidation_split(env: Env, split: LiquidationSplit) -> Result<(), Error> {
+        require_admin(&env)?;
+        if split.pool_bps + split.caller_bps + split.treasury_bps != 10_000 {
+            return Err(Error::InvalidConfiguration);
+        }
+        let mut state = storage::get_state(&env);
+        state.liquidation_split = split.clone();
+        storage::set_state(&env, &state);
+        LiquidationSplitChanged {
+            split,
+            effective_ledger: env.ledger().sequence(),
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn liquidation_split(env: Env) -> LiquidationSplit {
+        storage::get_state(&env).liquidation_split
+    }
+
+    /// Sets the liquidation penalty: extra collateral, in basis points
+    /// of the proportional seizure, that a liquidated CDP gives up to
+    /// pool stakers. Capped at 100% of the proportional amount.
+    pub fn set_liquidation_penalty(env: Env, penalty_bps: u32) -> Result<(), Error> {
+        let old = require_admin(&env)?;
+        if penalty_bps > 10_000 {
+            return Err(Error::InvalidConfiguration);
+        }
+        let mut state = old.clone();
+        state.liquidation_penalty_bps = penalty_bps;
+        storage::set_state(&env, &state);
+        emit_risk_params(&env, &old, &state);
+        Ok(())
+    }
+
+    pub fn liquidation_penalty(env: Env) -> u32 {
+        storage::get_state(&env).liquidation_penalty_bps
+    }
+
+    /// Replaces the piecewise interest schedule. Tiers must start at a
+    /// zero floor and ascend strictly; an empty vector reverts to the
+    /// flat `annual_interest_rate`.
+    pub fn set_rate_tiers(env: Env, tiers: Vec<RateTier>) -> Result<(), Error> {
+        require_admin(&env)?;
+        if let Some(first) = tiers.first() {
+            if first.floor != 0 {
+                return Err(Error::InvalidConfiguration);
+            }
+        }
+        for i in 1..tiers.len() {
+            if tiers.get_unchecked(i).floor <= tiers.get_unchecked(i - 1).floor {
+                return Err(Error::InvalidConfiguration);
+            }
+        }
+        let mut state = storage::get_state(&env);
+        update_borrow_index(&env, &mut state);
+        state.rate_tiers = tiers.clone();
+        storage::set_state(&env, &state);
+        RateTiersChanged {
+            tiers,
+            effective_ledger: env.ledger().sequence(),
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn rate_tiers(env: Env) -> Vec<RateTier> {
+        storage::get_state(&env).rate_tiers
+    }
+
+    /// Sets the oracle silence (seconds) after which the pegged feed is
+    /// treated as down and interest accrual pauses, so borrowers are not
+    /// charged for outage windows they could not have repaid through.
+    /// Zero disables the policy.
+    pub fn set_outage_threshold(env: Env, threshold: u64) -> Result<(), Error> {
+        require_admin(&env)?;
+        let mut state = storage::get_state(&env);
+        state.outage_threshold = threshold;
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    pub fn outage_threshold(env: Env) -> u64 {
+        storage::get_state(&env).outage_threshold
+    }
+
+    /// Sets the per-hour staleness haircut (basis points) applied to the
+    /// collateral price in ratio checks, so solvency protection keeps
+    /// running on the last known price during a feed outage instead of
+    /// halting. Zero disables the haircut.
+    pub fn set_stale_haircut(env: Env, bps_per_hour: u32) -> Result<(), Error> {
+        require_admin(&env)?;
+        if bps_per_hour > 10_000 {
+            return Err(Error::InvalidConfiguration);
+        }
+        let mut state = storage::get_state(&env);
+        state.stale_haircut_bps = bps_per_hour;
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    pub fn stale_haircut(env: Env) -> u32 {
+        storage::get_state(&env).stale_haircut_bps
+    }
+
+    /// Sets the rebasing supply factor so reported face values track the
+    /// underlying instrument as it amortizes. On-chain units are never
+    /// rescaled: balances, transfers, and CDP debt keep their unscaled
+    /// amounts, and only the `scaled_*` views change.
+    pub fn set_supply_factor(env: Env, factor: i128) -> Result<(), Error> {
+        require_admin(&env)?;
+        if factor <= 0 {
+            return Err(Error::InvalidConfiguration);
+        }
+        let mut state = storage::get_state(&env);
+        let old_factor = state.supply_factor;
+        state.supply_factor = factor;
+        storage::set_state(&env, &state);
+        SupplyFactorChanged {
+            old_factor,
+            new_factor: factor,
+            effective_ledger: env.ledger().sequence(),
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn supply_factor(env: Env) -> i128 {
+        storage::get_state(&env).supply_factor
+    }
+
+    pub fn set_stake_fee(env: Env, fee: i128) -> Result<(), Error> {
+        let old = require_admin(&env)?;
+        // The fee must at least cover the refund reserved at stake time.
+        if fee < storage::UNSTAKE_RETURN {
+            return Err(Error::InvalidConfiguration);
+        }
+        let mut state = old.clone();
+        state.stake_fee = fee;
+        storage::set_state(&env, &state);
+        emit_risk_params(&env, &old, &state);
+        Ok(())
+    }
+
+    /// Sets the flat collateral bounty paid from swept-able fees to
+    /// whoever freezes or liquidates a CDP. Zero disables keeper
+    /// rewards.
+    pub fn set_keeper_bounty(env: Env, bounty: i128) -> Result<(), Error> {
+        require_admin(&env)?;
+        if bounty < 0 {
+            return Err(Error::InvalidConfiguration);
+        }
+        let mut state = storage::get_state(&env);
+        state.keeper_bounty = bounty;
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    pub fn keeper_bounty(env: Env) -> i128 {
+        storage::get_state(&env).keeper_bounty
+    }
+
+    /// Sets the smallest stake the pool accepts, which is also the
+    /// smallest residual a partial withdrawal may leave. Zero disables
+    /// the check. Existing positions below a raised minimum are
+    /// unaffected until they deposit or withdraw.
+    pub fn set_min_stake(env: Env, min_stake: i128) -> Result<(), Error> {
+        require_admin(&env)?;
+        if min_stake < 0 {
+            return Err(Error::InvalidConfiguration);
+        }
+        let mut state = storage::get_state(&env);
+        state.min_stake = min_stake;
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    pub fn min_stake(env: Env) -> i128 {
+        storage::get_state(&env).min_stake
+    }
+
+    /// Configures keeper-driven reward settlement: once `delay` seconds
+    /// have passed since rewards last accrued, anyone may settle
+    /// stakers' pending rewards and keep `tip_bps` of each payout. A
+    /// zero delay disables keeper claims entirely.
+    pub fn set_claim_policy(env: Env, delay: u64, tip_bps: u32) -> Result<(), Error> {
+        require_admin(&env)?;
+        if tip_bps > 10_000 {
+            return Err(Error::InvalidConfiguration);
+        }
+        let mut state = storage::get_state(&env);
+        state.claim_delay = delay;
+        state.claim_tip_bps = tip_bps;
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    pub fn claim_policy(env: Env) -> (u64, u32) {
+        let state = storage::get_state(&env);
+        (state.claim_delay, state.claim_tip_bps)
+    }
+
+    /// Callable by the admin or a [`Role::RiskManager`].
+    pub fn set_oracle(env: Env, caller: Address, oracle: Address) -> Result<(), Error> {
+        require_role(&env, &caller, &Role::RiskManager)?;
+        let old = storage::get_state(&env);
+        let mut state = old.clone();
+        // A replacement oracle brings its own decimals expectation.
+        state.oracle_decimals = RWAOracleClient::new(&env, &oracle).decimals();
+        state.oracle = oracle;
+        storage::set_state(&env, &state);
+        emit_risk_params(&env, &old, &state);
+        Ok(())
+    }
+
+    /// Callable by the admin or a [`Role::RiskManager`].
+    pub fn set_collateral_oracle(env: Env, caller: Address, oracle: Address) -> Result<(), Error> {
+        require_role(&env, &caller, &Role::RiskManager)?;
+        let old = storage::get_state(&env);
+        let mut state = old.clone();
+        state.collateral_oracle_decimals = RWAOracleClient::new(&env, &oracle).decimals();
+        state.collateral_oracle = oracle;
+        storage::set_state(&env, &state);
+        emit_risk_params(&env, &old, &state);
+        Ok(())
+    }
+
+    /// Accepts the decimals both oracles currently report as the new
+    /// expectation. Until a decimals change is accepted, every price
+    /// read fails with `FeedDecimalsChanged`, halting risk operations
+    /// whose math would otherwise silently rescale.
+    pub fn accept_feed_decimals(env: Env) -> Result<(), Error> {
+        require_admin(&env)?;
+        let mut state = storage::get_state(&env);
+        state.oracle_decimals = RWAOracleClient::new(&env, &state.oracle).decimals();
+        state.collateral_oracle_decimals =
+            RWAOracleClient::new(&env, &state.collateral_oracle).decimals();
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    /// The feed decimals currently expected of `(oracle,
+    /// collateral_oracle)`.
+    pub fn expected_feed_decimals(env: Env) -> (u32, u32) {
+        let state = storage::get_state(&env);
+        (state.oracle_decimals, state.collateral_oracle_decimals)
+    }
+
+    /// Routes `share_bps` of every interest payment to `account`,
+    /// typically the oracle's feeder bond/reward pool, so the markets
+    /// consuming a price feed fund its upkeep. A `None` account or a
+    /// zero share disables streaming.
+    pub fn set_data_fee(
+        env: Env,
+        account: Option<Address>,
+        share_bps: u32,
+    ) -> Result<(), Error> {
+        require_admin(&env)?;
+        if share_bps > 10_000 {
+            return Err(Error::InvalidConfiguration);
+        }
+        storage::set_data_fee_account(&env, &account);
+        let mut state = storage::get_state(&env);
+        state.data_fee_bps = share_bps;
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    /// The data-fee destination and share: `(account, share_bps)`.
+    pub fn data_fee(env: Env) -> (Option<Address>, u32) {
+        (
+            storage::get_data_fee_account(&env),
+            storage::get_state(&env).data_fee_bps,
+        )
+    }
+
+    /// Sets the flash mint fee in basis points of the minted amount.
+    /// The fee is burned with the repayment rather than collected, so
+    /// heavy flash volume tightens the supply.
+    pub fn set_flash_mint_fee(env: Env, fee_bps: u32) -> Result<(), Error> {
+        require_admin(&env)?;
+        if fee_bps > 10_000 {
+            return Err(Error::InvalidConfiguration);
+        }
+        let mut state = storage::get_state(&env);
+        state.flash_mint_fee_bps = fee_bps;
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    pub fn flash_mint_fee(env: Env) -> u32 {
+        storage::get_state(&env).flash_mint_fee_bps
+    }
+
+    /// Sets the origination fee charged up front when CDP debt is
+    /// minted, in basis points of the RWA amount. The fee is collected
+    /// in collateral at face value, so the treasury earns on churn even
+    /// when rates are at zero.
+    pub fn set_origination_fee(env: Env, fee_bps: u32) -> Result<(), Error> {
+        require_admin(&env)?;
+        if fee_bps > 10_000 {
+            return Err(Error::InvalidConfiguration);
+        }
+        let mut state = storage::get_state(&env);
+        state.origination_fee_bps = fee_bps;
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    pub fn origination_fee(env: Env) -> u32 {
+        storage::get_state(&env).origination_fee_bps
+    }
+
+    /// Configures the redemption fee: a flat floor in basis points of
+    /// the collateral paid out, plus a base rate that spikes with
+    /// redemption volume and decays linearly to zero over
+    /// `decay_secs`. Passing 0 for `decay_secs` switches the base rate
+    /// off and any accumulated spike stops being charged.
+    pub fn set_redemption_fee(env: Env, floor_bps: u32, decay_secs: u64) -> Result<(), Error> {
+        require_admin(&env)?;
+        if floor_bps > 10_000 {
+            return Err(Error::InvalidConfiguration);
+        }
+        let mut state = storage::get_state(&env);
+        state.redemption_fee_floor_bps = floor_bps;
+        state.redemption_decay_secs = decay_secs;
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    pub fn redemption_fee(env: Env) -> (u32, u64) {
+        let state = storage::get_state(&env);
+        (state.redemption_fee_floor_bps, state.redemption_decay_secs)
+    }
+
+    /// The fee rate a redemption executed right now would pay, in basis
+    /// points: the floor plus whatever is left of the decaying base
+    /// rate.
+    pub fn redemption_rate(env: Env) -> u32 {
+        let state = storage::get_state(&env);
+        crate::cdp::redemption_rate_now(&env, &state)
+    }
+
+    /// Configures (or clears) the receipt-NFT contract that is notified
+    /// when CDPs open and close.
+    pub fn set_receipt_hook(env: Env, hook: Option<Address>) -> Result<(), Error> {
+        require_admin(&env)?;
+        storage::set_receipt_hook(&env, &hook);
+        Ok(())
+    }
+
+    pub fn receipt_hook(env: Env) -> Option<Address> {
+        storage::get_receipt_hook(&env)
+    }
+
+    /// Halts every state-changing entrypoint until `unpause`, so an
+    /// incident (an oracle exploit, say) can be contained in one
+    /// transaction instead of waiting on an upgrade. Only the pool's
+    /// `emergency_withdraw` stays open, so a pause never hard-locks
+    /// user funds. Callable by the admin or the configured pauser.
+    pub fn pause(env: Env, caller: Address) -> Result<(), Error> {
+        require_admin_or_pauser(&env, &caller)?;
+        storage::set_paused(&env, true);
+        Paused { by: caller }.publish(&env);
+        Ok(())
+    }
+
+    /// Resumes normal operation after a pause.
+    pub fn unpause(env: Env, caller: Address) -> Result<(), Error> {
+        require_admin_or_pauser(&env, &caller)?;
+        storage::set_paused(&env, false);
+        Unpaused { by: caller }.publish(&env);
+        Ok(())
+    }
+
+    pub fn paused(env: Env) -> bool {
+        storage::is_paused(&env)
+    }
+
+    /// Grants (or revokes) a dedicated pauser able to halt the contract
+    /// without the full admin key, so incident response does not hinge
+    /// on the cold key being reachable.
+    pub fn set_pauser(env: Env, pauser: Option<Address>) -> Result<(), Error> {
+        require_admin(&env)?;
+        storage::set_pauser(&env, &pauser);
+        Ok(())
+    }
+
+    pub fn pauser(env: Env) -> Option<Address> {
+        storage::get_pauser(&env)
+    }
+
+    /// Flips emergency mode. While enabled, pool stakers may exit with
+    /// their compounded principal only via `emergency_withdraw`, so an
+    /// operational pause or shutdown can never hard-lock staked funds.
+    pub fn set_emergency_mode(env: Env, enabled: bool) -> Result<(), Error> {
+        require_admin(&env)?;
+        storage::set_emergency_mode(&env, enabled);
+        EmergencyModeChanged { enabled }.publish(&env);
+        Ok(())
+    }
+
+    pub fn emergency_mode(env: Env) -> bool {
+        storage::emergency_mode(&env)
+    }
+
+    /// Toggles `OpMetered` diagnostics from the heaviest entrypoints.
+    /// Meant for testnet resource profiling; off by default.
+    pub fn set_metering(env: Env, enabled: bool) -> Result<(), Error> {
+        require_admin(&env)?;
+        storage::set_metering(&env, enabled);
+        Ok(())
+    }
+
+    pub fn metering(env: Env) -> bool {
+        storage::metering_enabled(&env)
+    }
+
+    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        let old = require_admin(&env)?;
+        let mut state = old;
+        state.admin = new_admin;
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    pub fn admin(env: Env) -> Address {
+        storage::get_state(&env).admin
+    }
+
+    pub fn fees_collected(env: Env) -> i128 {
+        storage::get_state(&env).fees_collected
+    }
+
+    /// Configures (or clears) the treasury address sweeps pay out to.
+    pub fn set_treasury(env: Env, treasury: Option<Address>) -> Result<(), Error> {
+        require_admin(&env)?;
+        let mut state = storage::get_state(&env);
+        state.treasury = treasury;
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    pub fn treasury(env: Env) -> Option<Address> {
+        storage::get_state(&env).treasury
+    }
+
+    /// Sends collected fees to the treasury, refusing to dip into the
+    /// collateral reserved for unstake refunds.
+    pub fn sweep_fees(env: Env, amount: i128) -> Result<(), Error> {
+        let old = require_admin(&env)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let mut state = old;
+        if amount > state.fees_collected - state.unstake_liability {
+            return Err(Error::InsufficientBalance);
+        }
+        state.fees_collected -= amount;
+        pay_treasury(&env, &state, symbol_short!("fees"), amount)?;
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    /// Sends collected interest to the treasury.
+    pub fn sweep_interest(env: Env, amount: i128) -> Result<(), Error> {
+        let old = require_admin(&env)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let mut state = old;
+        if amount > state.interest_collected {
+            return Err(Error::InsufficientBalance);
+        }
+        state.interest_collected -= amount;
+        pay_treasury(&env, &state, symbol_short!("interest"), amount)?;
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    /// Operator view of the collateral buffers: `(fees_collected,
+    /// interest_collected, interest_current_epoch, unstake_liability)`.
+    /// The last element is the portion of fees reserved for unstake
+    /// refunds and must stay covered when sweeping.
+    pub fn fee_stats(env: Env) -> (i128, i128, i128, i128) {
+        let state = storage::get_state(&env);
+        (
+            state.fees_collected,
+            state.interest_collected,
+            state.interest_current_epoch,
+            state.unstake_liability,
+        )
+    }
+
+    /// Lifetime revenue by source. Monotonic, unlike the sweepable cash
+    /// buckets in `fee_stats`.
+    pub fn revenue_breakdown(env: Env) -> RevenueBreakdown {
+        storage::get_state(&env).revenue
+    }
+
+    pub fn min_collat_ratio(env: Env) -> u32 {
+        storage::get_state(&env).min_collat_ratio
+    }
+
+    pub fn interest_rate(env: Env) -> u32 {
+        storage::get_state(&env).annual_interest_rate
+    }
+
+    pub fn version(_env: Env) -> u32 {
+        1
+    }
+
+    /// Which build is actually running: `(crate_version, git_sha,
+    /// build_timestamp, soroban_sdk_version)`. Everything but the crate
+    /// version is stamped in by the release pipeline through the
+    /// `GIT_SHA`, `BUILD_TIMESTAMP`, and `SOROBAN_SDK_VERSION` env
+    /// vars; local builds read "unknown".
+    pub fn build_info(env: Env) -> (String, String, String, String) {
+        (
+            String::from_str(&env, env!("CARGO_PKG_VERSION")),
+            String::from_str(&env, option_env!("GIT_SHA").unwrap_or("unknown")),
+            String::from_str(&env, option_env!("BUILD_TIMESTAMP").unwrap_or("unknown")),
+            String::from_str(&env, option_env!("SOROBAN_SDK_VERSION").unwrap_or("unknown")),
+        )
+    }
+
+    /// Replaces the contract code. Callable by the admin or a
+    /// [`Role::Owner`], so the upgrade path can be delegated to e.g. a
+    /// timelock contract while parameter tuning runs off other keys.
+    pub fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        require_role(&env, &caller, &Role::Owner)?;
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        Ok(())
+    }
+
+    /// Converts storage written by an older wasm to the current layout,
+    /// one version step at a time, stamping and publishing each step.
+    /// Meant to run right after `upgrade` in the same transaction set,
+    /// before anything else touches state. Idempotent — an
+    /// already-current deployment returns its version unchanged — so
+    /// deployment pipelines can call it unconditionally. Gated like
+    /// `upgrade` itself.
+    pub fn migrate(env: Env, caller: Address) -> Result<u32, Error> {
+        require_role(&env, &caller, &Role::Owner)?;
+        let mut version = storage::get_schema_version(&env);
+        while version < storage::SCHEMA_VERSION {
+            let from = version;
+            match version {
+                // v1 -> v2 introduced the version key itself; the state
+                // layout is unchanged, so stamping is the whole step.
+                // Future layout changes add an arm here converting the
+                // old shape before any code reads it.
+                1 => {}
+                _ => return Err(Error::InvalidConfiguration),
+            }
+            version += 1;
+            storage::set_schema_version(&env, version);
+            SchemaMigrated { from, to: version }.publish(&env);
+        }
+        Ok(version)
+    }
+
+    /// The storage layout version currently on-chain; `migrate` brings
+    /// it up to what this build writes.
+    pub fn schema_version(env: Env) -> u32 {
+        storage::get_schema_version(&env)
+    }
+
+    /// Extends the TTL of `who`'s persistent entries (balance, CDP,
+    /// pool position, savings) plus the contract instance, so dormant
+    /// accounts do not archive. Callable by anyone; bumping a fresh
+    /// entry or a missing one does nothing.
+    pub fn bump(env: Env, who: Address) {
+        storage::bump_account(&env, &who);
+        env.storage().instance().extend_ttl(
+            storage::PERSISTENT_LIFETIME_THRESHOLD,
+            storage::PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
+    /// `bump` over a list, for custodians maintaining many accounts in
+    /// one call.
+    pub fn bump_many(env: Env, list: Vec<Address>) {
+        for who in list.iter() {
+            storage::bump_account(&env, &who);
+        }
+        env.storage().instance().extend_ttl(
+            storage::PERSISTENT_LIFETIME_THRESHOLD,
+            storage::PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
+    /// Grants a permission role. Admin only.
+    pub fn grant_role(env: Env, role: Role, who: Address) -> Result<(), Error> {
+        require_admin(&env)?;
+        storage::set_role(&env, &role, &who, true);
+        RoleGranted { role, address: who }.publish(&env);
+        Ok(())
+    }
+
+    /// Revokes a permission role. Admin only.
+    pub fn revoke_role(env: Env, role: Role, who: Address) -> Result<(), Error> {
+        require_admin(&env)?;
+        storage::set_role(&env, &role, &who, false);
+        RoleRevoked { role, address: who }.publish(&env);
+        Ok(())
+    }
+
+    pub fn has_role(env: Env, role: Role, who: Address) -> bool {
+        storage::has_role(&env, &role, &who)
+    }
+}
+
+pub(crate) fn require_admin(env: &Env) -> Result<RWATokenStorage, Error> {
+    let state = storage::get_state(env);
+    state.admin.require_auth();
+    Ok(state)
+}
+
+/// Authenticates `caller` as the admin, the configured pauser, or a
+/// [`Role::Pauser`] holder.
+fn require_admin_or_pauser(env: &Env, caller: &Address) -> Result<(), Error> {
+    caller.require_auth();
+    let state = storage::get_state(env);
+    if *caller == state.admin
+        || storage::get_pauser(env).as_ref() == Some(caller)
+        || storage::has_role(env, &Role::Pauser, caller)
+    {
+        Ok(())
+    } else {
+        Err(Error::Unauthorized)
+    }
+}
+
+/// Authenticates `caller` and checks they hold `role`. The admin passes
+/// every role check, so a deployment that never grants roles keeps
+/// working off the single admin key.
+pub(crate) fn require_role(env: &Env, caller: &Address, role: &Role) -> Result<(), Error> {
+    caller.require_auth();
+    let state = storage::get_state(env);
+    if *caller == state.admin || storage::has_role(env, role, caller) {
+        Ok(())
+    } else {
+        Err(Error::Unauthorized)
+    }
+}
+
+/// Transfers `amount` of collateral to the configured treasury and
+/// publishes the sweep; fails if no treasury is set.
+fn pay_treasury(
+    env: &Env,
+    state: &RWATokenStorage,
+    bucket: Symbol,
+    amount: i128,
+) -> Result<(), Error> {
+    let treasury = match &state.treasury {
+        Some(treasury) => treasury.clone(),
+        None => return Err(Error::InvalidConfiguration),
+    };
+    TokenClient::new(env, &state.collateral_sac).transfer(
+        &env.current_contract_address(),
+        &treasury,
+        &amount,
+    );
+    FeeSweep {
+        treasury,
+        bucket,
+        amount,
+    }
+    .publish(env);
+    Ok(())
+}
+
+/// Rejects state-changing calls while the contract is paused.
+pub(crate) fn require_not_paused(env: &Env) -> Result<(), Error> {
+    if storage::is_paused(env) {
+        return Err(Error::ContractPaused);
+    }
+    Ok(())
+}
+
+fn emit_risk_params(env: &Env, old: &RWATokenStorage, new: &RWATokenStorage) {
+    RiskParamsChanged {
+        old_min_collat_ratio: old.min_collat_ratio,
+        new_min_collat_ratio: new.min_collat_ratio,
+        old_critical_collat_ratio: old.critical_collat_ratio,
+        new_critical_collat_ratio: new.critical_collat_ratio,
+        old_debt_ceiling: old.debt_ceiling,
+        new_debt_ceiling: new.debt_ceiling,
+        old_interest_rate: old.annual_interest_rate,
+        new_interest_rate: new.annual_interest_rate,
+        old_liquidation_penalty: old.liquidation_penalty_bps,
+        new_liquidation_penalty: new.liquidation_penalty_bps,
+        old_stake_fee: old.stake_fee,
+        new_stake_fee: new.stake_fee,
+        old_oracle: old.oracle.clone(),
+        new_oracle: new.oracle.clone(),
+        old_collateral_oracle: old.collateral_oracle.clone(),
+        new_collateral_oracle: new.collateral_oracle.clone(),
+        effective_ledger: env.ledger().sequence(),
+    }
+    .publish(env);
+}
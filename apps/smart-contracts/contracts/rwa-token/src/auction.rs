@@ -0,0 +1,186 @@
+//! Dutch auction fallback for frozen CDPs the stability pool cannot
+//! absorb: the collateral is offered at an ask that decays per ledger,
+//! and bidders pay in RWA tokens that are burned against the debt, so
+//! bad positions clear even without pool depth.
+
+use soroban_sdk::{contractimpl, symbol_short, token::TokenClient, Address, Env};
+
+use crate::admin::require_not_paused;
+use crate::cdp::notify_receipt_hook;
+use crate::errors::Error;
+use crate::events::{AuctionBid, AuctionSettled, AuctionStarted, Burned};
+use crate::rounding::mul_div_ceil;
+use crate::storage::{
+    self, Auction, CDPStatus, AUCTION_DECAY_LEDGERS, AUCTION_START_BPS, BPS,
+};
+use crate::token;
+use crate::{RWAToken, RWATokenArgs, RWATokenClient};
+
+#[contractimpl]
+impl RWAToken {
+    /// Opens a Dutch auction over a frozen CDP's collateral, callable by
+    /// anyone. Only allowed while the stability pool is empty, since
+    /// pool liquidation gives stakers first claim on seized collateral.
+    pub fn start_auction(env: Env, caller: Address, lender: Address) -> Result<(), Error> {
+        caller.require_auth();
+        require_not_paused(&env)?;
+        let cdp = match storage::get_cdp(&env, &lender) {
+            Some(cdp) => cdp,
+            None => return Err(Error::CDPNotFound),
+        };
+        if cdp.status != CDPStatus::Frozen {
+            return Err(Error::CDPNotFrozen);
+        }
+        if storage::get_auction(&env, &lender).is_some() {
+            return Err(Error::AuctionActive);
+        }
+        let state = storage::get_state(&env);
+        if state.total_rwa_deposited > 0 {
+            return Err(Error::PoolNotEmpty);
+        }
+        let auction = Auction {
+            lender: lender.clone(),
+            collateral_lot: cdp.collateral_deposited,
+            debt: cdp.asset_lent + cdp.accrued_interest,
+            start_ledger: env.ledger().sequence(),
+        };
+        storage::set_auction(&env, &auction);
+        AuctionStarted {
+            lender,
+            collateral_lot: auction.collateral_lot,
+            debt: auction.debt,
+            start_ledger: auction.start_ledger,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Buys `collateral_amount` from the running auction at the current
+    /// decayed ask, paying in RWA that is burned against the CDP's debt.
+    /// Settles the auction once the debt is covered (leftover collateral
+    /// returns to the lender) or the lot sells out (the uncovered
+    /// remainder is written off). Returns the RWA paid.
+    pub fn bid(
+        env: Env,
+        bidder: Address,
+        lender: Address,
+        collateral_amount: i128,
+    ) -> Result<i128, Error> {
+        bidder.require_auth();
+        require_not_paused(&env)?;
+        if collateral_amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let mut auction = storage::get_auction(&env, &lender).ok_or(Error::AuctionNotFound)?;
+        if collateral_amount > auction.collateral_lot {
+            return Err(Error::InvalidAmount);
+        }
+        let mut cdp = match storage::get_cdp(&env, &lender) {
+            Some(cdp) => cdp,
+            None => return Err(Error::CDPNotFound),
+        };
+        let mut state = storage::get_state(&env);
+        // Pro-rata share of the decayed ask for the remaining lot.
+        // Rounds up, as an amount owed to the protocol.
+        let cost = mul_div_ceil(
+            auction.debt * current_ask_bps(&env, &auction),
+            collateral_amount,
+            BPS * auction.collateral_lot,
+        );
+        if cost > 0 {
+            // Burn inline against the held state: `burn_internal` saves
+            // its own copy, which the `set_state` below would clobber.
+            token::spend_balance(&env, &bidder, cost)?;
+            state.total_supply -= cost;
+            Burned {
+                from: bidder.clone(),
+                amount: cost,
+            }
+            .publish(&env);
+        }
+        TokenClient::new(&env, &state.collateral_sac).transfer(
+            &env.current_contract_address(),
+            &bidder,
+            &collateral_amount,
+        );
+        auction.collateral_lot -= collateral_amount;
+        auction.debt -= cost.min(auction.debt);
+        cdp.collateral_deposited -= collateral_amount;
+        state.total_cdp_collateral -= collateral_amount;
+        let interest_paid = cost.min(cdp.accrued_interest);
+        cdp.accrued_interest -= interest_paid;
+        let principal_paid = (cost - interest_paid).min(cdp.asset_lent);
+        cdp.asset_lent -= principal_paid;
+        state.total_cdp_debt -= principal_paid;
+        AuctionBid {
+            bidder,
+            lender: lender.clone(),
+            collateral_sold: collateral_amount,
+            rwa_paid: cost,
+        }
+        .publish(&env);
+
+        if auction.debt == 0 || auction.collateral_lot == 0 {
+            let returned = cdp.collateral_deposited;
+            if returned > 0 {
+                TokenClient::new(&env, &state.collateral_sac).transfer(
+                    &env.current_contract_address(),
+                    &lender,
+                    &returned,
+                );
+                state.total_cdp_collateral -= returned;
+                cdp.collateral_deposited = 0;
+            }
+            // An exhausted lot writes the uncovered debt off: no burn
+            // backs it, so the loss is borne by the peg rather than any
+            // single account.
+            let shortfall = cdp.asset_lent + cdp.accrued_interest;
+            state.total_cdp_debt -= cdp.asset_lent;
+            cdp.asset_lent = 0;
+            cdp.accrued_interest = 0;
+            cdp.status = CDPStatus::Closed;
+            storage::remove_auction(&env, &lender);
+            AuctionSettled {
+                lender: lender.clone(),
+                debt_shortfall: shortfall,
+                collateral_returned: returned,
+            }
+            .publish(&env);
+        } else {
+            storage::set_auction(&env, &auction);
+        }
+        storage::set_state(&env, &state);
+        storage::set_cdp(&env, &cdp);
+        if cdp.status == CDPStatus::Closed {
+            notify_receipt_hook(&env, symbol_short!("burn_rcpt"), &lender);
+        }
+        Ok(cost)
+    }
+
+    // --- Views ----------------------------------------------------------
+
+    pub fn get_auction(env: Env, lender: Address) -> Option<Auction> {
+        storage::get_auction(&env, &lender)
+    }
+
+    /// Current RWA cost of the auction's full remaining lot.
+    pub fn auction_quote(env: Env, lender: Address) -> Result<i128, Error> {
+        let auction = storage::get_auction(&env, &lender).ok_or(Error::AuctionNotFound)?;
+        Ok(mul_div_ceil(
+            auction.debt,
+            current_ask_bps(&env, &auction),
+            BPS,
+        ))
+    }
+}
+
+/// The lot's ask at the current ledger, in basis points of the remaining
+/// debt: starts at [`AUCTION_START_BPS`] and decays linearly to zero
+/// over [`AUCTION_DECAY_LEDGERS`].
+fn current_ask_bps(env: &Env, auction: &Auction) -> i128 {
+    let elapsed = env.ledger().sequence().saturating_sub(auction.start_ledger);
+    if elapsed >= AUCTION_DECAY_LEDGERS {
+        return 0;
+    }
+    AUCTION_START_BPS * (AUCTION_DECAY_LEDGERS - elapsed) as i128 / AUCTION_DECAY_LEDGERS as i128
+}
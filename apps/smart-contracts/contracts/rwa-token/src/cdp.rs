@@ -0,0 +1,1712 @@
+//! Collateralized debt positions: collateral locked against minted
+//! RWA tokens, with per-second compound interest and permissionless
+//! liquidation.
+
+use soroban_sdk::{
+    contractimpl, symbol_short, token::TokenClient, Address, Env, IntoVal, MuxedAddress, Symbol,
+    Val, Vec,
+};
+
+use crate::admin::require_not_paused;
+use crate::errors::Error;
+use crate::events::{
+    self, Burned, DataFeeStreamed, Deleveraged, InsolventSwept, InterestPaid, KeeperReward,
+    Redeemed,
+    RevenueAccrued, StdBurn,
+};
+use crate::rounding::{mul_div_ceil, mul_div_floor, pow_ceil};
+use crate::storage::{
+    self, ActionPreview, CDPStatus, HealthReport, RWATokenStorage, RevenueSource, BPS, CDP,
+    FACTOR_SCALE, INTEREST_SCALE, SECONDS_PER_YEAR,
+};
+use crate::token;
+use crate::{RWAToken, RWATokenArgs, RWATokenClient};
+
+use oracle::{Asset, RWAOracleClient};
+
+#[contractimpl]
+impl RWAToken {
+    /// Opens a CDP for `lender`, locking `collateral_amount` of collateral and
+    /// minting `rwa_amount` of tokens against it. `deadline_ledger`
+    /// bounds how long the signed transaction stays executable, so a
+    /// submission delayed through a price swing fails instead of filling
+    /// at terms the user never saw.
+    pub fn open_cdp(
+        env: Env,
+        lender: Address,
+        collateral_amount: i128,
+        rwa_amount: i128,
+        deadline_ledger: Option<u32>,
+    ) -> Result<(), Error> {
+        lender.require_auth();
+        require_not_paused(&env)?;
+        #[cfg(feature = "compliance")]
+        crate::compliance::require_kyc(&env, &lender)?;
+        #[cfg(feature = "compliance")]
+        crate::compliance::require_not_frozen(&env, &lender)?;
+        check_deadline(&env, deadline_ledger)?;
+        if collateral_amount <= 0 || rwa_amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let existing = storage::get_cdp(&env, &lender);
+        match &existing {
+            Some(cdp) if cdp.status != CDPStatus::Closed => return Err(Error::CDPAlreadyExists),
+            _ => {}
+        }
+        let state = storage::get_state(&env);
+        if rwa_amount < state.min_debt {
+            return Err(Error::DebtBelowMinimum);
+        }
+        let cdp = CDP {
+            lender: lender.clone(),
+            collateral_deposited: collateral_amount,
+            asset_lent: rwa_amount,
+            accrued_interest: 0,
+            last_interest_time: env.ledger().timestamp(),
+            interest_index: current_borrow_index(&env, &state),
+            status: CDPStatus::Open,
+        };
+        let ratio = collateralization_ratio(&env, &state, &cdp)?;
+        if ratio < entry_mcr(&env, &state) {
+            return Err(Error::CollateralRatioBelowMinimum);
+        }
+        check_debt_ceiling(&state, rwa_amount)?;
+        let fee = origination_fee_due(&env, &state, rwa_amount)?;
+        TokenClient::new(&env, &state.collateral_sac).transfer(
+            &lender,
+            env.current_contract_address(),
+            &collateral_amount,
+        );
+        token::mint_internal(&env, &lender, rwa_amount)?;
+        // Reload: `mint_internal` saved its own copy of the state.
+        let mut state = storage::get_state(&env);
+        state.total_cdp_debt += rwa_amount;
+        state.total_cdp_collateral += collateral_amount;
+        charge_origination_fee(&env, &mut state, &lender, fee);
+        storage::set_state(&env, &state);
+        if existing.is_none() {
+            storage::push_cdp_lender(&env, &lender);
+        }
+        storage::set_cdp(&env, &cdp);
+        notify_receipt_hook(&env, symbol_short!("mint_rcpt"), &lender);
+        Ok(())
+    }
+
+    /// Adds collateral to the caller's open CDP.
+    pub fn add_collateral(env: Env, lender: Address, amount: i128) -> Result<(), Error> {
+        lender.require_auth();
+        require_not_paused(&env)?;
+        add_collateral_internal(&env, &lender, &lender, amount)
+    }
+
+    /// Withdraws collateral, provided the CDP stays at or above the MCR.
+    /// Optionally bounded by `deadline_ledger` like `open_cdp`. `lender`
+    /// may be a muxed address: the underlying account signs and owns the
+    /// CDP, and the mux id rides along on the payout so custodians can
+    /// credit the right sub-account.
+    pub fn withdraw_collateral(
+        env: Env,
+        lender: MuxedAddress,
+        amount: i128,
+        deadline_ledger: Option<u32>,
+    ) -> Result<(), Error> {
+        let payout = lender;
+        let lender = payout.address();
+        lender.require_auth();
+        require_not_paused(&env)?;
+        #[cfg(feature = "compliance")]
+        crate::compliance::require_not_frozen(&env, &lender)?;
+        check_deadline(&env, deadline_ledger)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let mut cdp = require_open_cdp(&env, &lender)?;
+        if amount > cdp.collateral_deposited {
+            return Err(Error::InvalidAmount);
+        }
+        let mut state = storage::get_state(&env);
+        if in_recovery_mode(&env, &state)? {
+            return Err(Error::RecoveryModeActive);
+        }
+        let fresh = accrue_interest(&env, &state, &mut cdp);
+        log_accrued_interest(&env, &state, &lender, fresh);
+        cdp.collateral_deposited -= amount;
+        let ratio = collateralization_ratio(&env, &state, &cdp)?;
+        if ratio < entry_mcr(&env, &state) {
+            return Err(Error::CollateralRatioBelowMinimum);
+        }
+        TokenClient::new(&env, &state.collateral_sac).transfer(
+            &env.current_contract_address(),
+            &payout,
+            &amount,
+        );
+        state.total_cdp_collateral -= amount;
+        storage::set_state(&env, &state);
+        storage::set_cdp(&env, &cdp);
+        Ok(())
+    }
+
+    /// Mints additional RWA against the caller's existing collateral.
+    /// Optionally bounded by `deadline_ledger` like `open_cdp`.
+    pub fn borrow_rwa(
+        env: Env,
+        lender: Address,
+        amount: i128,
+        deadline_ledger: Option<u32>,
+    ) -> Result<(), Error> {
+        lender.require_auth();
+        require_not_paused(&env)?;
+        #[cfg(feature = "compliance")]
+        crate::compliance::require_kyc(&env, &lender)?;
+        #[cfg(feature = "compliance")]
+        crate::compliance::require_not_frozen(&env, &lender)?;
+        check_deadline(&env, deadline_ledger)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let mut cdp = require_open_cdp(&env, &lender)?;
+        let state = storage::get_state(&env);
+        if in_recovery_mode(&env, &state)? {
+            return Err(Error::RecoveryModeActive);
+        }
+        let fresh = accrue_interest(&env, &state, &mut cdp);
+        log_accrued_interest(&env, &state, &lender, fresh);
+        cdp.asset_lent += amount;
+        let ratio = collateralization_ratio(&env, &state, &cdp)?;
+        if ratio < entry_mcr(&env, &state) {
+            return Err(Error::CollateralRatioBelowMinimum);
+        }
+        check_debt_ceiling(&state, amount)?;
+        let fee = origination_fee_due(&env, &state, amount)?;
+        token::mint_internal(&env, &lender, amount)?;
+        // Reload: `mint_internal` saved its own copy of the state.
+        let mut state = storage::get_state(&env);
+        state.total_cdp_debt += amount;
+        charge_origination_fee(&env, &mut state, &lender, fee);
+        storage::set_state(&env, &state);
+        storage::set_cdp(&env, &cdp);
+        Ok(())
+    }
+
+    /// Burns RWA from the caller to reduce their CDP's principal.
+    pub fn repay_debt(env: Env, lender: Address, amount: i128) -> Result<(), Error> {
+        lender.require_auth();
+        require_not_paused(&env)?;
+        repay_debt_internal(&env, &lender, &lender, amount)
+    }
+
+    /// Repays `rwa_amount` of principal by selling that much of the
+    /// CDP's own collateral to the protocol at oracle face value, so a
+    /// lender can deleverage without sourcing RWA on the market. Both
+    /// sides of the position shrink; as long as the CDP is above water
+    /// its ratio can only improve. The surrendered collateral moves
+    /// into the redemption buffer, where it keeps backing the RWA the
+    /// lender minted and spent until a redeemer claims it.
+    pub fn repay_with_collateral(
+        env: Env,
+        lender: Address,
+        rwa_amount: i128,
+    ) -> Result<(), Error> {
+        lender.require_auth();
+        require_not_paused(&env)?;
+        #[cfg(feature = "compliance")]
+        crate::compliance::require_not_frozen(&env, &lender)?;
+        if rwa_amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let mut cdp = require_open_cdp(&env, &lender)?;
+        let mut state = storage::get_state(&env);
+        let fresh = accrue_interest(&env, &state, &mut cdp);
+        log_accrued_interest(&env, &state, &lender, fresh);
+        if rwa_amount > cdp.asset_lent {
+            return Err(Error::InvalidAmount);
+        }
+        // Same dust floor as `repay_debt`.
+        let remaining = cdp.asset_lent - rwa_amount;
+        if remaining > 0 && remaining < state.min_debt {
+            return Err(Error::DebtBelowMinimum);
+        }
+        // Ceil: the protocol buys the debt back at no better than face
+        // value.
+        let collateral_due = rwa_to_collateral(&env, &state, rwa_amount)?;
+        if collateral_due > cdp.collateral_deposited {
+            return Err(Error::InsufficientBalance);
+        }
+        cdp.asset_lent -= rwa_amount;
+        cdp.collateral_deposited -= collateral_due;
+        state.total_cdp_debt -= rwa_amount;
+        state.total_cdp_collateral -= collateral_due;
+        state.redemption_buffer += collateral_due;
+        storage::set_state(&env, &state);
+        storage::set_cdp(&env, &cdp);
+        Deleveraged {
+            lender,
+            debt_repaid: rwa_amount,
+            collateral_spent: collateral_due,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Delegates `repay_debt_for` to `operator`, or revokes the
+    /// delegation with `None`. Operators burn their own RWA against the
+    /// position's principal and can never withdraw collateral or
+    /// borrow, so an automation bot can defend the position without
+    /// holding keys that could drain it. (Topping up collateral and
+    /// settling interest need no delegation at all.)
+    pub fn set_operator(env: Env, lender: Address, operator: Option<Address>) -> Result<(), Error> {
+        lender.require_auth();
+        storage::set_operator(&env, &lender, &operator);
+        Ok(())
+    }
+
+    pub fn operator(env: Env, lender: Address) -> Option<Address> {
+        storage::get_operator(&env, &lender)
+    }
+
+    /// Tops up `lender`'s CDP with collateral from `payer`'s balance.
+    /// Deliberately open to any payer — adding collateral can only make
+    /// the position safer, so rescue services and custodians may shore
+    /// up an account without a delegation or its keys.
+    pub fn add_collateral_for(
+        env: Env,
+        payer: Address,
+        lender: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        payer.require_auth();
+        require_not_paused(&env)?;
+        add_collateral_internal(&env, &payer, &lender, amount)
+    }
+
+    /// Operator variant of `repay_debt`: burns the operator's own RWA
+    /// against `lender`'s principal.
+    pub fn repay_debt_for(
+        env: Env,
+        caller: Address,
+        lender: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        require_not_paused(&env)?;
+        require_operator(&env, &caller, &lender)?;
+        repay_debt_internal(&env, &caller, &lender, amount)
+    }
+
+    /// Settles `lender`'s accrued interest with collateral from
+    /// `payer`'s balance. Open to any payer for the same reason as
+    /// `add_collateral_for`: clearing interest only ever improves the
+    /// position, so custodians and DAOs servicing client CDPs need no
+    /// delegation to cover the bill.
+    pub fn pay_interest_for(env: Env, payer: Address, lender: Address) -> Result<(), Error> {
+        payer.require_auth();
+        require_not_paused(&env)?;
+        pay_interest_internal(&env, &payer, &lender)
+    }
+
+    /// Redeems `rwa_amount` of the caller's tokens at face value for
+    /// collateral drawn from the lowest-ratio open CDPs, Liquity-style:
+    /// the tokens are burned and the affected CDPs' principal shrinks by
+    /// the amount redeemed against them, so arbitrage pulls the token
+    /// back to its peg whenever it trades below face value. Stops early
+    /// if open CDP debt runs out; frozen CDPs are left for liquidation.
+    /// Returns the collateral paid out.
+    pub fn redeem(env: Env, redeemer: Address, rwa_amount: i128) -> Result<i128, Error> {
+        redeemer.require_auth();
+        require_not_paused(&env)?;
+        if rwa_amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let state = storage::get_state(&env);
+        let (rwa_price, rwa_dec) =
+            price_of(&env, &state.oracle, &state.pegged_asset, state.oracle_decimals)?;
+        let (col_price, col_dec) = price_of(
+            &env,
+            &state.collateral_oracle,
+            &state.collateral_symbol,
+            state.collateral_oracle_decimals,
+        )?;
+        let mut remaining = rwa_amount;
+        let mut collateral_total = 0i128;
+        let mut entries_touched = 0u32;
+        while remaining > 0 {
+            // Each pass rescans the whole registry for the riskiest CDP,
+            // which is what makes redemption the heaviest entrypoint.
+            entries_touched += storage::cdp_count(&env) + 1;
+            let lender = match lowest_ratio_cdp(&env, &state)? {
+                Some(lender) => lender,
+                None => break,
+            };
+            let mut cdp = storage::get_cdp(&env, &lender).unwrap();
+            let fresh = accrue_interest(&env, &state, &mut cdp);
+            log_accrued_interest(&env, &state, &lender, fresh);
+            let redeemed = remaining.min(cdp.asset_lent);
+            // Face value, rounded down and capped at the CDP's
+            // collateral: dust stays with the CDP, and an underwater
+            // position cannot pay out more than it holds.
+            let collateral_out = mul_div_floor(
+                redeemed * rwa_price,
+                pow10(col_dec),
+                col_price * pow10(rwa_dec),
+            )
+            .min(cdp.collateral_deposited);
+            token::burn_internal(&env, &redeemer, redeemed)?;
+            cdp.asset_lent -= redeemed;
+            cdp.collateral_deposited -= collateral_out;
+            storage::set_cdp(&env, &cdp);
+            remaining -= redeemed;
+            collateral_total += collateral_out;
+        }
+        // Whatever the registry could not fill draws on the redemption
+        // buffer `repay_with_collateral` left behind. Floor both
+        // conversions, against the redeemer: first the RWA the buffer
+        // covers, then its face value.
+        let mut buffer_burned = 0i128;
+        let mut buffer_out = 0i128;
+        if remaining > 0 {
+            let buffer = storage::get_state(&env).redemption_buffer;
+            if buffer > 0 {
+                buffer_burned = remaining.min(mul_div_floor(
+                    buffer * col_price,
+                    pow10(rwa_dec),
+                    rwa_price * pow10(col_dec),
+                ));
+            }
+            if buffer_burned > 0 {
+                buffer_out = mul_div_floor(
+                    buffer_burned * rwa_price,
+                    pow10(col_dec),
+                    col_price * pow10(rwa_dec),
+                );
+                token::burn_internal(&env, &redeemer, buffer_burned)?;
+                remaining -= buffer_burned;
+            }
+        }
+        let redeemed_total = rwa_amount - remaining;
+        let mut collateral_net = collateral_total + buffer_out;
+        if redeemed_total > 0 {
+            // Reload: `burn_internal` saved the state each iteration.
+            let mut state = storage::get_state(&env);
+            state.total_cdp_debt -= redeemed_total - buffer_burned;
+            state.total_cdp_collateral -= collateral_total;
+            state.redemption_buffer -= buffer_out;
+            let fee = mul_div_ceil(
+                collateral_total + buffer_out,
+                redemption_rate_now(&env, &state) as i128,
+                BPS,
+            );
+            if fee > 0 {
+                state.fees_collected += fee;
+                state.revenue.redemption_fees += fee;
+                events::FeeCollected {
+                    kind: symbol_short!("redeem"),
+                    payer: redeemer.clone(),
+                    amount: fee,
+                }
+                .publish(&env);
+                RevenueAccrued {
+                    source: RevenueSource::Redemption,
+                    amount: fee,
+                }
+                .publish(&env);
+                collateral_net -= fee;
+            }
+            bump_redemption_base_rate(&env, &mut state, redeemed_total);
+            storage::set_state(&env, &state);
+            if collateral_net > 0 {
+                TokenClient::new(&env, &state.collateral_sac).transfer(
+                    &env.current_contract_address(),
+                    &redeemer,
+                    &collateral_net,
+                );
+            }
+            Redeemed {
+                redeemer,
+                rwa_burned: redeemed_total,
+                collateral_out: collateral_net,
+            }
+            .publish(&env);
+        }
+        events::meter(&env, symbol_short!("redeem"), entries_touched);
+        Ok(collateral_net)
+    }
+
+    /// Settles all accrued interest on the caller's CDP, collected in
+    /// the collateral asset at the current oracle prices.
+    pub fn pay_interest(env: Env, lender: Address) -> Result<(), Error> {
+        lender.require_auth();
+        require_not_paused(&env)?;
+        pay_interest_internal(&env, &lender, &lender)
+    }
+
+    /// Closes a fully repaid CDP and returns the remaining collateral.
+    pub fn close_cdp(env: Env, lender: Address) -> Result<(), Error> {
+        lender.require_auth();
+        require_not_paused(&env)?;
+        #[cfg(feature = "compliance")]
+        crate::compliance::require_not_frozen(&env, &lender)?;
+        let mut cdp = require_open_cdp(&env, &lender)?;
+        let mut state = storage::get_state(&env);
+        let fresh = accrue_interest(&env, &state, &mut cdp);
+        log_accrued_interest(&env, &state, &lender, fresh);
+        if cdp.asset_lent > 0 {
+            return Err(Error::OutstandingDebt);
+        }
+        if cdp.accrued_interest > 0 {
+            return Err(Error::OutstandingInterest);
+        }
+        if cdp.collateral_deposited > 0 {
+            TokenClient::new(&env, &state.collateral_sac).transfer(
+                &env.current_contract_address(),
+                &lender,
+                &cdp.collateral_deposited,
+            );
+            state.total_cdp_collateral -= cdp.collateral_deposited;
+            cdp.collateral_deposited = 0;
+        }
+        cdp.status = CDPStatus::Closed;
+        storage::set_state(&env, &state);
+        storage::set_cdp(&env, &cdp);
+        notify_receipt_hook(&env, symbol_short!("burn_rcpt"), &lender);
+        Ok(())
+    }
+
+    /// Permissionlessly freezes a CDP whose ratio has fallen below MCR.
+    /// The caller earns the keeper bounty, if one is configured.
+    pub fn freeze_cdp(env: Env, caller: Address, lender: Address) -> Result<(), Error> {
+        caller.require_auth();
+        require_not_paused(&env)?;
+        let mut cdp = require_open_cdp(&env, &lender)?;
+        let mut state = storage::get_state(&env);
+        let fresh = accrue_interest(&env, &state, &mut cdp);
+        log_accrued_interest(&env, &state, &lender, fresh);
+        let ratio = collateralization_ratio(&env, &state, &cdp)?;
+        if ratio >= freeze_threshold(&env, &state)? {
+            return Err(Error::CollateralRatioSafe);
+        }
+        cdp.status = CDPStatus::Frozen;
+        storage::set_cdp(&env, &cdp);
+        pay_keeper_bounty(&env, &mut state, &caller, symbol_short!("freeze"));
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    /// Freezes up to `max_n` undercollateralized CDPs in one call,
+    /// walking the CDP registry, so a single keeper transaction replaces
+    /// many individual freezes during market stress. Publishes a summary
+    /// event and returns the number frozen.
+    pub fn sweep_insolvent(env: Env, max_n: u32) -> Result<u32, Error> {
+        require_not_paused(&env)?;
+        if max_n == 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let state = storage::get_state(&env);
+        let threshold = freeze_threshold(&env, &state)?;
+        let mut count = 0u32;
+        let mut debt_frozen = 0i128;
+        let mut entries_touched = 0u32;
+        for i in 0..storage::cdp_count(&env) {
+            if count == max_n {
+                break;
+            }
+            entries_touched += 1;
+            let lender = match storage::cdp_at(&env, i) {
+                Some(lender) => lender,
+                None => continue,
+            };
+            let mut cdp = match storage::get_cdp(&env, &lender) {
+                Some(cdp) => cdp,
+                None => continue,
+            };
+            if cdp.status != CDPStatus::Open {
+                continue;
+            }
+            let fresh = accrue_interest(&env, &state, &mut cdp);
+            log_accrued_interest(&env, &state, &lender, fresh);
+            if collateralization_ratio(&env, &state, &cdp)? >= threshold {
+                continue;
+            }
+            cdp.status = CDPStatus::Frozen;
+            storage::set_cdp(&env, &cdp);
+            count += 1;
+            debt_frozen += cdp.asset_lent + cdp.accrued_interest;
+        }
+        if count > 0 {
+            InsolventSwept { count, debt_frozen }.publish(&env);
+        }
+        events::meter(&env, symbol_short!("sweep"), entries_touched);
+        Ok(count)
+    }
+
+    /// Liquidates a frozen CDP directly: the liquidator burns
+    /// `rwa_amount` of their own tokens against the debt and receives
+    /// the proportional collateral plus the liquidation penalty, so
+    /// arbitrageurs can clear bad positions without stability pool
+    /// depth. Partial repayments are fine; amounts beyond the debt are
+    /// capped. Returns the collateral seized.
+    pub fn liquidate_with_repay(
+        env: Env,
+        liquidator: Address,
+        lender: Address,
+        rwa_amount: i128,
+    ) -> Result<i128, Error> {
+        liquidator.require_auth();
+        require_not_paused(&env)?;
+        if rwa_amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let mut cdp = match storage::get_cdp(&env, &lender) {
+            Some(cdp) => cdp,
+            None => return Err(Error::CDPNotFound),
+        };
+        if cdp.status != CDPStatus::Frozen {
+            return Err(Error::CDPNotFrozen);
+        }
+        // A CDP already clearing through a Dutch auction is off-limits:
+        // both paths settle against the same collateral.
+        if storage::get_auction(&env, &lender).is_some() {
+            return Err(Error::AuctionActive);
+        }
+        let mut state = storage::get_state(&env);
+        let debt = cdp.asset_lent + cdp.accrued_interest;
+        let absorbed = rwa_amount.min(debt);
+        // Floor: rounding dust stays with the CDP, as in pool
+        // liquidation; the penalty is bounded by what the CDP holds.
+        let base = mul_div_floor(cdp.collateral_deposited, absorbed, debt);
+        let penalty = mul_div_floor(base, state.liquidation_penalty_bps as i128, BPS)
+            .min(cdp.collateral_deposited - base);
+        let seized = base + penalty;
+
+        // Burn inline against the held state: `burn_internal` saves its
+        // own copy, which the `set_state` below would clobber.
+        token::spend_balance(&env, &liquidator, absorbed)?;
+        state.total_supply -= absorbed;
+        Burned {
+            from: liquidator.clone(),
+            amount: absorbed,
+        }
+        .publish(&env);
+        StdBurn {
+            from: liquidator.clone(),
+            amount: absorbed,
+        }
+        .publish(&env);
+        if seized > 0 {
+            TokenClient::new(&env, &state.collateral_sac).transfer(
+                &env.current_contract_address(),
+                &liquidator,
+                &seized,
+            );
+        }
+
+        cdp.collateral_deposited -= seized;
+        state.total_cdp_collateral -= seized;
+        let interest_absorbed = absorbed.min(cdp.accrued_interest);
+        cdp.accrued_interest -= interest_absorbed;
+        cdp.asset_lent -= absorbed - interest_absorbed;
+        state.total_cdp_debt -= absorbed - interest_absorbed;
+
+        if cdp.asset_lent + cdp.accrued_interest == 0 {
+            if cdp.collateral_deposited > 0 {
+                TokenClient::new(&env, &state.collateral_sac).transfer(
+                    &env.current_contract_address(),
+                    &lender,
+                    &cdp.collateral_deposited,
+                );
+                state.total_cdp_collateral -= cdp.collateral_deposited;
+                cdp.collateral_deposited = 0;
+            }
+            cdp.status = CDPStatus::Closed;
+        }
+        storage::set_state(&env, &state);
+        storage::set_cdp(&env, &cdp);
+        if cdp.status == CDPStatus::Closed {
+            notify_receipt_hook(&env, symbol_short!("burn_rcpt"), &lender);
+        }
+        Ok(seized)
+    }
+
+    // --- Views ----------------------------------------------------------
+
+    /// Number of entries in the CDP index. Lenders are indexed the
+    /// first time they open a position and never removed, so this
+    /// bounds `list_cdps` pagination rather than counting open CDPs.
+    pub fn cdp_count(env: Env) -> u32 {
+        storage::cdp_count(&env)
+    }
+
+    /// A page of lender addresses from the CDP index: `limit` entries
+    /// starting at `offset`, in opening order. A page past the end
+    /// comes back short or empty, so keepers can walk the whole index
+    /// without reading the count first. Entries may point at closed
+    /// CDPs; filter on `get_cdp` status.
+    pub fn list_cdps(env: Env, offset: u32, limit: u32) -> Vec<Address> {
+        let mut out = Vec::new(&env);
+        let end = offset.saturating_add(limit).min(storage::cdp_count(&env));
+        for i in offset..end {
+            if let Some(lender) = storage::cdp_at(&env, i) {
+                out.push_back(lender);
+            }
+        }
+        out
+    }
+
+    /// Like `list_cdps`, but keeps only lenders whose CDP is in
+    /// `status`. `offset` and `limit` still page over index positions,
+    /// so a short page means the entries were filtered out, not that
+    /// the index ended; advance `offset` by `limit` regardless.
+    pub fn list_cdps_by_status(
+        env: Env,
+        status: CDPStatus,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<Address> {
+        let mut out = Vec::new(&env);
+        let end = offset.saturating_add(limit).min(storage::cdp_count(&env));
+        for i in offset..end {
+            if let Some(lender) = storage::cdp_at(&env, i) {
+                match storage::get_cdp(&env, &lender) {
+                    Some(cdp) if cdp.status == status => out.push_back(lender),
+                    _ => {}
+                }
+            }
+        }
+        out
+    }
+
+    /// Recomputes the collateralization ratio for a page of the index
+    /// at current prices and returns the open CDPs sitting below the
+    /// freeze threshold — the MCR, widened to the live TCR in recovery
+    /// mode — exactly the positions `freeze_cdp` will accept. Interest
+    /// is projected to now, matching what accrual would charge.
+    pub fn scan_insolvent(env: Env, offset: u32, limit: u32) -> Result<Vec<Address>, Error> {
+        let mut out = Vec::new(&env);
+        let state = storage::get_state(&env);
+        let threshold = freeze_threshold(&env, &state)?;
+        let end = offset.saturating_add(limit).min(storage::cdp_count(&env));
+        for i in offset..end {
+            let lender = match storage::cdp_at(&env, i) {
+                Some(lender) => lender,
+                None => continue,
+            };
+            let mut cdp = match storage::get_cdp(&env, &lender) {
+                Some(cdp) => cdp,
+                None => continue,
+            };
+            if cdp.status != CDPStatus::Open {
+                continue;
+            }
+            accrue_interest(&env, &state, &mut cdp);
+            if collateralization_ratio(&env, &state, &cdp)? < threshold {
+                out.push_back(lender);
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn get_cdp(env: Env, lender: Address) -> Option<CDP> {
+        storage::get_cdp(&env, &lender)
+    }
+
+    /// Current collateralization ratio of a CDP, in basis points,
+    /// including interest accrued up to now.
+    pub fn get_collateralization_ratio(env: Env, lender: Address) -> Result<u32, Error> {
+        let mut cdp = match storage::get_cdp(&env, &lender) {
+            Some(cdp) => cdp,
+            None => return Err(Error::CDPNotFound),
+        };
+        let state = storage::get_state(&env);
+        accrue_interest(&env, &state, &mut cdp);
+        collateralization_ratio(&env, &state, &cdp)
+    }
+
+    /// The CDP's collateralization ratio over the MCR, in
+    /// [`FACTOR_SCALE`] fixed point: one scale unit is the freeze
+    /// boundary, higher is safer.
+    ///
+    /// [`FACTOR_SCALE`]: crate::storage::FACTOR_SCALE
+    pub fn health_factor(env: Env, lender: Address) -> Result<i128, Error> {
+        Ok(Self::health_report(env, lender)?.factor)
+    }
+
+    /// `health_factor` together with every input that produced it, so
+    /// front-ends can show the contract's own numbers instead of
+    /// re-deriving the ratio math and drifting on rounding.
+    pub fn health_report(env: Env, lender: Address) -> Result<HealthReport, Error> {
+        let mut cdp = match storage::get_cdp(&env, &lender) {
+            Some(cdp) => cdp,
+            None => return Err(Error::CDPNotFound),
+        };
+        let state = storage::get_state(&env);
+        accrue_interest(&env, &state, &mut cdp);
+        let (rwa_price, rwa_decimals) =
+            price_of(&env, &state.oracle, &state.pegged_asset, state.oracle_decimals)?;
+        let (collateral_price, collateral_decimals) = price_of(
+            &env,
+            &state.collateral_oracle,
+            &state.collateral_symbol,
+            state.collateral_oracle_decimals,
+        )?;
+        let collateral_price = haircut_stale_price(&env, &state, collateral_price);
+        let ratio = collateralization_ratio(&env, &state, &cdp)?;
+        Ok(HealthReport {
+            // Floor: a reported health factor should never flatter the
+            // position.
+            factor: mul_div_floor(ratio as i128, FACTOR_SCALE, state.min_collat_ratio as i128),
+            ratio,
+            min_ratio: state.min_collat_ratio,
+            collateral: cdp.collateral_deposited,
+            debt: cdp.asset_lent + cdp.accrued_interest,
+            accrued_interest: cdp.accrued_interest,
+            rwa_price,
+            rwa_decimals,
+            collateral_price,
+            collateral_decimals,
+        })
+    }
+
+    /// The collateral feed price at which the CDP's ratio would sit
+    /// exactly on the MCR, holding the RWA price and interest accrued
+    /// to now fixed — the single number borrowers watch for alerts.
+    /// Below it the position is freezable (sooner, if a staleness
+    /// haircut is discounting the feed). Zero for a debt-free CDP;
+    /// `i128::MAX` when no price could save it.
+    pub fn liquidation_price(env: Env, lender: Address) -> Result<i128, Error> {
+        let mut cdp = match storage::get_cdp(&env, &lender) {
+            Some(cdp) => cdp,
+            None => return Err(Error::CDPNotFound),
+        };
+        let state = storage::get_state(&env);
+        accrue_interest(&env, &state, &mut cdp);
+        let debt = cdp.asset_lent + cdp.accrued_interest;
+        if debt == 0 {
+            return Ok(0);
+        }
+        if cdp.collateral_deposited == 0 {
+            return Ok(i128::MAX);
+        }
+        let (rwa_price, rwa_dec) =
+            price_of(&env, &state.oracle, &state.pegged_asset, state.oracle_decimals)?;
+        let (_, col_dec) = price_of(
+            &env,
+            &state.collateral_oracle,
+            &state.collateral_symbol,
+            state.collateral_oracle_decimals,
+        )?;
+        // Ceil: at the returned price the CDP is exactly safe, one
+        // stroop under it is not.
+        Ok(mul_div_ceil(
+            (state.min_collat_ratio as i128) * debt * rwa_price,
+            pow10(col_dec),
+            cdp.collateral_deposited * pow10(rwa_dec) * BPS,
+        ))
+    }
+
+    /// Additional RWA the CDP could mint right now without breaching
+    /// the MCR, after the debt ceiling and recovery mode — the largest
+    /// amount `borrow_rwa` would accept at current prices. Zero for a
+    /// position at or below the MCR.
+    pub fn max_borrowable(env: Env, lender: Address) -> Result<i128, Error> {
+        let mut cdp = require_open_cdp(&env, &lender)?;
+        let state = storage::get_state(&env);
+        if in_recovery_mode(&env, &state)? {
+            return Ok(0);
+        }
+        accrue_interest(&env, &state, &mut cdp);
+        let (rwa_price, rwa_dec) =
+            price_of(&env, &state.oracle, &state.pegged_asset, state.oracle_decimals)?;
+        let (col_price, col_dec) = price_of(
+            &env,
+            &state.collateral_oracle,
+            &state.collateral_symbol,
+            state.collateral_oracle_decimals,
+        )?;
+        let col_price = haircut_stale_price(&env, &state, col_price);
+        // Invert the ratio formula: the largest debt the collateral
+        // supports at exactly the MCR, floored so borrowing the full
+        // answer still passes the check.
+        let max_debt = mul_div_floor(
+            cdp.collateral_deposited * col_price,
+            pow10(rwa_dec) * BPS,
+            (entry_mcr(&env, &state) as i128) * rwa_price * pow10(col_dec),
+        );
+        let mut headroom = (max_debt - cdp.asset_lent - cdp.accrued_interest).max(0);
+        if state.debt_ceiling > 0 {
+            headroom = headroom.min((state.debt_ceiling - state.total_supply).max(0));
+        }
+        Ok(headroom)
+    }
+
+    /// Collateral needed to mint `rwa_amount` at exactly the MCR and
+    /// current prices, rounded up so opening with the answer passes the
+    /// ratio check. Wallets size deposits with this instead of
+    /// re-implementing the decimal normalization.
+    pub fn required_collateral(env: Env, rwa_amount: i128) -> Result<i128, Error> {
+        if rwa_amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let state = storage::get_state(&env);
+        let (rwa_price, rwa_dec) =
+            price_of(&env, &state.oracle, &state.pegged_asset, state.oracle_decimals)?;
+        let (col_price, col_dec) = price_of(
+            &env,
+            &state.collateral_oracle,
+            &state.collateral_symbol,
+            state.collateral_oracle_decimals,
+        )?;
+        let col_price = haircut_stale_price(&env, &state, col_price);
+        Ok(mul_div_ceil(
+            rwa_amount * rwa_price * (entry_mcr(&env, &state) as i128),
+            pow10(col_dec),
+            col_price * pow10(rwa_dec) * BPS,
+        ))
+    }
+
+    /// Dry run of `open_cdp` with the same checks in the same order,
+    /// minus auth and the deadline. Oracle failures still surface as
+    /// errors: a preview that cannot price is no preview.
+    pub fn preview_open_cdp(
+        env: Env,
+        lender: Address,
+        collateral_amount: i128,
+        rwa_amount: i128,
+    ) -> Result<ActionPreview, Error> {
+        let state = storage::get_state(&env);
+        let cdp = CDP {
+            lender: lender.clone(),
+            collateral_deposited: collateral_amount.max(0),
+            asset_lent: rwa_amount.max(0),
+            accrued_interest: 0,
+            last_interest_time: env.ledger().timestamp(),
+            interest_index: current_borrow_index(&env, &state),
+            status: CDPStatus::Open,
+        };
+        let ratio = collateralization_ratio(&env, &state, &cdp)?;
+        let fee = origination_fee_due(&env, &state, rwa_amount.max(0))?;
+        let check = (|| {
+            require_not_paused(&env)?;
+            if collateral_amount <= 0 || rwa_amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+            match storage::get_cdp(&env, &lender) {
+                Some(cdp) if cdp.status != CDPStatus::Closed => {
+                    return Err(Error::CDPAlreadyExists)
+                }
+                _ => {}
+            }
+            if rwa_amount < state.min_debt {
+                return Err(Error::DebtBelowMinimum);
+            }
+            if ratio < entry_mcr(&env, &state) {
+                return Err(Error::CollateralRatioBelowMinimum);
+            }
+            check_debt_ceiling(&state, rwa_amount)
+        })();
+        Ok(preview_from(check, ratio, 0, fee))
+    }
+
+    /// Dry run of `borrow_rwa`; see `preview_open_cdp`.
+    pub fn preview_borrow(env: Env, lender: Address, amount: i128) -> Result<ActionPreview, Error> {
+        let mut cdp = require_open_cdp(&env, &lender)?;
+        let state = storage::get_state(&env);
+        accrue_interest(&env, &state, &mut cdp);
+        let interest_due = cdp.accrued_interest;
+        cdp.asset_lent += amount.max(0);
+        let ratio = collateralization_ratio(&env, &state, &cdp)?;
+        let fee = origination_fee_due(&env, &state, amount.max(0))?;
+        let check = (|| {
+            require_not_paused(&env)?;
+            if amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+            if in_recovery_mode(&env, &state)? {
+                return Err(Error::RecoveryModeActive);
+            }
+            if ratio < entry_mcr(&env, &state) {
+                return Err(Error::CollateralRatioBelowMinimum);
+            }
+            check_debt_ceiling(&state, amount)
+        })();
+        Ok(preview_from(check, ratio, interest_due, fee))
+    }
+
+    /// Dry run of `withdraw_collateral`; see `preview_open_cdp`.
+    pub fn preview_withdraw_collateral(
+        env: Env,
+        lender: Address,
+        amount: i128,
+    ) -> Result<ActionPreview, Error> {
+        let mut cdp = require_open_cdp(&env, &lender)?;
+        let state = storage::get_state(&env);
+        accrue_interest(&env, &state, &mut cdp);
+        let interest_due = cdp.accrued_interest;
+        let over = amount > cdp.collateral_deposited;
+        cdp.collateral_deposited -= amount.clamp(0, cdp.collateral_deposited);
+        let ratio = collateralization_ratio(&env, &state, &cdp)?;
+        let check = (|| {
+            require_not_paused(&env)?;
+            if amount <= 0 || over {
+                return Err(Error::InvalidAmount);
+            }
+            if in_recovery_mode(&env, &state)? {
+                return Err(Error::RecoveryModeActive);
+            }
+            if ratio < entry_mcr(&env, &state) {
+                return Err(Error::CollateralRatioBelowMinimum);
+            }
+            Ok(())
+        })();
+        Ok(preview_from(check, ratio, interest_due, 0))
+    }
+
+    /// Dry run of `repay_debt`; see `preview_open_cdp`.
+    pub fn preview_repay(env: Env, lender: Address, amount: i128) -> Result<ActionPreview, Error> {
+        let mut cdp = require_open_cdp(&env, &lender)?;
+        let state = storage::get_state(&env);
+        accrue_interest(&env, &state, &mut cdp);
+        let interest_due = cdp.accrued_interest;
+        let over = amount > cdp.asset_lent;
+        cdp.asset_lent -= amount.clamp(0, cdp.asset_lent);
+        let ratio = collateralization_ratio(&env, &state, &cdp)?;
+        let check = (|| {
+            require_not_paused(&env)?;
+            if amount <= 0 || over {
+                return Err(Error::InvalidAmount);
+            }
+            if cdp.asset_lent > 0 && cdp.asset_lent < state.min_debt {
+                return Err(Error::DebtBelowMinimum);
+            }
+            if storage::get_balance(&env, &lender) < amount {
+                return Err(Error::InsufficientBalance);
+            }
+            Ok(())
+        })();
+        Ok(preview_from(check, ratio, interest_due, 0))
+    }
+
+    /// Everything a wallet needs to construct a full payoff: the RWA
+    /// principal, the interest accrued so far in RWA, and that interest
+    /// converted to collateral both now and `horizon_secs` from now (so a
+    /// signed transaction has enough approval headroom to land late).
+    pub fn payoff_quote(
+        env: Env,
+        lender: Address,
+        horizon_secs: u64,
+    ) -> Result<(i128, i128, i128, i128), Error> {
+        let mut cdp = match storage::get_cdp(&env, &lender) {
+            Some(cdp) => cdp,
+            None => return Err(Error::CDPNotFound),
+        };
+        let state = storage::get_state(&env);
+        accrue_interest(&env, &state, &mut cdp);
+        let interest_now = cdp.accrued_interest;
+        let interest_at_horizon = if cdp.status == CDPStatus::Open && horizon_secs > 0 {
+            interest_now + compound_interest(&state, cdp.asset_lent + interest_now, horizon_secs)
+        } else {
+            interest_now
+        };
+        let collateral_now = rwa_to_collateral(&env, &state, interest_now)?;
+        let collateral_at_horizon = rwa_to_collateral(&env, &state, interest_at_horizon)?;
+        Ok((cdp.asset_lent, interest_now, collateral_now, collateral_at_horizon))
+    }
+
+    /// Total collateralization ratio across every CDP, in basis points:
+    /// aggregate locked collateral against aggregate principal.
+    pub fn total_collateralization_ratio(env: Env) -> Result<u32, Error> {
+        let state = storage::get_state(&env);
+        total_collat_ratio(&env, &state)
+    }
+
+    /// Whether the system is in recovery mode: the TCR is below the
+    /// configured critical ratio, so leverage-increasing actions are
+    /// blocked and the liquidation threshold widens to the TCR.
+    pub fn recovery_mode(env: Env) -> Result<bool, Error> {
+        let state = storage::get_state(&env);
+        in_recovery_mode(&env, &state)
+    }
+
+    /// Interest owed if settled right now, in RWA units.
+    /// The global borrow index as of now ([`INTEREST_SCALE`] fixed
+    /// point at deployment). Debt grows with this index under the flat
+    /// rate, so off-chain accounting can project any CDP from one read.
+    /// Collateral held against debt repaid in kind, awaiting redemption.
+    pub fn redemption_buffer(env: Env) -> i128 {
+        storage::get_state(&env).redemption_buffer
+    }
+
+    pub fn borrow_index(env: Env) -> i128 {
+        let state = storage::get_state(&env);
+        current_borrow_index(&env, &state)
+    }
+
+    pub fn get_projected_interest(env: Env, lender: Address) -> Result<i128, Error> {
+        let mut cdp = match storage::get_cdp(&env, &lender) {
+            Some(cdp) => cdp,
+            None => return Err(Error::CDPNotFound),
+        };
+        let state = storage::get_state(&env);
+        accrue_interest(&env, &state, &mut cdp);
+        Ok(cdp.accrued_interest)
+    }
+}
+
+/// Invokes the configured receipt-NFT contract, if any. Hook failures are
+/// swallowed so a broken or malicious hook can never block CDP lifecycle
+/// operations.
+pub(crate) fn notify_receipt_hook(env: &Env, function: Symbol, lender: &Address) {
+    if let Some(hook) = storage::get_receipt_hook(env) {
+        let args = (lender.clone(),).into_val(env);
+        let _ = env.try_invoke_contract::<Val, Val>(&hook, &function, args);
+    }
+}
+
+/// The redemption fee rate in force right now, in basis points: the
+/// configured floor plus the linearly decayed remainder of the base
+/// rate. The base rate is ignored entirely while decay is disabled.
+pub(crate) fn redemption_rate_now(env: &Env, state: &RWATokenStorage) -> u32 {
+    if state.redemption_decay_secs == 0 {
+        return state.redemption_fee_floor_bps;
+    }
+    let elapsed = env
+        .ledger()
+        .timestamp()
+        .saturating_sub(state.base_rate_updated_at);
+    let decayed = if elapsed >= state.redemption_decay_secs {
+        0
+    } else {
+        let remaining = (state.redemption_decay_secs - elapsed) as i128;
+        mul_div_floor(
+            state.redemption_base_rate_bps as i128,
+            remaining,
+            state.redemption_decay_secs as i128,
+        ) as u32
+    };
+    (state.redemption_fee_floor_bps + decayed).min(BPS as u32)
+}
+
+/// Spikes the base rate by the share of supply just redeemed (in basis
+/// points, on top of whatever had not decayed yet) and restarts the
+/// decay clock. The caller saves the state afterwards.
+fn bump_redemption_base_rate(env: &Env, state: &mut RWATokenStorage, redeemed: i128) {
+    if state.redemption_decay_secs == 0 || state.total_supply <= 0 {
+        return;
+    }
+    let left = redemption_rate_now(env, state) - state.redemption_fee_floor_bps;
+    let bump = mul_div_ceil(redeemed, BPS, state.total_supply + redeemed) as u32;
+    state.redemption_base_rate_bps = (left + bump).min(BPS as u32);
+    state.base_rate_updated_at = env.ledger().timestamp();
+}
+
+/// Folds a mirrored guard sequence into the preview wallets consume.
+fn preview_from(check: Result<(), Error>, ratio: u32, interest_due: i128, fee: i128) -> ActionPreview {
+    ActionPreview {
+        ok: check.is_ok(),
+        error_code: check.err().map_or(0, |e| e as u32),
+        ratio,
+        interest_due,
+        fee,
+    }
+}
+
+/// Rejects callers that are not `lender`'s configured operator.
+fn require_operator(env: &Env, caller: &Address, lender: &Address) -> Result<(), Error> {
+    match storage::get_operator(env, lender) {
+        Some(operator) if operator == *caller => Ok(()),
+        _ => Err(Error::Unauthorized),
+    }
+}
+
+/// Body of `add_collateral`, with the account the collateral is drawn
+/// from split out so operators can fund it.
+fn add_collateral_internal(
+    env: &Env,
+    payer: &Address,
+    lender: &Address,
+    amount: i128,
+) -> Result<(), Error> {
+    #[cfg(feature = "compliance")]
+    crate::compliance::require_not_frozen(env, lender)?;
+    if amount <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+    let mut cdp = require_open_cdp(env, lender)?;
+    let mut state = storage::get_state(env);
+    TokenClient::new(env, &state.collateral_sac).transfer(
+        payer,
+        env.current_contract_address(),
+        &amount,
+    );
+    cdp.collateral_deposited += amount;
+    state.total_cdp_collateral += amount;
+    storage::set_state(env, &state);
+    storage::set_cdp(env, &cdp);
+    Ok(())
+}
+
+/// Body of `repay_debt`, burning from `payer` against `lender`'s CDP.
+fn repay_debt_internal(
+    env: &Env,
+    payer: &Address,
+    lender: &Address,
+    amount: i128,
+) -> Result<(), Error> {
+    #[cfg(feature = "compliance")]
+    crate::compliance::require_not_frozen(env, lender)?;
+    if amount <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+    let mut cdp = require_open_cdp(env, lender)?;
+    let state = storage::get_state(env);
+    let fresh = accrue_interest(env, &state, &mut cdp);
+    log_accrued_interest(env, &state, lender, fresh);
+    if amount > cdp.asset_lent {
+        return Err(Error::InvalidAmount);
+    }
+    // Either clear the principal or leave it at the floor: a partial
+    // repayment must not create the dust position `open_cdp` rejects.
+    let remaining = cdp.asset_lent - amount;
+    if remaining > 0 && remaining < state.min_debt {
+        return Err(Error::DebtBelowMinimum);
+    }
+    token::burn_internal(env, payer, amount)?;
+    cdp.asset_lent -= amount;
+    // Reload: `burn_internal` saved its own copy of the state.
+    let mut state = storage::get_state(env);
+    state.total_cdp_debt -= amount;
+    storage::set_state(env, &state);
+    storage::set_cdp(env, &cdp);
+    Ok(())
+}
+
+/// Body of `pay_interest`, charging the collateral to `payer`.
+fn pay_interest_internal(env: &Env, payer: &Address, lender: &Address) -> Result<(), Error> {
+    #[cfg(feature = "compliance")]
+    crate::compliance::require_not_frozen(env, lender)?;
+    let mut cdp = require_open_cdp(env, lender)?;
+    let mut state = storage::get_state(env);
+    let fresh = accrue_interest(env, &state, &mut cdp);
+    log_accrued_interest(env, &state, lender, fresh);
+    if cdp.accrued_interest > 0 {
+        let collateral_due = rwa_to_collateral(env, &state, cdp.accrued_interest)?;
+        if collateral_due > 0 {
+            TokenClient::new(env, &state.collateral_sac).transfer(
+                payer,
+                env.current_contract_address(),
+                &collateral_due,
+            );
+        }
+        // A configured share streams on to the data-fee account so
+        // interest income helps fund the feeds it depends on; only
+        // the remainder is protocol revenue.
+        let mut kept = collateral_due;
+        if state.data_fee_bps > 0 {
+            if let Some(account) = storage::get_data_fee_account(env) {
+                let fee = mul_div_floor(collateral_due, state.data_fee_bps as i128, BPS);
+                if fee > 0 {
+                    TokenClient::new(env, &state.collateral_sac).transfer(
+                        &env.current_contract_address(),
+                        &account,
+                        &fee,
+                    );
+                    kept -= fee;
+                    DataFeeStreamed { account, amount: fee }.publish(env);
+                }
+            }
+        }
+        // The savings facility takes its configured share next; like
+        // the data fee it never counts as revenue.
+        kept -= crate::savings::carve_interest_share(env, collateral_due, kept);
+        state.interest_collected += kept;
+        state.interest_current_epoch += kept;
+        state.revenue.interest += kept;
+        InterestPaid {
+            lender: lender.clone(),
+            amount: cdp.accrued_interest,
+            collateral_paid: collateral_due,
+            epoch: state.current_epoch,
+        }
+        .publish(env);
+        RevenueAccrued {
+            source: RevenueSource::Interest,
+            amount: kept,
+        }
+        .publish(env);
+        cdp.accrued_interest = 0;
+        storage::set_state(env, &state);
+    }
+    storage::set_cdp(env, &cdp);
+    Ok(())
+}
+
+/// Upfront collateral owed on minting `rwa_amount` of new CDP debt, at
+/// the configured origination fee converted at face value; rounds up,
+/// like everything the protocol is owed.
+fn origination_fee_due(
+    env: &Env,
+    state: &RWATokenStorage,
+    rwa_amount: i128,
+) -> Result<i128, Error> {
+    if state.origination_fee_bps == 0 {
+        return Ok(0);
+    }
+    let fee_rwa = mul_div_ceil(rwa_amount, state.origination_fee_bps as i128, BPS);
+    rwa_to_collateral(env, state, fee_rwa)
+}
+
+/// Pulls a non-zero origination fee from the borrower and books it as
+/// treasury revenue. The caller saves the state afterwards.
+fn charge_origination_fee(env: &Env, state: &mut RWATokenStorage, lender: &Address, fee: i128) {
+    if fee == 0 {
+        return;
+    }
+    TokenClient::new(env, &state.collateral_sac).transfer(
+        lender,
+        env.current_contract_address(),
+        &fee,
+    );
+    state.fees_collected += fee;
+    state.revenue.origination_fees += fee;
+    events::FeeCollected {
+        kind: symbol_short!("originate"),
+        payer: lender.clone(),
+        amount: fee,
+    }
+    .publish(env);
+    RevenueAccrued {
+        source: RevenueSource::Origination,
+        amount: fee,
+    }
+    .publish(env);
+}
+
+/// Rejects a mutation whose transaction outlived the user's deadline.
+fn check_deadline(env: &Env, deadline_ledger: Option<u32>) -> Result<(), Error> {
+    match deadline_ledger {
+        Some(deadline) if env.ledger().sequence() > deadline => Err(Error::DeadlineExpired),
+        _ => Ok(()),
+    }
+}
+
+/// The open CDP with outstanding principal and the lowest
+/// collateralization ratio, if any, scanning the full lender index.
+fn lowest_ratio_cdp(env: &Env, state: &RWATokenStorage) -> Result<Option<Address>, Error> {
+    let mut best: Option<(Address, u32)> = None;
+    for i in 0..storage::cdp_count(env) {
+        let lender = match storage::cdp_at(env, i) {
+            Some(lender) => lender,
+            None => continue,
+        };
+        let mut cdp = match storage::get_cdp(env, &lender) {
+            Some(cdp) => cdp,
+            None => continue,
+        };
+        if cdp.status != CDPStatus::Open || cdp.asset_lent == 0 {
+            continue;
+        }
+        accrue_interest(env, state, &mut cdp);
+        let ratio = collateralization_ratio(env, state, &cdp)?;
+        match &best {
+            Some((_, best_ratio)) if *best_ratio <= ratio => {}
+            _ => best = Some((lender, ratio)),
+        }
+    }
+    Ok(best.map(|(lender, _)| lender))
+}
+
+pub(crate) fn require_open_cdp(env: &Env, lender: &Address) -> Result<CDP, Error> {
+    match storage::get_cdp(env, lender) {
+        Some(cdp) if cdp.status == CDPStatus::Open => Ok(cdp),
+        Some(_) => Err(Error::CDPNotOpen),
+        None => Err(Error::CDPNotFound),
+    }
+}
+
+/// Settles per-second compound interest on the CDP's debt up to now.
+/// Under the flat rate the debt grows by the global borrow index since
+/// the CDP's snapshot, so every position prices a rate change from the
+/// same instant. A tier schedule prices tranches of the individual
+/// principal, which no shared index can express, so it compounds from
+/// the CDP's own checkpoint instead. Returns the freshly accrued
+/// amount so state-changing callers can log it.
+pub(crate) fn accrue_interest(env: &Env, state: &RWATokenStorage, cdp: &mut CDP) -> i128 {
+    let now = env.ledger().timestamp();
+    let index = current_borrow_index(env, state);
+    let before = cdp.accrued_interest;
+    if cdp.status == CDPStatus::Open && cdp.asset_lent > 0 {
+        let debt = cdp.asset_lent + cdp.accrued_interest;
+        if state.rate_tiers.is_empty() {
+            if index > cdp.interest_index {
+                cdp.accrued_interest +=
+                    mul_div_ceil(debt, index - cdp.interest_index, cdp.interest_index);
+            }
+        } else if now > cdp.last_interest_time {
+            let elapsed = now - cdp.last_interest_time;
+            let charged = elapsed - outage_credit(env, state, cdp.last_interest_time, now);
+            cdp.accrued_interest += compound_interest(state, debt, charged);
+        }
+    }
+    cdp.interest_index = index;
+    cdp.last_interest_time = now;
+    cdp.accrued_interest - before
+}
+
+/// Publishes [`events::InterestAccrued`] for `delta` freshly accrued
+/// units. Only paths that persist the CDP afterwards log, so indexers
+/// count each unit of interest exactly once; views project interest on
+/// a scratch copy and stay silent.
+fn log_accrued_interest(env: &Env, state: &RWATokenStorage, lender: &Address, delta: i128) {
+    if delta > 0 {
+        events::InterestAccrued {
+            lender: lender.clone(),
+            amount: delta,
+            epoch: state.current_epoch,
+        }
+        .publish(env);
+    }
+}
+
+/// The global borrow index as it stands right now: the stored value
+/// compounded forward at the flat per-second rate over the seconds
+/// since its checkpoint, minus provable oracle outages. Pure read; the
+/// stored index only moves when [`update_borrow_index`] checkpoints it.
+pub(crate) fn current_borrow_index(env: &Env, state: &RWATokenStorage) -> i128 {
+    let now = env.ledger().timestamp();
+    if now <= state.index_updated_at {
+        return state.borrow_index;
+    }
+    let elapsed = now - state.index_updated_at;
+    let charged = elapsed - outage_credit(env, state, state.index_updated_at, now);
+    let per_second = INTEREST_SCALE
+        + mul_div_ceil(
+            state.annual_interest_rate as i128,
+            INTEREST_SCALE,
+            BPS * SECONDS_PER_YEAR as i128,
+        );
+    mul_div_ceil(
+        state.borrow_index,
+        pow_ceil(per_second, charged, INTEREST_SCALE),
+        INTEREST_SCALE,
+    )
+}
+
+/// Checkpoints the borrow index under the rate in force. Must run
+/// before any flat-rate change so already-elapsed time keeps its old
+/// pricing instead of being repriced retroactively.
+pub(crate) fn update_borrow_index(env: &Env, state: &mut RWATokenStorage) {
+    state.borrow_index = current_borrow_index(env, state);
+    state.index_updated_at = env.ledger().timestamp();
+}
+
+/// Interest owed on `debt` after `seconds` of per-second compounding of
+/// the nominal annual rate, so the effective annual charge lands
+/// slightly above the advertised APR, as with any compounding schedule.
+/// The tier schedule prices the debt as it stands when the window
+/// opens. Interest is owed to the protocol, so every step rounds up.
+pub(crate) fn compound_interest(state: &RWATokenStorage, debt: i128, seconds: u64) -> i128 {
+    if debt <= 0 || seconds == 0 {
+        return 0;
+    }
+    let annual = annual_interest_amount(state, debt);
+    if annual == 0 {
+        return 0;
+    }
+    let per_second = INTEREST_SCALE
+        + mul_div_ceil(
+            annual,
+            INTEREST_SCALE,
+            debt * BPS * SECONDS_PER_YEAR as i128,
+        );
+    let growth = pow_ceil(per_second, seconds, INTEREST_SCALE) - INTEREST_SCALE;
+    mul_div_ceil(debt, growth, INTEREST_SCALE)
+}
+
+/// Seconds within `[from, to]` during which the pegged feed was provably
+/// down: any silence between consecutive oracle updates (or since the
+/// latest one) longer than the configured threshold counts from the
+/// moment the threshold elapsed. Silence predating the oldest retained
+/// record is not provable and earns no credit; returns 0 when the policy
+/// is disabled or the feed has no history at all.
+fn outage_credit(env: &Env, state: &RWATokenStorage, from: u64, to: u64) -> u64 {
+    let threshold = state.outage_threshold;
+    if threshold == 0 {
+        return 0;
+    }
+    let client = RWAOracleClient::new(env, &state.oracle);
+    let history = match client.prices(&Asset::Other(state.pegged_asset.clone()), &u32::MAX) {
+        Some(history) => history,
+        None => return 0,
+    };
+    let mut credit = 0u64;
+    let mut prev = history.get_unchecked(0).timestamp;
+    for i in 1..=history.len() {
+        let next = if i < history.len() {
+            history.get_unchecked(i).timestamp
+        } else {
+            to
+        };
+        if next > prev + threshold {
+            let lo = (prev + threshold).max(from);
+            let hi = next.min(to);
+            if hi > lo {
+                credit += hi - lo;
+            }
+        }
+        prev = next;
+    }
+    credit
+}
+
+/// Interest owed per year on `principal`, scaled by [`BPS`]. Applies the
+/// piecewise tier schedule tranche by tranche when one is configured,
+/// otherwise the flat annual rate.
+pub(crate) fn annual_interest_amount(state: &RWATokenStorage, principal: i128) -> i128 {
+    if state.rate_tiers.is_empty() {
+        return principal * state.annual_interest_rate as i128;
+    }
+    let mut amount = 0i128;
+    let n = state.rate_tiers.len();
+    for i in 0..n {
+        let tier = state.rate_tiers.get_unchecked(i);
+        let upper = if i + 1 < n {
+            state.rate_tiers.get_unchecked(i + 1).floor
+        } else {
+            i128::MAX
+        };
+        let tranche = principal.min(upper) - tier.floor;
+        if tranche <= 0 {
+            break;
+        }
+        amount += tranche * tier.rate_bps as i128;
+    }
+    amount
+}
+
+/// Pays the flat keeper bounty out of swept-able fees when the buffer
+/// covers it, and skips payment silently otherwise, so the incentive
+/// can never block a freeze or liquidation.
+pub(crate) fn pay_keeper_bounty(
+    env: &Env,
+    state: &mut RWATokenStorage,
+    caller: &Address,
+    function: Symbol,
+) {
+    let bounty = state.keeper_bounty;
+    if bounty <= 0 || state.fees_collected - state.unstake_liability < bounty {
+        return;
+    }
+    state.fees_collected -= bounty;
+    TokenClient::new(env, &state.collateral_sac).transfer(
+        &env.current_contract_address(),
+        caller,
+        &bounty,
+    );
+    events::FeeRefunded {
+        kind: symbol_short!("bounty"),
+        recipient: caller.clone(),
+        amount: bounty,
+    }
+    .publish(env);
+    KeeperReward {
+        caller: caller.clone(),
+        function,
+        amount: bounty,
+    }
+    .publish(env);
+}
+
+/// Rejects a CDP mint that would push total supply past the configured
+/// debt ceiling; a ceiling of zero means uncapped.
+pub(crate) fn check_debt_ceiling(state: &RWATokenStorage, minted: i128) -> Result<(), Error> {
+    if state.debt_ceiling > 0 && state.total_supply + minted > state.debt_ceiling {
+        return Err(Error::DebtCeilingExceeded);
+    }
+    Ok(())
+}
+
+/// Latest price and decimals from an oracle for the given feed symbol.
+/// An oracle upgrade reporting different decimals than the recorded
+/// expectation would silently rescale every ratio, so the read halts
+/// until the admin runs `accept_feed_decimals`.
+pub(crate) fn price_of(
+    env: &Env,
+    oracle: &Address,
+    symbol: &Symbol,
+    expected_decimals: u32,
+) -> Result<(i128, u32), Error> {
+    let client = RWAOracleClient::new(env, oracle);
+    let decimals = client.decimals();
+    if decimals != expected_decimals {
+        return Err(Error::FeedDecimalsChanged);
+    }
+    match client.lastprice(&Asset::Other(symbol.clone())) {
+        Some(record) => Ok((record.price, decimals)),
+        None => Err(Error::NoOraclePrice),
+    }
+}
+
+/// Collateralization ratio in basis points; `u32::MAX` for debt-free
+/// CDPs. Rounds down, so borderline positions read as undercollateralized.
+pub(crate) fn collateralization_ratio(
+    env: &Env,
+    state: &RWATokenStorage,
+    cdp: &CDP,
+) -> Result<u32, Error> {
+    ratio_bps(
+        env,
+        state,
+        cdp.collateral_deposited,
+        cdp.asset_lent + cdp.accrued_interest,
+    )
+}
+
+/// The system-wide ratio over the maintained aggregates. Excludes
+/// accrued interest, which is not tracked globally.
+pub(crate) fn total_collat_ratio(env: &Env, state: &RWATokenStorage) -> Result<u32, Error> {
+    ratio_bps(env, state, state.total_cdp_collateral, state.total_cdp_debt)
+}
+
+/// Recovery mode engages while the TCR sits below the critical ratio;
+/// a zero critical ratio disables the mechanism entirely.
+pub(crate) fn in_recovery_mode(env: &Env, state: &RWATokenStorage) -> Result<bool, Error> {
+    if state.critical_collat_ratio == 0 {
+        return Ok(false);
+    }
+    Ok(total_collat_ratio(env, state)? < state.critical_collat_ratio)
+}
+
+/// The ratio below which a CDP may be frozen: normally the MCR, but in
+/// recovery mode the live TCR, so any position dragging the system
+/// average down can be cleared.
+fn freeze_threshold(env: &Env, state: &RWATokenStorage) -> Result<u32, Error> {
+    if state.critical_collat_ratio > 0 {
+        let tcr = total_collat_ratio(env, state)?;
+        if tcr < state.critical_collat_ratio {
+            return Ok(tcr.max(state.min_collat_ratio));
+        }
+    }
+    Ok(state.min_collat_ratio)
+}
+
+/// The MCR applied at entry: opening, borrowing, and withdrawing.
+/// Without a configured [`McrBand`] this is the static
+/// `min_collat_ratio`. With one, the collateral feed's recent return
+/// volatility scales the requirement between the band's floor and
+/// ceiling — a calm feed lets positions run tighter, a jumpy one
+/// demands a buffer — and a feed too short to measure reads as the
+/// ceiling, since calm must be proven. Liquidation keeps using the
+/// static MCR through [`freeze_threshold`], so a volatility spike
+/// tightens new exposure without retroactively freezing positions.
+pub(crate) fn entry_mcr(env: &Env, state: &RWATokenStorage) -> u32 {
+    let band = match storage::get_mcr_band(env) {
+        Some(band) => band,
+        None => return state.min_collat_ratio,
+    };
+    let client = RWAOracleClient::new(env, &state.collateral_oracle);
+    match client.volatility(&Asset::Other(state.collateral_symbol.clone()), &band.window) {
+        Some(vol) => {
+            // Ceil both conversions: uncertainty rounds against the
+            // borrower.
+            let vol_bps = mul_div_ceil(vol, BPS, pow10(state.collateral_oracle_decimals));
+            let raised = band.floor as i128 + mul_div_ceil(vol_bps, band.slope_bps as i128, BPS);
+            raised.clamp(band.floor as i128, band.ceiling as i128) as u32
+        }
+        None => band.ceiling,
+    }
+}
+
+/// `collateral / debt` at current oracle prices, in basis points,
+/// rounded down; `u32::MAX` when there is no debt.
+fn ratio_bps(
+    env: &Env,
+    state: &RWATokenStorage,
+    collateral: i128,
+    debt: i128,
+) -> Result<u32, Error> {
+    if debt == 0 {
+        return Ok(u32::MAX);
+    }
+    let (rwa_price, rwa_dec) =
+        price_of(env, &state.oracle, &state.pegged_asset, state.oracle_decimals)?;
+    let (col_price, col_dec) = price_of(
+        env,
+        &state.collateral_oracle,
+        &state.collateral_symbol,
+        state.collateral_oracle_decimals,
+    )?;
+    let col_price = haircut_stale_price(env, state, col_price);
+    let ratio = mul_div_floor(
+        collateral * col_price,
+        pow10(rwa_dec) * BPS,
+        debt * rwa_price * pow10(col_dec),
+    );
+    Ok(ratio.clamp(0, u32::MAX as i128) as u32)
+}
+
+/// Discounts a collateral price by the configured haircut for each full
+/// hour since the collateral feed's latest update, saturating at 100%,
+/// so ratio checks stay usable through a feed outage while valuing
+/// collateral ever more conservatively. A fresh feed (or a disabled
+/// haircut) passes the price through unchanged.
+fn haircut_stale_price(env: &Env, state: &RWATokenStorage, price: i128) -> i128 {
+    if state.stale_haircut_bps == 0 {
+        return price;
+    }
+    let client = RWAOracleClient::new(env, &state.collateral_oracle);
+    let updated = match client.last_timestamp_for(&Asset::Other(state.collateral_symbol.clone())) {
+        Some(updated) => updated,
+        None => return price,
+    };
+    let hours = env.ledger().timestamp().saturating_sub(updated) / 3_600;
+    let cut = (state.stale_haircut_bps as i128 * hours as i128).min(BPS);
+    price - mul_div_floor(price, cut, BPS)
+}
+
+/// Converts an RWA amount into collateral at current oracle prices.
+/// Rounds up:
+/// callers use this to size payments owed to the protocol.
+pub(crate) fn rwa_to_collateral(
+    env: &Env,
+    state: &RWATokenStorage,
+    amount: i128,
+) -> Result<i128, Error> {
+    let (rwa_price, rwa_dec) =
+        price_of(env, &state.oracle, &state.pegged_asset, state.oracle_decimals)?;
+    let (col_price, col_dec) = price_of(
+        env,
+        &state.collateral_oracle,
+        &state.collateral_symbol,
+        state.collateral_oracle_decimals,
+    )?;
+    Ok(mul_div_ceil(
+        amount * rwa_price,
+        pow10(col_dec),
+        col_price * pow10(rwa_dec),
+    ))
+}
+
+/// Converts a collateral amount into RWA at current oracle prices.
+/// Rounds down: callers use this to size payouts to users.
+#[cfg(feature = "stability-pool")]
+pub(crate) fn collateral_to_rwa(
+    env: &Env,
+    state: &RWATokenStorage,
+    amount: i128,
+) -> Result<i128, Error> {
+    let (rwa_price, rwa_dec) =
+        price_of(env, &state.oracle, &state.pegged_asset, state.oracle_decimals)?;
+    let (col_price, col_dec) = price_of(
+        env,
+        &state.collateral_oracle,
+        &state.collateral_symbol,
+        state.collateral_oracle_decimals,
+    )?;
+    Ok(mul_div_floor(
+        amount * col_price,
+        pow10(rwa_dec),
+        rwa_price * pow10(col_dec),
+    ))
+}
+
+pub(crate) fn pow10(n: u32) -> i128 {
+    10i128.pow(n)
+}
@@ -0,0 +1,322 @@
+//! Regulatory controls: account authorization flags and admin clawback,
+//! for assets whose issuers must be able to freeze holders.
+
+use soroban_sdk::{contractimpl, Address, BytesN, Env, IntoVal, Symbol};
+
+use crate::admin::require_admin;
+use crate::errors::Error;
+use crate::events::{
+    AccountFrozen, AccountUnfrozen, Clawback, ClawbackLimitChanged, StdClawback, StdSetAuthorized,
+    TransferApproved,
+};
+use crate::storage::{ClawbackLimit, ClawbackLimitProposal, ClawbackWindow, CLAWBACK_LIMIT_DELAY};
+use crate::token;
+use crate::{storage, RWAToken, RWATokenArgs, RWATokenClient};
+
+use oracle::{Asset, RWAOracleClient};
+
+#[contractimpl]
+impl RWAToken {
+    /// Marks an account as (de)authorized. Deauthorized accounts cannot
+    /// send or receive the token until re-authorized.
+    pub fn set_authorized(env: Env, id: Address, authorized: bool) -> Result<(), Error> {
+        require_admin(&env)?;
+        storage::set_authorized(&env, &id, authorized);
+        StdSetAuthorized {
+            admin: storage::get_state(&env).admin,
+            id,
+            authorize: authorized,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn authorized(env: Env, id: Address) -> bool {
+        storage::is_authorized(&env, &id)
+    }
+
+    /// Places a sanctions freeze on an account. Stronger than
+    /// deauthorization: besides blocking token movement in both
+    /// directions it also stops every modification of the account's
+    /// CDP, so a sanctioned borrower cannot keep managing a position.
+    /// `reason` is an issuer-defined code recorded in the event so the
+    /// freeze can be tied back to the off-chain case.
+    pub fn freeze_account(env: Env, id: Address, reason: u32) -> Result<(), Error> {
+        require_admin(&env)?;
+        storage::set_frozen(&env, &id, true);
+        AccountFrozen { id, reason }.publish(&env);
+        Ok(())
+    }
+
+    /// Lifts a sanctions freeze placed by [`Self::freeze_account`].
+    pub fn unfreeze_account(env: Env, id: Address) -> Result<(), Error> {
+        require_admin(&env)?;
+        storage::set_frozen(&env, &id, false);
+        AccountUnfrozen { id }.publish(&env);
+        Ok(())
+    }
+
+    pub fn frozen(env: Env, id: Address) -> bool {
+        storage::is_frozen(&env, &id)
+    }
+
+    /// Burns tokens out of an account without its signature. Admin only,
+    /// rate-limited by the configured [`ClawbackLimit`], and every burn
+    /// records the issuer's justification hash in the event so investors
+    /// can audit how the authority is used.
+    pub fn clawback(
+        env: Env,
+        from: Address,
+        amount: i128,
+        justification: BytesN<32>,
+    ) -> Result<(), Error> {
+        require_admin(&env)?;
+        if let Some(limit) = storage::get_clawback_limit(&env) {
+            let now = env.ledger().sequence();
+            let mut window = match storage::get_clawback_window(&env) {
+                Some(w) if now < w.start_ledger + limit.period_ledgers => w,
+                _ => ClawbackWindow {
+                    start_ledger: now,
+                    used: 0,
+                },
+            };
+            if window.used + amount > limit.cap {
+                return Err(Error::ClawbackCapExceeded);
+            }
+            window.used += amount;
+            storage::set_clawback_window(&env, &window);
+        }
+        token::burn_internal(&env, &from, amount)?;
+        StdClawback {
+            admin: storage::get_state(&env).admin,
+            from: from.clone(),
+            amount,
+        }
+        .publish(&env);
+        Clawback {
+            from,
+            amount,
+            justification,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Proposes a new clawback rate limit, which can be applied once the
+    /// timelock elapses. Returns the ledger it becomes applicable. A
+    /// fresh proposal replaces any pending one.
+    pub fn propose_clawback_limit(
+        env: Env,
+        cap: i128,
+        period_ledgers: u32,
+    ) -> Result<u32, Error> {
+        require_admin(&env)?;
+        if cap <= 0 || period_ledgers == 0 {
+            return Err(Error::InvalidConfiguration);
+        }
+        let effective_ledger = env.ledger().sequence() + CLAWBACK_LIMIT_DELAY;
+        storage::set_clawback_proposal(
+            &env,
+            &ClawbackLimitProposal {
+                limit: ClawbackLimit {
+                    cap,
+                    period_ledgers,
+                },
+                effective_ledger,
+            },
+        );
+        Ok(effective_ledger)
+    }
+
+    /// Applies the pending clawback limit once its timelock has elapsed.
+    /// Permissionless, so the change cannot be proposed and then quietly
+    /// withheld.
+    pub fn apply_clawback_limit(env: Env) -> Result<(), Error> {
+        let proposal = match storage::get_clawback_proposal(&env) {
+            Some(proposal) => proposal,
+            None => return Err(Error::InvalidConfiguration),
+        };
+        if env.ledger().sequence() < proposal.effective_ledger {
+            return Err(Error::TimelockNotElapsed);
+        }
+        storage::set_clawback_limit(&env, &proposal.limit);
+        storage::remove_clawback_proposal(&env);
+        ClawbackLimitChanged {
+            cap: proposal.limit.cap,
+            period_ledgers: proposal.limit.period_ledgers,
+            effective_ledger: env.ledger().sequence(),
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Transfer size above which the SEP-8 gate engages while the
+    /// oracle reports the pegged asset as regulated; 0 disables it.
+    pub fn set_regulated_threshold(env: Env, threshold: i128) -> Result<(), Error> {
+        require_admin(&env)?;
+        if threshold < 0 {
+            return Err(Error::InvalidConfiguration);
+        }
+        storage::set_regulated_threshold(&env, threshold);
+        Ok(())
+    }
+
+    pub fn regulated_threshold(env: Env) -> i128 {
+        storage::get_regulated_threshold(&env)
+    }
+
+    /// Delegates SEP-8 approvals to an approval server, or revokes the
+    /// delegation with `None`. The admin can always approve directly.
+    pub fn set_approval_server(env: Env, server: Option<Address>) -> Result<(), Error> {
+        require_admin(&env)?;
+        storage::set_approval_server(&env, &server);
+        Ok(())
+    }
+
+    pub fn approval_server(env: Env) -> Option<Address> {
+        storage::get_approval_server(&env)
+    }
+
+    /// Records a one-shot approval for a regulated transfer, the way a
+    /// SEP-8 approval server does after its off-chain checks pass. The
+    /// next transfer from `from` to `to` of up to `amount` consumes
+    /// it; `tx_id` only labels the approval in the event. Stored in
+    /// temporary storage, so unused approvals expire on their own.
+    pub fn approve_transfer(
+        env: Env,
+        caller: Address,
+        tx_id: BytesN<32>,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        let state = storage::get_state(&env);
+        let is_server = storage::get_approval_server(&env).is_some_and(|s| s == caller);
+        if caller != state.admin && !is_server {
+            return Err(Error::Unauthorized);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        storage::set_transfer_approval(&env, &from, &to, amount);
+        TransferApproved {
+            from,
+            to,
+            tx_id,
+            amount,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Wires up (or with `None` removes) a compliance hook contract.
+    /// While set, every transfer and mint first calls
+    /// `check_transfer(from, to, amount)` on it and aborts unless the
+    /// hook allows the movement, so issuers can bolt on
+    /// jurisdiction-specific rules without forking the token.
+    pub fn set_compliance_contract(env: Env, hook: Option<Address>) -> Result<(), Error> {
+        require_admin(&env)?;
+        storage::set_compliance_hook(&env, &hook);
+        Ok(())
+    }
+
+    pub fn compliance_contract(env: Env) -> Option<Address> {
+        storage::get_compliance_hook(&env)
+    }
+
+    /// Points the token at a KYC registry, or removes it with `None`.
+    /// While set, `open_cdp`, `borrow_rwa` and `stake` require the
+    /// caller to be attested there: a regulated issuer cannot let
+    /// anonymous addresses mint exposure to the underlying security.
+    pub fn set_kyc_registry(env: Env, registry: Option<Address>) -> Result<(), Error> {
+        require_admin(&env)?;
+        storage::set_kyc_registry(&env, &registry);
+        Ok(())
+    }
+
+    pub fn kyc_registry(env: Env) -> Option<Address> {
+        storage::get_kyc_registry(&env)
+    }
+
+    pub fn clawback_limit(env: Env) -> Option<ClawbackLimit> {
+        storage::get_clawback_limit(&env)
+    }
+}
+
+/// SEP-8 gate on the transfer entrypoints: while the oracle's metadata
+/// marks the pegged asset as regulated, a transfer above the configured
+/// threshold must consume a recorded approval for its exact lane and at
+/// least its amount.
+pub(crate) fn require_transfer_approval(
+    env: &Env,
+    from: &Address,
+    to: &Address,
+    amount: i128,
+) -> Result<(), Error> {
+    let threshold = storage::get_regulated_threshold(env);
+    if threshold == 0 || amount <= threshold {
+        return Ok(());
+    }
+    let state = storage::get_state(env);
+    let regulated = RWAOracleClient::new(env, &state.oracle)
+        .get_rwa_metadata(&Asset::Other(state.pegged_asset.clone()))
+        .is_some_and(|m| m.regulatory_info.is_regulated);
+    if !regulated {
+        return Ok(());
+    }
+    match storage::get_transfer_approval(env, from, to) {
+        Some(approved) if approved >= amount => {
+            storage::remove_transfer_approval(env, from, to);
+            Ok(())
+        }
+        _ => Err(Error::TransferNotApproved),
+    }
+}
+
+/// Consults the configured compliance hook about a token movement, if
+/// one is set. Mints pass the token contract itself as `from`. Unlike
+/// the best-effort receipt hook, a rejection — or a hook that traps —
+/// must stop the movement, so this is a plain `invoke_contract`.
+pub(crate) fn check_transfer_hook(
+    env: &Env,
+    from: &Address,
+    to: &Address,
+    amount: i128,
+) -> Result<(), Error> {
+    if let Some(hook) = storage::get_compliance_hook(env) {
+        let allowed: bool = env.invoke_contract(
+            &hook,
+            &Symbol::new(env, "check_transfer"),
+            (from.clone(), to.clone(), amount).into_val(env),
+        );
+        if !allowed {
+            return Err(Error::TransferRejected);
+        }
+    }
+    Ok(())
+}
+
+/// Errors if a sanctions freeze is in place on `who`.
+pub(crate) fn require_not_frozen(env: &Env, who: &Address) -> Result<(), Error> {
+    if storage::is_frozen(env, who) {
+        return Err(Error::AccountFrozen);
+    }
+    Ok(())
+}
+
+/// Requires `who` to be attested in the configured KYC registry via
+/// its `is_attested(address)` view; no registry means no restriction.
+pub(crate) fn require_kyc(env: &Env, who: &Address) -> Result<(), Error> {
+    if let Some(registry) = storage::get_kyc_registry(env) {
+        let attested: bool = env.invoke_contract(
+            &registry,
+            &Symbol::new(env, "is_attested"),
+            (who.clone(),).into_val(env),
+        );
+        if !attested {
+            return Err(Error::KycRequired);
+        }
+    }
+    Ok(())
+}
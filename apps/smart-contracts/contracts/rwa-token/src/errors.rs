@@ -0,0 +1,44 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    Unauthorized = 1,
+    InvalidAmount = 2,
+    InsufficientBalance = 3,
+    InsufficientAllowance = 4,
+    DeauthorizedAccount = 5,
+    CDPNotFound = 6,
+    CDPAlreadyExists = 7,
+    CDPNotOpen = 8,
+    CDPNotFrozen = 9,
+    CollateralRatioBelowMinimum = 10,
+    CollateralRatioSafe = 11,
+    NoOraclePrice = 12,
+    OutstandingInterest = 13,
+    OutstandingDebt = 14,
+    StakeAlreadyExists = 15,
+    StakeNotFound = 16,
+    PoolEmpty = 18,
+    InvalidConfiguration = 19,
+    DeadlineExpired = 20,
+    DebtCeilingExceeded = 21,
+    ClawbackCapExceeded = 22,
+    TimelockNotElapsed = 23,
+    DepositTooSmall = 24,
+    PoolNotEmpty = 25,
+    AuctionActive = 26,
+    AuctionNotFound = 27,
+    FeedDecimalsChanged = 28,
+    RecoveryModeActive = 29,
+    DebtBelowMinimum = 30,
+    NotInEmergencyMode = 31,
+    ClaimNotOpen = 32,
+    ContractPaused = 33,
+    TransferNotApproved = 34,
+    TransferRejected = 35,
+    KycRequired = 36,
+    AccountFrozen = 37,
+    WithdrawalNotRequested = 38,
+}
@@ -0,0 +1,558 @@
+use soroban_sdk::{contractevent, Address, Env, Symbol, Vec};
+#[cfg(feature = "compliance")]
+use soroban_sdk::BytesN;
+
+use crate::storage::{self, LiquidationSplit, RateTier, RevenueSource, Role};
+
+/// Publishes an [`OpMetered`] sample for `op` if metering is enabled.
+/// `entries_touched` counts the storage entries the call visited; the
+/// CPU bucket is its base-2 magnitude, coarse on purpose so samples
+/// aggregate cleanly across calls of similar shape.
+pub(crate) fn meter(env: &Env, op: Symbol, entries_touched: u32) {
+    if !storage::metering_enabled(env) {
+        return;
+    }
+    OpMetered {
+        op,
+        cpu_estimate_bucket: 32 - entries_touched.max(1).leading_zeros(),
+        entries_touched,
+    }
+    .publish(env);
+}
+
+// --- Canonical SEP-41 events -----------------------------------------
+//
+// The protocol's own events below carry richer payloads for its
+// indexer; these mirror the Stellar Asset Contract's topic and data
+// layout so generic explorers and wallets see activity for the token
+// without a custom decoder.
+
+/// Canonical `transfer` event: topics `[from, to]`, data the amount.
+#[contractevent(topics = ["transfer"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StdTransfer {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+}
+
+/// Canonical `approve` event: data is `[amount, expiration_ledger]`.
+#[contractevent(topics = ["approve"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StdApprove {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub spender: Address,
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+/// Canonical `burn` event, published wherever tokens are destroyed.
+#[contractevent(topics = ["burn"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StdBurn {
+    #[topic]
+    pub from: Address,
+    pub amount: i128,
+}
+
+/// Canonical `clawback` event; the richer [`Clawback`] with the
+/// issuer's justification hash is published beside it.
+#[cfg(feature = "compliance")]
+#[contractevent(topics = ["clawback"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StdClawback {
+    #[topic]
+    pub admin: Address,
+    #[topic]
+    pub from: Address,
+    pub amount: i128,
+}
+
+/// Published when the admin or the SEP-8 approval server records a
+/// one-shot approval for a regulated transfer; `tx_id` labels it for
+/// off-chain audit.
+#[cfg(feature = "compliance")]
+#[contractevent(topics = ["tx_approved"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransferApproved {
+    #[topic]
+    pub from: Address,
+    pub to: Address,
+    pub tx_id: BytesN<32>,
+    pub amount: i128,
+}
+
+/// Canonical `set_authorized` event.
+#[cfg(feature = "compliance")]
+#[contractevent(topics = ["set_authorized"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StdSetAuthorized {
+    #[topic]
+    pub admin: Address,
+    #[topic]
+    pub id: Address,
+    pub authorize: bool,
+}
+
+/// One storage schema conversion step ran during `migrate`.
+#[contractevent(topics = ["migrated"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SchemaMigrated {
+    pub from: u32,
+    pub to: u32,
+}
+
+/// The admin granted a permission role to an address.
+#[contractevent(topics = ["role_granted"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleGranted {
+    #[topic]
+    pub role: Role,
+    pub address: Address,
+}
+
+/// The admin revoked a permission role from an address.
+#[contractevent(topics = ["role_revoked"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleRevoked {
+    #[topic]
+    pub role: Role,
+    pub address: Address,
+}
+
+/// A sanctions freeze was placed on an account. `reason` is an
+/// issuer-defined code tying the action back to the off-chain case.
+#[cfg(feature = "compliance")]
+#[contractevent(topics = ["acct_frozen"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountFrozen {
+    #[topic]
+    pub id: Address,
+    pub reason: u32,
+}
+
+/// A sanctions freeze was lifted from an account.
+#[cfg(feature = "compliance")]
+#[contractevent(topics = ["acct_unfrozen"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountUnfrozen {
+    #[topic]
+    pub id: Address,
+}
+
+/// Published when new RWA tokens are minted against a CDP.
+#[contractevent(topics = ["minted"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Minted {
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+}
+
+/// Published when RWA tokens are burned (repayment or liquidation).
+#[contractevent(topics = ["burned"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Burned {
+    #[topic]
+    pub from: Address,
+    pub amount: i128,
+}
+
+/// Published when a holder redeems RWA at face value for collateral
+/// drawn from the riskiest CDPs.
+#[contractevent(topics = ["redeemed"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Redeemed {
+    #[topic]
+    pub redeemer: Address,
+    pub rwa_burned: i128,
+    pub collateral_out: i128,
+}
+
+/// Published when a lender repays CDP principal in kind, selling
+/// position collateral to the protocol at oracle price.
+#[contractevent(topics = ["deleveraged"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Deleveraged {
+    #[topic]
+    pub lender: Address,
+    pub debt_repaid: i128,
+    pub collateral_spent: i128,
+}
+
+/// Published when a keeper is paid the flat bounty for freezing or
+/// liquidating a CDP; `function` names the entrypoint that earned it.
+#[contractevent(topics = ["keeper"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeeperReward {
+    #[topic]
+    pub caller: Address,
+    pub function: Symbol,
+    pub amount: i128,
+}
+
+/// Summary of a keeper sweep: how many undercollateralized CDPs were
+/// frozen in one call and the total debt they carried.
+#[contractevent(topics = ["sweep"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsolventSwept {
+    pub count: u32,
+    pub debt_frozen: i128,
+}
+
+/// Published when a frozen CDP's collateral goes to Dutch auction
+/// because the stability pool had no depth to absorb it.
+#[contractevent(topics = ["auction"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionStarted {
+    #[topic]
+    pub lender: Address,
+    pub collateral_lot: i128,
+    pub debt: i128,
+    pub start_ledger: u32,
+}
+
+/// Published for each auction bid: `rwa_paid` was burned against the
+/// CDP's debt in exchange for `collateral_sold`.
+#[contractevent(topics = ["auc_bid"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionBid {
+    #[topic]
+    pub bidder: Address,
+    pub lender: Address,
+    pub collateral_sold: i128,
+    pub rwa_paid: i128,
+}
+
+/// Published when an auction clears: either the debt was fully covered
+/// (leftover collateral returned to the lender) or the lot sold out,
+/// leaving `debt_shortfall` written off against the peg.
+#[contractevent(topics = ["auc_done"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionSettled {
+    #[topic]
+    pub lender: Address,
+    pub debt_shortfall: i128,
+    pub collateral_returned: i128,
+}
+
+/// Consolidated snapshot published whenever any risk parameter changes:
+/// MCR, interest rate, fees, or oracle addresses. Downstream systems can
+/// diff old and new values and react from `effective_ledger` onward.
+#[contractevent(topics = ["risk_params"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RiskParamsChanged {
+    pub old_min_collat_ratio: u32,
+    pub new_min_collat_ratio: u32,
+    pub old_critical_collat_ratio: u32,
+    pub new_critical_collat_ratio: u32,
+    pub old_debt_ceiling: i128,
+    pub new_debt_ceiling: i128,
+    pub old_interest_rate: u32,
+    pub new_interest_rate: u32,
+    pub old_liquidation_penalty: u32,
+    pub new_liquidation_penalty: u32,
+    pub old_stake_fee: i128,
+    pub new_stake_fee: i128,
+    pub old_oracle: Address,
+    pub new_oracle: Address,
+    pub old_collateral_oracle: Address,
+    pub new_collateral_oracle: Address,
+    pub effective_ledger: u32,
+}
+
+/// Published when the stability pool empties completely and its epoch
+/// rolls over, carrying the closed epoch's final constants so dashboards
+/// need not infer the reset from storage diffs.
+#[cfg(feature = "stability-pool")]
+#[contractevent(topics = ["epoch"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EpochAdvanced {
+    pub old_epoch: u64,
+    pub new_epoch: u64,
+    pub final_compounded_constant: i128,
+    pub interest_recorded: i128,
+}
+
+/// Interest was added to a CDP's balance owed during a state-changing
+/// call. Denominated in RWA debt units; the collateral leg appears on
+/// [`InterestPaid`], where it is actually charged. Views project
+/// interest without publishing, so summing these reconciles exactly
+/// with stored debt.
+#[contractevent(topics = ["int_accrued"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterestAccrued {
+    #[topic]
+    pub lender: Address,
+    pub amount: i128,
+    pub epoch: u64,
+}
+
+/// Accrued interest was settled: `amount` RWA debt units cleared
+/// against `collateral_paid` of collateral charged to the payer.
+#[contractevent(topics = ["int_paid"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterestPaid {
+    #[topic]
+    pub lender: Address,
+    pub amount: i128,
+    pub collateral_paid: i128,
+    pub epoch: u64,
+}
+
+/// Published when a share of an interest payment streams on to the
+/// data-fee account funding the price feeds.
+#[contractevent(topics = ["data_fee"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DataFeeStreamed {
+    #[topic]
+    pub account: Address,
+    pub amount: i128,
+}
+
+/// Published when the admin or pauser halts state-changing entrypoints.
+#[contractevent(topics = ["paused"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Paused {
+    #[topic]
+    pub by: Address,
+}
+
+/// Published when normal operation resumes after a pause.
+#[contractevent(topics = ["unpaused"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Unpaused {
+    #[topic]
+    pub by: Address,
+}
+
+/// Summary of a keeper-driven reward settlement: how many positions
+/// were settled, the rewards that went to their owners, and the tips
+/// the caller collected.
+#[cfg(feature = "stability-pool")]
+#[contractevent(topics = ["claim_for"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RewardsClaimedFor {
+    #[topic]
+    pub caller: Address,
+    pub claims: u32,
+    pub rewards_settled: i128,
+    pub tips_paid: i128,
+}
+
+/// Published when the admin flips emergency mode, which opens (or
+/// closes) principal-only pool exits during an incident or shutdown.
+#[contractevent(topics = ["emergency"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmergencyModeChanged {
+    pub enabled: bool,
+}
+
+/// Published when a staker takes the emergency exit: the compounded
+/// principal went back to them and any unclaimed rewards fell to the
+/// treasury.
+#[cfg(feature = "stability-pool")]
+#[contractevent(topics = ["emg_exit"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmergencyWithdrawn {
+    #[topic]
+    pub staker: Address,
+    pub principal: i128,
+    pub forfeited_reward: i128,
+}
+
+/// RWA locked into the savings facility.
+#[contractevent(topics = ["saved"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SavingsDeposited {
+    #[topic]
+    pub holder: Address,
+    pub amount: i128,
+}
+
+/// RWA unlocked from the savings facility.
+#[contractevent(topics = ["unsaved"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SavingsWithdrawn {
+    #[topic]
+    pub holder: Address,
+    pub amount: i128,
+}
+
+/// A saver's accumulated interest share, paid out in collateral.
+#[contractevent(topics = ["sav_claim"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SavingsClaimed {
+    #[topic]
+    pub holder: Address,
+    pub amount: i128,
+}
+
+/// The savings-rate share of one interest payment, credited to locked
+/// savings pro rata.
+#[contractevent(topics = ["sav_fund"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SavingsFunded {
+    pub amount: i128,
+}
+
+/// A frontend operator's cut of a staker's settled reward, carved out
+/// before the remainder is paid or compounded.
+#[cfg(feature = "stability-pool")]
+#[contractevent(topics = ["kickback"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KickbackPaid {
+    #[topic]
+    pub frontend: Address,
+    pub staker: Address,
+    pub amount: i128,
+}
+
+/// A stability pool position changed hands whole: rewards were settled
+/// to the old owner and the deposit re-snapshotted under the new one.
+#[cfg(feature = "stability-pool")]
+#[contractevent(topics = ["stake_xfer"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StakeTransferred {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+}
+
+/// A staker served notice of exit under the withdrawal cooldown; the
+/// amount becomes withdrawable at `ready_at` but keeps absorbing
+/// liquidations until then.
+#[cfg(feature = "stability-pool")]
+#[contractevent(topics = ["wd_request"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithdrawalRequested {
+    #[topic]
+    pub staker: Address,
+    pub amount: i128,
+    pub ready_at: u64,
+}
+
+/// Published when a flash mint completes: `amount` was minted, the
+/// callback ran, and `amount + fee` was burned back in the same call.
+#[contractevent(topics = ["flash"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FlashMinted {
+    #[topic]
+    pub receiver: Address,
+    pub amount: i128,
+    pub fee: i128,
+}
+
+/// Per-call resource sample from one of the heaviest entrypoints. Only
+/// published while the admin has metering switched on, so testnet
+/// deployments can collect real resource profiles without adding event
+/// overhead to every mainnet call.
+#[contractevent(topics = ["metered"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OpMetered {
+    #[topic]
+    pub op: Symbol,
+    pub cpu_estimate_bucket: u32,
+    pub entries_touched: u32,
+}
+
+/// Collateral entered the protocol's fee balance. `kind` tags the
+/// lane (`originate`, `redeem`, `stake`, `forfeit`, `compound`,
+/// `liq_pen`) and `payer` is the account whose collateral funded it,
+/// so revenue can be attributed without replaying every entrypoint.
+#[contractevent(topics = ["fee_in"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeCollected {
+    #[topic]
+    pub kind: Symbol,
+    pub payer: Address,
+    pub amount: i128,
+}
+
+/// Collateral left the fee balance to someone other than the treasury
+/// (sweeps publish [`FeeSweep`] instead): unstake refunds and keeper
+/// bounties.
+#[contractevent(topics = ["fee_out"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeRefunded {
+    #[topic]
+    pub kind: Symbol,
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+/// Published when the admin moves accumulated protocol collateral out
+/// to the treasury; `bucket` names the buffer that was drained.
+#[contractevent(topics = ["fee_sweep"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeSweep {
+    #[topic]
+    pub treasury: Address,
+    pub bucket: Symbol,
+    pub amount: i128,
+}
+
+/// Published each time the protocol earns collateral, tagged by source so
+/// off-chain P&L reporting can segment income streams.
+#[contractevent(topics = ["revenue"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RevenueAccrued {
+    #[topic]
+    pub source: RevenueSource,
+    pub amount: i128,
+}
+
+/// Published on every admin clawback, carrying the justification hash
+/// the issuer supplied for the burn.
+#[cfg(feature = "compliance")]
+#[contractevent(topics = ["clawback"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Clawback {
+    #[topic]
+    pub from: Address,
+    pub amount: i128,
+    pub justification: BytesN<32>,
+}
+
+/// Published when a proposed clawback limit clears its timelock and
+/// takes effect.
+#[cfg(feature = "compliance")]
+#[contractevent(topics = ["cb_limit"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClawbackLimitChanged {
+    pub cap: i128,
+    pub period_ledgers: u32,
+    pub effective_ledger: u32,
+}
+
+/// Published when the issuer adjusts the rebasing supply factor, e.g.
+/// to track principal amortization of the underlying instrument.
+#[contractevent(topics = ["rebase"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SupplyFactorChanged {
+    pub old_factor: i128,
+    pub new_factor: i128,
+    pub effective_ledger: u32,
+}
+
+/// Published when the liquidation reward split is reconfigured.
+#[contractevent(topics = ["liq_split"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LiquidationSplitChanged {
+    pub split: LiquidationSplit,
+    pub effective_ledger: u32,
+}
+
+/// Published when the piecewise interest schedule is replaced.
+#[contractevent(topics = ["rate_tiers"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateTiersChanged {
+    pub tiers: Vec<RateTier>,
+    pub effective_ledger: u32,
+}
@@ -0,0 +1,142 @@
+//! RWA-pegged token with collateralized debt positions and a stability
+//! pool, priced against the RWA oracle.
+//!
+//! Holders mint the token by locking collateral in a CDP; positions
+//! below the minimum collateralization ratio can be frozen and
+//! liquidated against the stability pool. One contract bundles the
+//! SEP-41 token, CDP, pool, and admin surfaces; the pool and the
+//! regulatory controls sit behind the `stability-pool` and `compliance`
+//! cargo features so deployments can compile out subsystems they do not
+//! need.
+#![no_std]
+
+mod admin;
+mod auction;
+mod cdp;
+#[cfg(feature = "compliance")]
+mod compliance;
+mod errors;
+mod events;
+#[cfg(feature = "stability-pool")]
+mod pool;
+mod rounding;
+mod savings;
+mod storage;
+mod token;
+
+#[cfg(test)]
+mod test;
+
+pub use crate::errors::Error;
+pub use crate::storage::{
+    ActionPreview, Auction, CDPStatus, ClawbackLimit, CompoundRecord, EpochSummary, HealthReport,
+    InterestRecord, LiquidationRecord, LiquidationShare, McrBand,
+    LiquidationSplit, RWATokenStorage, RateTier, RevenueBreakdown, RevenueSource, RewardMode, Role,
+    SavingsPosition, StakePosition, WithdrawalRequest, CDP,
+};
+
+use soroban_sdk::{contract, contractimpl, Address, Env, String, Symbol};
+
+use oracle::RWAOracleClient;
+
+use crate::storage::{FACTOR_SCALE, INTEREST_SCALE, POOL_SCALE};
+
+/// Default flat collateral fee charged when opening a stake position.
+pub const DEFAULT_STAKE_FEE: i128 = 7_0000000;
+
+#[contract]
+pub struct RWAToken;
+
+#[contractimpl]
+impl RWAToken {
+    #[allow(clippy::too_many_arguments)]
+    pub fn __constructor(
+        env: Env,
+        admin: Address,
+        name: String,
+        symbol: String,
+        pegged_asset: Symbol,
+        oracle: Address,
+        collateral_oracle: Address,
+        collateral_sac: Address,
+        collateral_symbol: Symbol,
+        min_collat_ratio: u32,
+        annual_interest_rate: u32,
+    ) {
+        if min_collat_ratio < 10_000 {
+            panic!("min_collat_ratio must be at least 100%");
+        }
+        // Record the oracles' decimals so a later upgrade changing them
+        // halts price math instead of silently rescaling it.
+        let oracle_decimals = RWAOracleClient::new(&env, &oracle).decimals();
+        let collateral_oracle_decimals = RWAOracleClient::new(&env, &collateral_oracle).decimals();
+        storage::set_state(
+            &env,
+            &RWATokenStorage {
+                admin,
+                name,
+                symbol,
+                decimals: 7,
+                pegged_asset,
+                oracle,
+                collateral_oracle,
+                collateral_sac,
+                collateral_symbol,
+                oracle_decimals,
+                collateral_oracle_decimals,
+                min_collat_ratio,
+                critical_collat_ratio: 0,
+                debt_ceiling: 0,
+                min_debt: 0,
+                annual_interest_rate,
+                rate_tiers: soroban_sdk::Vec::new(&env),
+                borrow_index: INTEREST_SCALE,
+                index_updated_at: env.ledger().timestamp(),
+                supply_factor: FACTOR_SCALE,
+                outage_threshold: 0,
+                stale_haircut_bps: 0,
+                liquidation_split: storage::LiquidationSplit {
+                    pool_bps: 10_000,
+                    caller_bps: 0,
+                    treasury_bps: 0,
+                },
+                liquidation_penalty_bps: 0,
+                stake_fee: DEFAULT_STAKE_FEE,
+                keeper_bounty: 0,
+                min_stake: 0,
+                data_fee_bps: 0,
+                flash_mint_fee_bps: 0,
+                origination_fee_bps: 0,
+                redemption_fee_floor_bps: 0,
+                redemption_base_rate_bps: 0,
+                base_rate_updated_at: env.ledger().timestamp(),
+                redemption_decay_secs: 0,
+                total_supply: 0,
+                treasury: None,
+                fees_collected: 0,
+                unstake_liability: 0,
+                interest_collected: 0,
+                interest_current_epoch: 0,
+                revenue: storage::RevenueBreakdown {
+                    origination_fees: 0,
+                    interest: 0,
+                    liquidation_penalties: 0,
+                    redemption_fees: 0,
+                    pool_fees: 0,
+                },
+                total_cdp_debt: 0,
+                total_cdp_collateral: 0,
+                redemption_buffer: 0,
+                total_rwa_deposited: 0,
+                total_pool_collateral: 0,
+                current_epoch: 0,
+                compounded_constant: POOL_SCALE,
+                reward_constant: 0,
+                last_reward_time: 0,
+                claim_delay: 0,
+                claim_tip_bps: 0,
+            },
+        );
+        storage::set_schema_version(&env, storage::SCHEMA_VERSION);
+    }
+}
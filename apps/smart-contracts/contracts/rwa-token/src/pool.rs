@@ -0,0 +1,978 @@
+//! Stability pool: stakers deposit RWA that absorbs liquidated debt in
+//! exchange for the liquidated collateral, Liquity-style. Deposits
+//! compound down via `compounded_constant`; collateral gains accrue via
+//! `reward_constant`. The epoch advances whenever the pool fully empties.
+//! Liquidation lives here too, since it settles against the pool.
+//!
+//! Reward accounting deliberately tracks a single collateral stream:
+//! each deployment binds exactly one collateral asset (see
+//! `RWATokenStorage::debt_ceiling`), so liquidation proceeds only ever
+//! arrive in that asset. Should multi-collateral CDPs land, the single
+//! `reward_constant` would have to become a per-asset accumulator with
+//! matching per-asset snapshots, behind a schema migration.
+
+use soroban_sdk::{contractimpl, symbol_short, token::TokenClient, Address, Env, MuxedAddress, Vec};
+
+use crate::admin::require_not_paused;
+use crate::cdp::{collateral_to_rwa, notify_receipt_hook, pay_keeper_bounty};
+use crate::errors::Error;
+use crate::events::{
+    self, Burned, EmergencyWithdrawn, EpochAdvanced, KickbackPaid, Minted, RevenueAccrued,
+    RewardsClaimedFor, StakeTransferred, WithdrawalRequested,
+};
+use crate::rounding::mul_div_floor;
+use crate::storage::{
+    self, CDPStatus, CompoundRecord, InterestRecord, LiquidationRecord, LiquidationShare,
+    EpochSummary, RWATokenStorage, RevenueSource, RewardMode, StakePosition, WithdrawalRequest,
+    BPS, POOL_SCALE, UNSTAKE_RETURN,
+};
+use crate::token;
+use crate::{RWAToken, RWATokenArgs, RWATokenClient};
+
+#[contractimpl]
+impl RWAToken {
+    /// Opens a stability pool position, charging the flat stake fee in
+    /// collateral. An optional `frontend` tag credits that operator
+    /// with the configured kickback share of every reward this position
+    /// settles, Liquity-style, so third-party UIs earn from the
+    /// deposits they bring in. The tag is fixed for the position's
+    /// life; `deposit` top-ups inherit it.
+    pub fn stake(
+        env: Env,
+        staker: Address,
+        amount: i128,
+        frontend: Option<Address>,
+    ) -> Result<(), Error> {
+        staker.require_auth();
+        require_not_paused(&env)?;
+        #[cfg(feature = "compliance")]
+        crate::compliance::require_kyc(&env, &staker)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if storage::get_stake(&env, &staker).is_some() {
+            return Err(Error::StakeAlreadyExists);
+        }
+        let mut state = storage::get_state(&env);
+        if amount < state.min_stake {
+            return Err(Error::DepositTooSmall);
+        }
+        TokenClient::new(&env, &state.collateral_sac).transfer(
+            &staker,
+            env.current_contract_address(),
+            &state.stake_fee,
+        );
+        state.fees_collected += state.stake_fee;
+        state.unstake_liability += UNSTAKE_RETURN;
+        events::FeeCollected {
+            kind: symbol_short!("stake"),
+            payer: staker.clone(),
+            amount: state.stake_fee,
+        }
+        .publish(&env);
+        // Only the non-refundable portion of the fee is revenue; the
+        // rest is held for the unstake refund.
+        state.revenue.pool_fees += state.stake_fee - UNSTAKE_RETURN;
+        RevenueAccrued {
+            source: RevenueSource::PoolFee,
+            amount: state.stake_fee - UNSTAKE_RETURN,
+        }
+        .publish(&env);
+        token::spend_balance(&env, &staker, amount)?;
+        token::receive_balance(&env, &env.current_contract_address(), amount)?;
+        if let Some(frontend) = &frontend {
+            storage::set_frontend(&env, &staker, frontend);
+        }
+        storage::set_stake(
+            &env,
+            &StakePosition {
+                owner: staker,
+                deposited: amount,
+                p_snapshot: state.compounded_constant,
+                s_snapshot: state.reward_constant,
+                epoch: state.current_epoch,
+                reward_mode: RewardMode::Collateral,
+            },
+        );
+        state.total_rwa_deposited += amount;
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    pub fn frontend_tag(env: Env, staker: Address) -> Option<Address> {
+        storage::get_frontend(&env, &staker)
+    }
+
+    /// Sets the share of settled rewards carved out for a position's
+    /// frontend tag, in basis points; zero disables kickbacks. Admin
+    /// only.
+    pub fn set_frontend_kickback(env: Env, bps: u32) -> Result<(), Error> {
+        crate::admin::require_admin(&env)?;
+        if bps as i128 > BPS {
+            return Err(Error::InvalidConfiguration);
+        }
+        storage::set_frontend_kickback_bps(&env, bps);
+        Ok(())
+    }
+
+    pub fn frontend_kickback(env: Env) -> u32 {
+        storage::get_frontend_kickback_bps(&env)
+    }
+
+    /// Adds to an existing position. Pending rewards are settled first,
+    /// per the position's [`RewardMode`], so the snapshot reset cannot
+    /// discard them.
+    pub fn deposit(env: Env, staker: Address, amount: i128) -> Result<(), Error> {
+        staker.require_auth();
+        require_not_paused(&env)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let position = require_stake(&env, &staker)?;
+        let mut state = storage::get_state(&env);
+        let (_, rwa_gain) = settle_reward(&env, &mut state, &position, &staker.clone().into())?;
+        let compounded = compounded_deposit(&state, &position) + rwa_gain;
+        // Liquidations may have eaten the deposit below the minimum; a
+        // top-up must bring it back over the line.
+        if compounded + amount < state.min_stake {
+            return Err(Error::DepositTooSmall);
+        }
+        token::spend_balance(&env, &staker, amount)?;
+        token::receive_balance(&env, &env.current_contract_address(), amount)?;
+        storage::set_stake(
+            &env,
+            &StakePosition {
+                owner: staker,
+                deposited: compounded + amount,
+                p_snapshot: state.compounded_constant,
+                s_snapshot: state.reward_constant,
+                epoch: state.current_epoch,
+                reward_mode: position.reward_mode,
+            },
+        );
+        state.total_rwa_deposited += amount;
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    /// Moves the caller's entire pool position to another wallet:
+    /// pending rewards are settled to `from` first (per the position's
+    /// [`RewardMode`]), then the compounded deposit is re-snapshotted
+    /// under `to` at the live pool constants. The position moves whole
+    /// — deposits are only comparable after settling, and the unstake
+    /// refund reservation travels with the position, so partial share
+    /// transfers would either strand or mint refund liabilities. Any
+    /// pending withdrawal notice stays behind; the new owner files
+    /// their own. One position per wallet still holds.
+    pub fn transfer_stake(env: Env, from: Address, to: Address) -> Result<(), Error> {
+        from.require_auth();
+        require_not_paused(&env)?;
+        #[cfg(feature = "compliance")]
+        crate::compliance::require_kyc(&env, &to)?;
+        let position = require_stake(&env, &from)?;
+        if storage::get_stake(&env, &to).is_some() {
+            return Err(Error::StakeAlreadyExists);
+        }
+        let mut state = storage::get_state(&env);
+        let (_, rwa_gain) = settle_reward(&env, &mut state, &position, &from.clone().into())?;
+        let compounded = compounded_deposit(&state, &position) + rwa_gain;
+        storage::remove_stake(&env, &from);
+        storage::remove_withdrawal_request(&env, &from);
+        // The tag follows the position: kickbacks reward whoever
+        // brought the deposit in, not where it later lives.
+        if let Some(frontend) = storage::get_frontend(&env, &from) {
+            storage::set_frontend(&env, &to, &frontend);
+            storage::remove_frontend(&env, &from);
+        }
+        storage::set_stake(
+            &env,
+            &StakePosition {
+                owner: to.clone(),
+                deposited: compounded,
+                p_snapshot: state.compounded_constant,
+                s_snapshot: state.reward_constant,
+                epoch: state.current_epoch,
+                reward_mode: position.reward_mode,
+            },
+        );
+        storage::set_state(&env, &state);
+        StakeTransferred {
+            from,
+            to,
+            amount: compounded,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Serves notice of exit while a withdrawal cooldown is configured:
+    /// `withdraw` (and `unstake`) only succeed once the cooldown has
+    /// elapsed since the most recent notice, so stakers cannot pull
+    /// their deposit the moment a liquidation heads for the pool. The
+    /// noticed amount keeps absorbing liquidations until it leaves.
+    /// Renoticing replaces the pending request and restarts the timer.
+    pub fn request_withdrawal(env: Env, staker: Address, amount: i128) -> Result<(), Error> {
+        staker.require_auth();
+        require_not_paused(&env)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        require_stake(&env, &staker)?;
+        let ready_at = env.ledger().timestamp() + storage::get_withdraw_cooldown(&env);
+        storage::set_withdrawal_request(
+            &env,
+            &WithdrawalRequest {
+                owner: staker.clone(),
+                amount,
+                ready_at,
+            },
+        );
+        WithdrawalRequested {
+            staker,
+            amount,
+            ready_at,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn withdrawal_request(env: Env, staker: Address) -> Option<WithdrawalRequest> {
+        storage::get_withdrawal_request(&env, &staker)
+    }
+
+    /// Sets the seconds between a withdrawal notice and the exit it
+    /// unlocks; zero restores instant exits. Admin only.
+    pub fn set_withdraw_cooldown(env: Env, secs: u64) -> Result<(), Error> {
+        crate::admin::require_admin(&env)?;
+        storage::set_withdraw_cooldown(&env, secs);
+        Ok(())
+    }
+
+    pub fn withdraw_cooldown(env: Env) -> u64 {
+        storage::get_withdraw_cooldown(&env)
+    }
+
+    /// Withdraws part of the compounded deposit, settling any pending
+    /// rewards on the way out. While a cooldown is configured the amount
+    /// must be covered by an elapsed `request_withdrawal` notice.
+    pub fn withdraw(env: Env, staker: Address, amount: i128) -> Result<(), Error> {
+        staker.require_auth();
+        require_not_paused(&env)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let position = require_stake(&env, &staker)?;
+        consume_withdrawal_notice(&env, &staker, amount)?;
+        let mut state = storage::get_state(&env);
+        let (_, rwa_gain) = settle_reward(&env, &mut state, &position, &staker.clone().into())?;
+        let compounded = compounded_deposit(&state, &position) + rwa_gain;
+        if amount > compounded {
+            return Err(Error::InsufficientBalance);
+        }
+        // A partial withdrawal may empty the deposit but not leave dust
+        // below the minimum; use `unstake` to exit entirely.
+        let remaining = compounded - amount;
+        if remaining > 0 && remaining < state.min_stake {
+            return Err(Error::DepositTooSmall);
+        }
+        token::spend_balance(&env, &env.current_contract_address(), amount)?;
+        token::receive_balance(&env, &staker, amount)?;
+        storage::set_stake(
+            &env,
+            &StakePosition {
+                owner: staker,
+                deposited: compounded - amount,
+                p_snapshot: state.compounded_constant,
+                s_snapshot: state.reward_constant,
+                epoch: state.current_epoch,
+                reward_mode: position.reward_mode,
+            },
+        );
+        state.total_rwa_deposited -= amount;
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    /// Fully exits the pool: returns the compounded deposit, pays out any
+    /// collateral rewards, and refunds part of the stake fee. A muxed
+    /// `staker` is resolved to its underlying account for auth and the
+    /// position lookup; the collateral payout keeps the mux id.
+    pub fn unstake(env: Env, staker: MuxedAddress) -> Result<(), Error> {
+        let payout = staker;
+        let staker = payout.address();
+        staker.require_auth();
+        require_not_paused(&env)?;
+        let position = require_stake(&env, &staker)?;
+        // A full exit needs an elapsed notice too — any amount serves,
+        // since liquidations may have shrunk the deposit since it was
+        // filed and renoticing the remainder would reward impatience
+        // with a fresh timer.
+        consume_withdrawal_notice(&env, &staker, 0)?;
+        let mut state = storage::get_state(&env);
+        let compounded = compounded_deposit(&state, &position);
+        let reward = pending_reward(&env, &state, &position);
+
+        if compounded > 0 {
+            token::spend_balance(&env, &env.current_contract_address(), compounded)?;
+            token::receive_balance(&env, &staker, compounded)?;
+            state.total_rwa_deposited -= compounded;
+        }
+        state.total_pool_collateral -= reward;
+        let reward = pay_frontend_kickback(&env, &state, &staker, reward);
+        let mut collateral_out = reward;
+        // The refund was reserved when the position opened, so it is
+        // always payable regardless of intervening fee sweeps.
+        state.unstake_liability -= UNSTAKE_RETURN;
+        state.fees_collected -= UNSTAKE_RETURN;
+        collateral_out += UNSTAKE_RETURN;
+        events::FeeRefunded {
+            kind: symbol_short!("unstake"),
+            recipient: staker.clone(),
+            amount: UNSTAKE_RETURN,
+        }
+        .publish(&env);
+        if collateral_out > 0 {
+            TokenClient::new(&env, &state.collateral_sac).transfer(
+                &env.current_contract_address(),
+                &payout,
+                &collateral_out,
+            );
+        }
+        storage::remove_stake(&env, &staker);
+        storage::remove_frontend(&env, &staker);
+        storage::set_state(&env, &state);
+        Ok(())
+    }
+
+    /// Exits the pool with the compounded principal only, forfeiting
+    /// unclaimed collateral rewards and the unstake refund to the
+    /// treasury. Only available while the admin has emergency mode
+    /// switched on, so an incident pause can never hard-lock staked
+    /// funds behind reward claiming or fee logic. Returns the
+    /// principal paid out.
+    pub fn emergency_withdraw(env: Env, staker: Address) -> Result<i128, Error> {
+        staker.require_auth();
+        if !storage::emergency_mode(&env) {
+            return Err(Error::NotInEmergencyMode);
+        }
+        let position = require_stake(&env, &staker)?;
+        let mut state = storage::get_state(&env);
+        let compounded = compounded_deposit(&state, &position);
+        let reward = pending_reward(&env, &state, &position);
+        if compounded > 0 {
+            token::spend_balance(&env, &env.current_contract_address(), compounded)?;
+            token::receive_balance(&env, &staker, compounded)?;
+            state.total_rwa_deposited -= compounded;
+        }
+        // Forfeited rewards and the released refund reservation fall to
+        // the treasury; nothing stays earmarked for a position that no
+        // longer exists.
+        state.total_pool_collateral -= reward;
+        state.fees_collected += reward;
+        state.unstake_liability -= UNSTAKE_RETURN;
+        storage::remove_withdrawal_request(&env, &staker);
+        if reward > 0 {
+            events::FeeCollected {
+                kind: symbol_short!("forfeit"),
+                payer: staker.clone(),
+                amount: reward,
+            }
+            .publish(&env);
+        }
+        storage::remove_stake(&env, &staker);
+        storage::remove_frontend(&env, &staker);
+        storage::set_state(&env, &state);
+        EmergencyWithdrawn {
+            staker,
+            principal: compounded,
+            forfeited_reward: reward,
+        }
+        .publish(&env);
+        Ok(compounded)
+    }
+
+    /// Settles the caller's accumulated collateral rewards and resets
+    /// their snapshot to the current pool constants. Depending on the
+    /// position's [`RewardMode`] the gains are either paid out in
+    /// collateral or sold to the treasury at oracle face value and
+    /// recycled into the deposit as freshly minted RWA. Returns the
+    /// settled reward in collateral units either way. Accepts a muxed
+    /// `staker` so paid-out rewards can carry a sub-account id.
+    pub fn claim_rewards(env: Env, staker: MuxedAddress) -> Result<i128, Error> {
+        let payout = staker;
+        let staker = payout.address();
+        staker.require_auth();
+        require_not_paused(&env)?;
+        let position = require_stake(&env, &staker)?;
+        let mut state = storage::get_state(&env);
+        let (reward, rwa_gain) = settle_reward(&env, &mut state, &position, &payout)?;
+        let compounded = compounded_deposit(&state, &position) + rwa_gain;
+        storage::set_stake(
+            &env,
+            &StakePosition {
+                owner: staker,
+                deposited: compounded,
+                p_snapshot: state.compounded_constant,
+                s_snapshot: state.reward_constant,
+                epoch: state.current_epoch,
+                reward_mode: position.reward_mode,
+            },
+        );
+        storage::set_state(&env, &state);
+        Ok(reward)
+    }
+
+    /// Settles pending rewards for every position in `list` on its
+    /// owner's behalf, honoring each position's [`RewardMode`]; the
+    /// caller keeps the configured tip from each payout. Only open once
+    /// the claim delay has passed since rewards last accrued, so owners
+    /// always get first chance to claim themselves. Positions without
+    /// pending rewards are skipped. Keeps the pool's collateral from
+    /// being dominated by idle unclaimed rewards. Returns the total tip
+    /// paid to the caller.
+    pub fn claim_rewards_for(
+        env: Env,
+        caller: Address,
+        list: Vec<Address>,
+    ) -> Result<i128, Error> {
+        caller.require_auth();
+        require_not_paused(&env)?;
+        let mut state = storage::get_state(&env);
+        if state.claim_delay == 0
+            || env.ledger().timestamp() < state.last_reward_time + state.claim_delay
+        {
+            return Err(Error::ClaimNotOpen);
+        }
+        let mut claims = 0u32;
+        let mut rewards_settled = 0i128;
+        let mut tips_paid = 0i128;
+        for owner in list.iter() {
+            let position = match storage::get_stake(&env, &owner) {
+                Some(position) => position,
+                None => continue,
+            };
+            let reward = pending_reward(&env, &state, &position);
+            if reward == 0 {
+                continue;
+            }
+            let tip = mul_div_floor(reward, state.claim_tip_bps as i128, BPS);
+            let mut compounded = compounded_deposit(&state, &position);
+            state.total_pool_collateral -= reward;
+            // Tip first, then the frontend cut; the owner keeps the rest.
+            let owner_share = pay_frontend_kickback(&env, &state, &owner, reward - tip);
+            if position.reward_mode == RewardMode::Compound {
+                let rwa_gain = collateral_to_rwa(&env, &state, owner_share)?;
+                state.fees_collected += owner_share;
+                events::FeeCollected {
+                    kind: symbol_short!("compound"),
+                    payer: owner.clone(),
+                    amount: owner_share,
+                }
+                .publish(&env);
+                token::receive_balance(&env, &env.current_contract_address(), rwa_gain)?;
+                state.total_supply += rwa_gain;
+                Minted {
+                    to: env.current_contract_address(),
+                    amount: rwa_gain,
+                }
+                .publish(&env);
+                compounded += rwa_gain;
+                state.total_rwa_deposited += rwa_gain;
+            } else if owner_share > 0 {
+                TokenClient::new(&env, &state.collateral_sac).transfer(
+                    &env.current_contract_address(),
+                    &owner,
+                    &owner_share,
+                );
+            }
+            storage::set_stake(
+                &env,
+                &StakePosition {
+                    owner: owner.clone(),
+                    deposited: compounded,
+                    p_snapshot: state.compounded_constant,
+                    s_snapshot: state.reward_constant,
+                    epoch: state.current_epoch,
+                    reward_mode: position.reward_mode,
+                },
+            );
+            claims += 1;
+            rewards_settled += reward;
+            tips_paid += tip;
+        }
+        if tips_paid > 0 {
+            TokenClient::new(&env, &state.collateral_sac).transfer(
+                &env.current_contract_address(),
+                &caller,
+                &tips_paid,
+            );
+        }
+        if claims > 0 {
+            RewardsClaimedFor {
+                caller,
+                claims,
+                rewards_settled,
+                tips_paid,
+            }
+            .publish(&env);
+            storage::set_state(&env, &state);
+        }
+        Ok(tips_paid)
+    }
+
+    /// Chooses how the caller's future claims settle: paid out in
+    /// collateral or compounded into the deposit. Unstaking always pays
+    /// collateral regardless of the mode.
+    pub fn set_reward_mode(env: Env, staker: Address, mode: RewardMode) -> Result<(), Error> {
+        staker.require_auth();
+        require_not_paused(&env)?;
+        let mut position = require_stake(&env, &staker)?;
+        position.reward_mode = mode;
+        storage::set_stake(&env, &position);
+        Ok(())
+    }
+
+    /// Liquidates a frozen CDP against the stability pool, callable by
+    /// anyone. Seized collateral is distributed per the configured split:
+    /// pool stakers, the liquidation caller, and the treasury.
+    pub fn liquidate_cdp(env: Env, caller: Address, lender: Address) -> Result<(), Error> {
+        caller.require_auth();
+        require_not_paused(&env)?;
+        let mut cdp = match storage::get_cdp(&env, &lender) {
+            Some(cdp) => cdp,
+            None => return Err(Error::CDPNotFound),
+        };
+        if cdp.status != CDPStatus::Frozen {
+            return Err(Error::CDPNotFrozen);
+        }
+        // A CDP already clearing through a Dutch auction is off-limits:
+        // both paths settle against the same collateral.
+        if storage::get_auction(&env, &lender).is_some() {
+            return Err(Error::AuctionActive);
+        }
+        let mut state = storage::get_state(&env);
+        let debt = cdp.asset_lent + cdp.accrued_interest;
+        let total = state.total_rwa_deposited;
+        if total == 0 {
+            return Err(Error::PoolEmpty);
+        }
+        let absorbed = debt.min(total);
+        // Floor: rounding dust stays with the CDP rather than the pool.
+        let base = mul_div_floor(cdp.collateral_deposited, absorbed, debt);
+        // The penalty rewards stakers with extra collateral on top of
+        // the proportional seizure, bounded by what the CDP holds.
+        let penalty = mul_div_floor(base, state.liquidation_penalty_bps as i128, BPS)
+            .min(cdp.collateral_deposited - base);
+        let seized = base + penalty;
+
+        // Caller and treasury shares round down and are carved from the
+        // proportional part only; the pool absorbs the remainder plus
+        // the penalty, so the whole seizure is always accounted for.
+        let split = state.liquidation_split.clone();
+        let caller_cut = mul_div_floor(base, split.caller_bps as i128, BPS);
+        let treasury_cut = mul_div_floor(base, split.treasury_bps as i128, BPS);
+        let pool_cut = seized - caller_cut - treasury_cut;
+
+        // Burn inline against the held state: `burn_internal` saves its
+        // own copy, which the `set_state` below would clobber.
+        token::spend_balance(&env, &env.current_contract_address(), absorbed)?;
+        state.total_supply -= absorbed;
+        Burned {
+            from: env.current_contract_address(),
+            amount: absorbed,
+        }
+        .publish(&env);
+        absorb_debt(&env, &mut state, absorbed, pool_cut);
+        if caller_cut > 0 {
+            TokenClient::new(&env, &state.collateral_sac).transfer(
+                &env.current_contract_address(),
+                &caller,
+                &caller_cut,
+            );
+        }
+        state.fees_collected += treasury_cut;
+        if treasury_cut > 0 {
+            state.revenue.liquidation_penalties += treasury_cut;
+            events::FeeCollected {
+                kind: symbol_short!("liq_pen"),
+                payer: lender.clone(),
+                amount: treasury_cut,
+            }
+            .publish(&env);
+            RevenueAccrued {
+                source: RevenueSource::LiquidationPenalty,
+                amount: treasury_cut,
+            }
+            .publish(&env);
+        }
+
+        cdp.collateral_deposited -= seized;
+        state.total_cdp_collateral -= seized;
+        let interest_absorbed = absorbed.min(cdp.accrued_interest);
+        cdp.accrued_interest -= interest_absorbed;
+        cdp.asset_lent -= absorbed - interest_absorbed;
+        state.total_cdp_debt -= absorbed - interest_absorbed;
+
+        if cdp.asset_lent + cdp.accrued_interest == 0 {
+            if cdp.collateral_deposited > 0 {
+                TokenClient::new(&env, &state.collateral_sac).transfer(
+                    &env.current_contract_address(),
+                    &lender,
+                    &cdp.collateral_deposited,
+                );
+                state.total_cdp_collateral -= cdp.collateral_deposited;
+                cdp.collateral_deposited = 0;
+            }
+            cdp.status = CDPStatus::Closed;
+        }
+        pay_keeper_bounty(&env, &mut state, &caller, symbol_short!("liquidate"));
+        storage::set_state(&env, &state);
+        storage::set_cdp(&env, &cdp);
+        if cdp.status == CDPStatus::Closed {
+            notify_receipt_hook(&env, symbol_short!("burn_rcpt"), &lender);
+        }
+        // Fixed-shape call: state, CDP, auction probe, pool balance, and
+        // the liquidation log.
+        events::meter(&env, symbol_short!("liquidate"), 5);
+        Ok(())
+    }
+
+    // --- Views ----------------------------------------------------------
+
+    pub fn get_stake(env: Env, staker: Address) -> Option<StakePosition> {
+        storage::get_stake(&env, &staker)
+    }
+
+    /// The staker's live deposit after liquidation compounding.
+    pub fn get_deposit(env: Env, staker: Address) -> Result<i128, Error> {
+        let position = require_stake(&env, &staker)?;
+        let state = storage::get_state(&env);
+        Ok(compounded_deposit(&state, &position))
+    }
+
+    /// Collateral rewards claimable by the staker right now.
+    pub fn get_rewards(env: Env, staker: Address) -> Result<i128, Error> {
+        let position = require_stake(&env, &staker)?;
+        let state = storage::get_state(&env);
+        Ok(pending_reward(&env, &state, &position))
+    }
+
+    /// Total RWA currently deposited in the stability pool.
+    pub fn get_total_rwa(env: Env) -> i128 {
+        storage::get_state(&env).total_rwa_deposited
+    }
+
+    /// The bookkeeping of one epoch — final constants, interest, and
+    /// how many retained liquidations hit it — so stakers can audit
+    /// where their returns came from. The current epoch reports its
+    /// live running values; epochs that have not happened yet are
+    /// `None`.
+    pub fn epoch_summary(env: Env, epoch: u64) -> Option<EpochSummary> {
+        let state = storage::get_state(&env);
+        summarize_epoch(&env, &state, epoch)
+    }
+
+    /// Up to `limit` consecutive epoch summaries starting at
+    /// `from_epoch`, oldest first; paging past the current epoch just
+    /// truncates. See `epoch_summary`.
+    pub fn epoch_summaries(env: Env, from_epoch: u64, limit: u32) -> Vec<EpochSummary> {
+        let state = storage::get_state(&env);
+        let mut out = Vec::new(&env);
+        for i in 0..limit as u64 {
+            match summarize_epoch(&env, &state, from_epoch + i) {
+                Some(summary) => out.push_back(summary),
+                None => break,
+            }
+        }
+        out
+    }
+
+    /// The last `n` liquidations that hit the staker's position since its
+    /// latest snapshot, newest last, each with the position's pro-rata
+    /// debt and collateral attribution, so individual gain/loss
+    /// statements are self-serviceable. Bounded by the log's retention.
+    pub fn position_history(
+        env: Env,
+        staker: Address,
+        n: u32,
+    ) -> Result<Vec<LiquidationShare>, Error> {
+        let position = require_stake(&env, &staker)?;
+        let mut out = Vec::new(&env);
+        for record in storage::get_liquidation_log(&env).iter() {
+            // Records from other epochs, or from before the position's
+            // snapshot, did not touch this deposit.
+            if record.epoch != position.epoch
+                || record.compounded_constant > position.p_snapshot
+            {
+                continue;
+            }
+            let deposit_at = mul_div_floor(
+                position.deposited,
+                record.compounded_constant,
+                position.p_snapshot,
+            );
+            out.push_back(LiquidationShare {
+                epoch: record.epoch,
+                debt_absorbed: mul_div_floor(
+                    record.debt_absorbed,
+                    deposit_at,
+                    record.total_deposits,
+                ),
+                collateral_gained: mul_div_floor(
+                    record.collateral_gained,
+                    deposit_at,
+                    record.total_deposits,
+                ),
+            });
+        }
+        while out.len() > n {
+            out.remove(0);
+        }
+        Ok(out)
+    }
+}
+
+/// Settles `position`'s pending reward against `state`, honoring its
+/// [`RewardMode`]: the collateral is either transferred to `payout` or
+/// sold to the treasury at oracle face value and minted into the pool
+/// as fresh RWA. Returns the reward in collateral units and the RWA
+/// credited to the deposit (zero in payout mode); the caller folds the
+/// latter into the position's next snapshot and persists `state`.
+fn settle_reward(
+    env: &Env,
+    state: &mut RWATokenStorage,
+    position: &StakePosition,
+    payout: &MuxedAddress,
+) -> Result<(i128, i128), Error> {
+    let reward = pending_reward(env, state, position);
+    if reward == 0 {
+        return Ok((0, 0));
+    }
+    state.total_pool_collateral -= reward;
+    let reward = pay_frontend_kickback(env, state, &position.owner, reward);
+    if position.reward_mode == RewardMode::Compound {
+        let rwa_gain = collateral_to_rwa(env, state, reward)?;
+        // The treasury keeps the collateral as the reserve backing the
+        // newly minted RWA; it is not revenue.
+        state.fees_collected += reward;
+        events::FeeCollected {
+            kind: symbol_short!("compound"),
+            payer: position.owner.clone(),
+            amount: reward,
+        }
+        .publish(env);
+        token::receive_balance(env, &env.current_contract_address(), rwa_gain)?;
+        state.total_supply += rwa_gain;
+        Minted {
+            to: env.current_contract_address(),
+            amount: rwa_gain,
+        }
+        .publish(env);
+        state.total_rwa_deposited += rwa_gain;
+        Ok((reward, rwa_gain))
+    } else {
+        TokenClient::new(env, &state.collateral_sac).transfer(
+            &env.current_contract_address(),
+            payout,
+            &reward,
+        );
+        Ok((reward, 0))
+    }
+}
+
+/// Carves the configured frontend kickback out of `reward` and pays it
+/// to the staker's tag, if any; returns what is left for the staker.
+/// The full reward has already left the pool totals when this runs.
+fn pay_frontend_kickback(
+    env: &Env,
+    state: &RWATokenStorage,
+    staker: &Address,
+    reward: i128,
+) -> i128 {
+    let bps = storage::get_frontend_kickback_bps(env);
+    if reward == 0 || bps == 0 {
+        return reward;
+    }
+    let frontend = match storage::get_frontend(env, staker) {
+        Some(frontend) => frontend,
+        None => return reward,
+    };
+    let cut = mul_div_floor(reward, bps as i128, BPS);
+    if cut == 0 {
+        return reward;
+    }
+    TokenClient::new(env, &state.collateral_sac).transfer(
+        &env.current_contract_address(),
+        &frontend,
+        &cut,
+    );
+    KickbackPaid {
+        frontend,
+        staker: staker.clone(),
+        amount: cut,
+    }
+    .publish(env);
+    reward - cut
+}
+
+/// Body of `epoch_summary`: closed epochs come from the records written
+/// at epoch close, the current one from the live pool state.
+fn summarize_epoch(env: &Env, state: &RWATokenStorage, epoch: u64) -> Option<EpochSummary> {
+    let (compounded_constant, reward_constant, interest_collected) =
+        if epoch == state.current_epoch {
+            (
+                state.compounded_constant,
+                state.reward_constant,
+                state.interest_current_epoch,
+            )
+        } else {
+            let compound = storage::get_compound_record(env, epoch)?;
+            let interest = storage::get_interest_record(env, epoch)
+                .map_or(0, |record| record.interest_collected);
+            (
+                compound.compounded_constant,
+                compound.reward_constant,
+                interest,
+            )
+        };
+    let mut liquidations = 0u32;
+    for record in storage::get_liquidation_log(env).iter() {
+        if record.epoch == epoch {
+            liquidations += 1;
+        }
+    }
+    Some(EpochSummary {
+        epoch,
+        compounded_constant,
+        reward_constant,
+        interest_collected,
+        liquidations,
+    })
+}
+
+fn require_stake(env: &Env, staker: &Address) -> Result<StakePosition, Error> {
+    storage::get_stake(env, staker).ok_or(Error::StakeNotFound)
+}
+
+/// Enforces the withdrawal cooldown, if one is configured: there must be
+/// an elapsed notice covering `amount`, and it is spent by the exit.
+/// With no cooldown set, exits stay instant and notices are ignored.
+fn consume_withdrawal_notice(env: &Env, staker: &Address, amount: i128) -> Result<(), Error> {
+    if storage::get_withdraw_cooldown(env) == 0 {
+        return Ok(());
+    }
+    let request = match storage::get_withdrawal_request(env, staker) {
+        Some(request) => request,
+        None => return Err(Error::WithdrawalNotRequested),
+    };
+    if env.ledger().timestamp() < request.ready_at {
+        return Err(Error::TimelockNotElapsed);
+    }
+    if amount > request.amount {
+        return Err(Error::WithdrawalNotRequested);
+    }
+    storage::remove_withdrawal_request(env, staker);
+    Ok(())
+}
+
+/// The staker's deposit after applying liquidation compounding; zero if
+/// their epoch has been emptied. Rounds down, as a protocol payout.
+pub(crate) fn compounded_deposit(state: &RWATokenStorage, position: &StakePosition) -> i128 {
+    if position.epoch != state.current_epoch {
+        return 0;
+    }
+    mul_div_floor(
+        position.deposited,
+        state.compounded_constant,
+        position.p_snapshot,
+    )
+}
+
+/// Collateral gains accumulated since the position's snapshot. Rounds
+/// down, as a protocol payout.
+pub(crate) fn pending_reward(
+    env: &Env,
+    state: &RWATokenStorage,
+    position: &StakePosition,
+) -> i128 {
+    let reward_constant = if position.epoch == state.current_epoch {
+        state.reward_constant
+    } else {
+        // The position's epoch is closed; settle against its final record.
+        match storage::get_compound_record(env, position.epoch) {
+            Some(record) => record.reward_constant,
+            None => return 0,
+        }
+    };
+    mul_div_floor(
+        position.deposited,
+        reward_constant - position.s_snapshot,
+        position.p_snapshot,
+    )
+}
+
+/// Applies a liquidation to the pool: `absorbed` RWA of debt is burned
+/// from deposits and `seized` collateral becomes claimable.
+/// Advances the epoch when the pool is emptied, exactly or to within
+/// rounding dust.
+pub(crate) fn absorb_debt(env: &Env, state: &mut RWATokenStorage, absorbed: i128, seized: i128) {
+    let total = state.total_rwa_deposited;
+    storage::push_liquidation_record(
+        env,
+        &LiquidationRecord {
+            epoch: state.current_epoch,
+            debt_absorbed: absorbed,
+            collateral_gained: seized,
+            total_deposits: total,
+            compounded_constant: state.compounded_constant,
+        },
+    );
+    state.reward_constant += mul_div_floor(seized, state.compounded_constant, total);
+    let new_constant = mul_div_floor(state.compounded_constant, total - absorbed, total);
+    if absorbed == total || new_constant == 0 {
+        // A near-total absorption can floor the constant to zero, and
+        // every later snapshot would then divide by it. A liquidation
+        // that gets there has consumed all but dust — under one part in
+        // `POOL_SCALE` of the epoch's deposits — so close the epoch as
+        // if the pool were emptied and let the dust lapse with it.
+        increment_epoch(env, state);
+        state.total_rwa_deposited = 0;
+    } else {
+        state.compounded_constant = new_constant;
+        state.total_rwa_deposited -= absorbed;
+    }
+    state.total_pool_collateral += seized;
+    state.last_reward_time = env.ledger().timestamp();
+}
+
+/// Closes the current epoch, recording its final constants and interest,
+/// and resets the pool for the next one.
+pub(crate) fn increment_epoch(env: &Env, state: &mut RWATokenStorage) {
+    storage::set_compound_record(
+        env,
+        state.current_epoch,
+        &CompoundRecord {
+            compounded_constant: state.compounded_constant,
+            reward_constant: state.reward_constant,
+        },
+    );
+    storage::set_interest_record(
+        env,
+        state.current_epoch,
+        &InterestRecord {
+            interest_collected: state.interest_current_epoch,
+        },
+    );
+    let old_epoch = state.current_epoch;
+    state.current_epoch += 1;
+    EpochAdvanced {
+        old_epoch,
+        new_epoch: state.current_epoch,
+        final_compounded_constant: state.compounded_constant,
+        interest_recorded: state.interest_current_epoch,
+    }
+    .publish(env);
+    state.compounded_constant = POOL_SCALE;
+    state.reward_constant = 0;
+    state.interest_current_epoch = 0;
+}
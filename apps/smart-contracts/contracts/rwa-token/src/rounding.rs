@@ -0,0 +1,41 @@
+//! Deterministic rounding policy for all fixed-point math.
+//!
+//! Every division rounds against the user so dust always accumulates in
+//! the protocol's favor: amounts owed *to* the protocol (interest,
+//! payments) round up, amounts paid *out* by the protocol (deposits,
+//! rewards, conversions held by users) round down. Both helpers expect
+//! non-negative operands and a positive denominator, which every caller
+//! guarantees by validating amounts up front.
+
+/// `a * b / denom`, rounded down. Use for amounts the protocol pays out.
+pub(crate) fn mul_div_floor(a: i128, b: i128, denom: i128) -> i128 {
+    a * b / denom
+}
+
+/// `a * b / denom`, rounded up. Use for amounts the protocol is owed.
+pub(crate) fn mul_div_ceil(a: i128, b: i128, denom: i128) -> i128 {
+    let product = a * b;
+    let quotient = product / denom;
+    if product % denom != 0 {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
+
+/// Raises a `scale`-fixed-point growth factor to the `exp`-th power by
+/// squaring, rounding up at every step so compounding can never charge
+/// less than the exact value.
+pub(crate) fn pow_ceil(mut factor: i128, mut exp: u64, scale: i128) -> i128 {
+    let mut result = scale;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_div_ceil(result, factor, scale);
+        }
+        exp >>= 1;
+        if exp > 0 {
+            factor = mul_div_ceil(factor, factor, scale);
+        }
+    }
+    result
+}
@@ -0,0 +1,197 @@
+//! Savings facility: holders lock RWA and earn the configured share of
+//! every interest payment, pro rata, in collateral — demand for holding
+//! the token with no liquidation exposure. The savings rate is a
+//! monetary-policy lever distinct from the stability pool: raising it
+//! pays holders without asking them to underwrite liquidations.
+//! Accounting mirrors the pool's accumulator: a global per-unit
+//! constant grows with each carve-out and positions settle against the
+//! delta since their snapshot.
+
+use soroban_sdk::{contractimpl, token::TokenClient, Address, Env};
+
+use crate::admin::require_not_paused;
+use crate::errors::Error;
+use crate::events::{SavingsClaimed, SavingsDeposited, SavingsFunded, SavingsWithdrawn};
+use crate::rounding::mul_div_floor;
+use crate::storage::{self, SavingsPosition, BPS, POOL_SCALE};
+use crate::token;
+use crate::{RWAToken, RWATokenArgs, RWATokenClient};
+
+#[contractimpl]
+impl RWAToken {
+    /// Locks `amount` RWA into the caller's savings position, settling
+    /// any accumulated interest share first so the snapshot reset
+    /// cannot discard it. Top-ups merge into the existing position.
+    pub fn save(env: Env, holder: Address, amount: i128) -> Result<(), Error> {
+        holder.require_auth();
+        require_not_paused(&env)?;
+        #[cfg(feature = "compliance")]
+        crate::compliance::require_kyc(&env, &holder)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let held = settle_savings(&env, &holder)?;
+        token::spend_balance(&env, &holder, amount)?;
+        token::receive_balance(&env, &env.current_contract_address(), amount)?;
+        storage::set_savings(
+            &env,
+            &SavingsPosition {
+                owner: holder.clone(),
+                amount: held + amount,
+                s_snapshot: storage::get_savings_constant(&env),
+            },
+        );
+        storage::set_total_savings(&env, storage::get_total_savings(&env) + amount);
+        SavingsDeposited { holder, amount }.publish(&env);
+        Ok(())
+    }
+
+    /// Unlocks `amount` RWA from the caller's savings position, paying
+    /// out its settled interest share along the way. Unlocking the full
+    /// balance closes the position.
+    pub fn unsave(env: Env, holder: Address, amount: i128) -> Result<(), Error> {
+        holder.require_auth();
+        require_not_paused(&env)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let held = settle_savings(&env, &holder)?;
+        if amount > held {
+            return Err(Error::InsufficientBalance);
+        }
+        token::spend_balance(&env, &env.current_contract_address(), amount)?;
+        token::receive_balance(&env, &holder, amount)?;
+        if amount == held {
+            storage::remove_savings(&env, &holder);
+        } else {
+            storage::set_savings(
+                &env,
+                &SavingsPosition {
+                    owner: holder.clone(),
+                    amount: held - amount,
+                    s_snapshot: storage::get_savings_constant(&env),
+                },
+            );
+        }
+        storage::set_total_savings(&env, storage::get_total_savings(&env) - amount);
+        SavingsWithdrawn { holder, amount }.publish(&env);
+        Ok(())
+    }
+
+    /// Pays out the caller's accumulated interest share in collateral
+    /// and resets their snapshot; the locked RWA stays put. Returns the
+    /// amount paid.
+    pub fn claim_savings(env: Env, holder: Address) -> Result<i128, Error> {
+        holder.require_auth();
+        require_not_paused(&env)?;
+        let position = match storage::get_savings(&env, &holder) {
+            Some(position) => position,
+            None => return Err(Error::StakeNotFound),
+        };
+        let pending = pending_savings(&env, &position);
+        settle_savings(&env, &holder)?;
+        storage::set_savings(
+            &env,
+            &SavingsPosition {
+                owner: holder,
+                amount: position.amount,
+                s_snapshot: storage::get_savings_constant(&env),
+            },
+        );
+        Ok(pending)
+    }
+
+    pub fn savings_of(env: Env, holder: Address) -> Option<SavingsPosition> {
+        storage::get_savings(&env, &holder)
+    }
+
+    /// The caller's interest share accumulated since their snapshot.
+    pub fn savings_rewards(env: Env, holder: Address) -> i128 {
+        match storage::get_savings(&env, &holder) {
+            Some(position) => pending_savings(&env, &position),
+            None => 0,
+        }
+    }
+
+    pub fn total_savings(env: Env) -> i128 {
+        storage::get_total_savings(&env)
+    }
+
+    /// Sets the share of each interest payment carved out for savings,
+    /// in basis points; zero disables it. Admin only. The carve-out
+    /// only applies while RWA is actually locked — with no savers the
+    /// full payment stays revenue.
+    pub fn set_savings_rate(env: Env, bps: u32) -> Result<(), Error> {
+        crate::admin::require_admin(&env)?;
+        if bps as i128 > BPS {
+            return Err(Error::InvalidConfiguration);
+        }
+        storage::set_savings_rate_bps(&env, bps);
+        Ok(())
+    }
+
+    pub fn savings_rate(env: Env) -> u32 {
+        storage::get_savings_rate_bps(&env)
+    }
+}
+
+/// A position's interest share accumulated since its snapshot; rounds
+/// down, as a protocol payout.
+fn pending_savings(env: &Env, position: &SavingsPosition) -> i128 {
+    mul_div_floor(
+        position.amount,
+        storage::get_savings_constant(env) - position.s_snapshot,
+        POOL_SCALE,
+    )
+}
+
+/// Pays out the holder's pending interest share, if any, and returns
+/// their locked amount (zero for no position). Callers re-snapshot the
+/// position themselves.
+fn settle_savings(env: &Env, holder: &Address) -> Result<i128, Error> {
+    let position = match storage::get_savings(env, holder) {
+        Some(position) => position,
+        None => return Ok(0),
+    };
+    let pending = pending_savings(env, &position);
+    if pending > 0 {
+        let state = storage::get_state(env);
+        TokenClient::new(env, &state.collateral_sac).transfer(
+            &env.current_contract_address(),
+            holder,
+            &pending,
+        );
+        SavingsClaimed {
+            holder: holder.clone(),
+            amount: pending,
+        }
+        .publish(env);
+    }
+    Ok(position.amount)
+}
+
+/// Carves the savings-rate share out of one interest payment's
+/// collateral and credits it to locked savings via the accumulator.
+/// `kept` caps the cut at what other carve-outs have left. Returns the
+/// amount diverted; flooring dust stays in the contract. Called from
+/// `pay_interest_internal` before the remainder counts as revenue.
+pub(crate) fn carve_interest_share(env: &Env, collateral_due: i128, kept: i128) -> i128 {
+    let bps = storage::get_savings_rate_bps(env);
+    if bps == 0 {
+        return 0;
+    }
+    let total = storage::get_total_savings(env);
+    if total == 0 {
+        return 0;
+    }
+    let cut = mul_div_floor(collateral_due, bps as i128, BPS).min(kept);
+    if cut == 0 {
+        return 0;
+    }
+    storage::set_savings_constant(
+        env,
+        storage::get_savings_constant(env) + mul_div_floor(cut, POOL_SCALE, total),
+    );
+    SavingsFunded { amount: cut }.publish(env);
+    cut
+}
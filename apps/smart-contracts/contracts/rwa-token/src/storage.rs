@@ -0,0 +1,1198 @@
+use soroban_sdk::{contracttype, Address, Env, String, Symbol, Vec};
+
+/// One day of 5-second ledgers, the unit TTL maintenance works in.
+pub(crate) const DAY_IN_LEDGERS: u32 = 17_280;
+
+/// Ledgers a bumped persistent entry stays live.
+pub(crate) const PERSISTENT_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+
+/// Remaining lifetime below which a bump actually extends, so repeated
+/// bumps of a fresh entry cost nothing.
+pub(crate) const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+/// Seconds in a (non-leap) year, over which the nominal annual rate is
+/// spread for per-second compounding.
+pub(crate) const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+/// Basis-point denominator for ratios and rates.
+pub(crate) const BPS: i128 = 10_000;
+
+/// Fixed-point scale of the per-second interest growth factor. It has
+/// to be much finer than [`BPS`] because a per-second rate is the
+/// annual rate divided by ~31.5 million.
+pub(crate) const INTEREST_SCALE: i128 = 1_000_000_000_000_000_000;
+
+/// Fixed-point scale of the stability pool compounding constants.
+pub(crate) const POOL_SCALE: i128 = 1_000_000_000;
+
+/// Fixed-point scale of the rebasing supply factor; a factor equal to
+/// the scale means face value and unscaled units coincide.
+pub(crate) const FACTOR_SCALE: i128 = 1_000_000_000;
+
+/// Flat collateral amount returned to a staker when they fully unstake,
+/// refunded out of the portion of their stake fee reserved for it.
+pub(crate) const UNSTAKE_RETURN: i128 = 2_0000000;
+
+/// Maximum liquidation records retained in the pool's attribution log;
+/// older records are dropped.
+#[cfg(feature = "stability-pool")]
+pub(crate) const MAX_LIQUIDATION_HISTORY: u32 = 50;
+
+/// Ledgers a proposed clawback limit waits before it can be applied
+/// (roughly one day of 5-second ledgers), so holders can see authority
+/// changes coming.
+#[cfg(feature = "compliance")]
+pub(crate) const CLAWBACK_LIMIT_DELAY: u32 = 17_280;
+
+/// Storage layout version the current code writes. A wasm whose layout
+/// differs from what is on-chain must ship a conversion step in
+/// `migrate`, which deployments call right after `upgrade`; version 1
+/// covers every layout from before the version key existed.
+pub(crate) const SCHEMA_VERSION: u32 = 2;
+
+/// Starting ask of a Dutch auction, in basis points of the frozen CDP's
+/// debt, so early bidders pay a premium over face value.
+pub(crate) const AUCTION_START_BPS: i128 = 13_000;
+
+/// Ledgers over which an auction's ask decays linearly from
+/// [`AUCTION_START_BPS`] to zero (roughly one day of 5-second ledgers),
+/// guaranteeing the lot eventually clears at some price.
+pub(crate) const AUCTION_DECAY_LEDGERS: u32 = 17_280;
+
+/// Instance-level configuration and aggregate bookkeeping for the token,
+/// its CDPs, and the stability pool.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RWATokenStorage {
+    pub admin: Address,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u32,
+    /// Oracle symbol the token is pegged to (the RWA's feed id).
+    pub pegged_asset: Symbol,
+    /// Oracle serving the pegged asset's price.
+    pub oracle: Address,
+    /// Oracle serving the collateral asset's price, in the same base as
+    /// `oracle`.
+    pub collateral_oracle: Address,
+    /// Stellar Asset Contract of the collateral asset (XLM, USDC, ...),
+    /// used for collateral movement.
+    pub collateral_sac: Address,
+    /// Feed symbol of the collateral asset on `collateral_oracle`.
+    pub collateral_symbol: Symbol,
+    /// Decimals `oracle` reported when it was wired up. Price reads halt
+    /// with `FeedDecimalsChanged` if the live value ever differs, until
+    /// the admin accepts the change.
+    pub oracle_decimals: u32,
+    /// Same guard for `collateral_oracle`.
+    pub collateral_oracle_decimals: u32,
+    /// Minimum collateralization ratio, in basis points (11000 = 110%).
+    pub min_collat_ratio: u32,
+    /// Total collateralization ratio (basis points) below which the
+    /// system enters recovery mode: leverage-increasing actions are
+    /// blocked and the liquidation threshold widens from the MCR to the
+    /// live TCR. 0 disables recovery mode.
+    pub critical_collat_ratio: u32,
+    /// Maximum total supply mintable against CDPs; 0 means uncapped.
+    /// Each deployment binds one collateral asset, so together with the
+    /// MCR and liquidation split this is that collateral's risk
+    /// configuration.
+    pub debt_ceiling: i128,
+    /// Smallest principal a CDP may carry: `open_cdp` rejects smaller
+    /// loans and `repay_debt` must either clear the debt or leave at
+    /// least this much, so positions too small to be worth liquidating
+    /// cannot exist. 0 disables the floor.
+    pub min_debt: i128,
+    /// Annual interest rate charged on CDP debt, in basis points. Used
+    /// whenever `rate_tiers` is empty.
+    pub annual_interest_rate: u32,
+    /// Piecewise rate schedule by CDP size; empty means the flat
+    /// `annual_interest_rate` applies to the full principal.
+    pub rate_tiers: Vec<RateTier>,
+    /// Global borrow index ([`INTEREST_SCALE`] fixed point), growing by
+    /// the flat per-second rate and never shrinking. A CDP owes its
+    /// debt times the index growth since its snapshot, so rate changes
+    /// apply from the change onward instead of repricing elapsed time.
+    pub borrow_index: i128,
+    /// Timestamp the borrow index was last brought up to date.
+    pub index_updated_at: u64,
+    /// Face value of one unscaled token unit ([`FACTOR_SCALE`] fixed
+    /// point), lowered by the issuer as the underlying instrument
+    /// amortizes. Balances, transfers, and CDP debt all stay in unscaled
+    /// units; the factor only drives the `scaled_*` conversion views.
+    pub supply_factor: i128,
+    /// Oracle silence (seconds) after which the pegged feed counts as
+    /// down and interest accrual pauses until the next update; 0
+    /// disables outage credits.
+    pub outage_threshold: u64,
+    /// Haircut applied to the collateral price per full hour since the
+    /// collateral feed's latest update, in basis points, saturating at
+    /// 100%. Solvency checks keep working through a feed outage while
+    /// valuing collateral increasingly conservatively. 0 disables the
+    /// haircut and stale prices are used at face value.
+    pub stale_haircut_bps: u32,
+    /// How seized liquidation collateral is distributed.
+    pub liquidation_split: LiquidationSplit,
+    /// Extra collateral (basis points of the proportional seizure) a
+    /// liquidated CDP gives up to pool stakers, capped by what the CDP
+    /// holds. Zero means liquidations seize exactly pro-rata.
+    pub liquidation_penalty_bps: u32,
+    /// Flat collateral fee charged when opening a stake position.
+    pub stake_fee: i128,
+    /// Flat collateral bounty paid from swept-able fees to whoever
+    /// freezes or liquidates a CDP; 0 disables keeper rewards.
+    pub keeper_bounty: i128,
+    /// Smallest stake the pool accepts, and the smallest residual a
+    /// partial withdrawal may leave, so dust positions whose fee
+    /// overhead exceeds their value cannot accumulate. 0 disables.
+    pub min_stake: i128,
+    /// Share of each interest payment streamed to the configured
+    /// data-fee account, in basis points; 0 disables streaming.
+    pub data_fee_bps: u32,
+    /// Fee on flash mints, in basis points of the minted amount, burned
+    /// together with the repayment; 0 makes flash mints free.
+    pub flash_mint_fee_bps: u32,
+    /// Upfront fee on newly minted CDP debt, in basis points of the
+    /// RWA minted, collected in collateral at face value like interest;
+    /// 0 disables it.
+    pub origination_fee_bps: u32,
+    /// Minimum fee on redemptions, in basis points of the collateral
+    /// paid out; 0 together with a zero base rate makes redemptions
+    /// free.
+    pub redemption_fee_floor_bps: u32,
+    /// Volume-driven component of the redemption fee: each redemption
+    /// bumps it by the share of supply redeemed, and it falls linearly
+    /// back to zero over `redemption_decay_secs`, so redeeming against
+    /// CDP owners during a brief dip below peg is never free.
+    pub redemption_base_rate_bps: u32,
+    /// When the redemption base rate last changed.
+    pub base_rate_updated_at: u64,
+    /// Seconds a base-rate spike takes to decay fully, like the Dutch
+    /// auction ask but in time rather than ledgers; 0 disables the base
+    /// rate and leaves only the floor.
+    pub redemption_decay_secs: u64,
+    pub total_supply: i128,
+    /// Destination of fee and interest sweeps; sweeping is impossible
+    /// until the admin sets one.
+    pub treasury: Option<Address>,
+    /// Collateral held back for the protocol (stake fees, etc.).
+    pub fees_collected: i128,
+    /// Portion of `fees_collected` earmarked for unstake refunds, one
+    /// [`UNSTAKE_RETURN`] per open stake position.
+    pub unstake_liability: i128,
+    /// Collateral collected as CDP interest and not yet swept to the
+    /// treasury.
+    pub interest_collected: i128,
+    /// Collateral collected as interest during the current pool epoch.
+    pub interest_current_epoch: i128,
+    /// Lifetime revenue by source. Monotonic: sweeps and refunds reduce
+    /// the cash buckets above, never these counters.
+    pub revenue: RevenueBreakdown,
+    /// Aggregate principal lent across every CDP, maintained at each
+    /// debt mutation so the TCR needs no registry walk. Accrued interest
+    /// is excluded.
+    pub total_cdp_debt: i128,
+    /// Aggregate collateral locked in CDPs, maintained alongside
+    /// `total_cdp_debt`.
+    pub total_cdp_collateral: i128,
+    /// Collateral surrendered through [`repay_with_collateral`]: it no
+    /// longer belongs to any CDP but still backs the RWA whose
+    /// principal it cleared, so `redeem` draws on it once the registry
+    /// is exhausted.
+    ///
+    /// [`repay_with_collateral`]: crate::RWAToken::repay_with_collateral
+    pub redemption_buffer: i128,
+    /// RWA currently deposited in the stability pool.
+    pub total_rwa_deposited: i128,
+    /// Liquidation proceeds not yet claimed by stakers.
+    pub total_pool_collateral: i128,
+    /// Stability pool epoch; advances whenever the pool is fully emptied.
+    pub current_epoch: u64,
+    /// Product of deposit-scaling factors within the current epoch
+    /// ([`POOL_SCALE`] fixed point).
+    pub compounded_constant: i128,
+    /// Collateral reward accumulator for the current epoch.
+    pub reward_constant: i128,
+    /// When pool rewards last accrued (a liquidation was absorbed);
+    /// keeper claims open this long plus `claim_delay` later.
+    pub last_reward_time: u64,
+    /// Seconds after the latest reward accrual before anyone may settle
+    /// stakers' rewards on their behalf; 0 disables keeper claims.
+    pub claim_delay: u64,
+    /// Tip carved from each keeper-settled payout for the caller, in
+    /// basis points of the reward.
+    pub claim_tip_bps: u32,
+}
+
+/// Cumulative protocol revenue segmented by source, in collateral
+/// units.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RevenueBreakdown {
+    pub origination_fees: i128,
+    pub interest: i128,
+    pub liquidation_penalties: i128,
+    pub redemption_fees: i128,
+    pub pool_fees: i128,
+}
+
+/// Tags a [`crate::events::RevenueAccrued`] event with where the
+/// revenue came from.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RevenueSource {
+    Origination,
+    Interest,
+    LiquidationPenalty,
+    Redemption,
+    PoolFee,
+}
+
+/// Three-way distribution of seized liquidation collateral, in basis
+/// points summing to [`BPS`]: stability pool stakers, the liquidation
+/// caller, and the protocol treasury.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LiquidationSplit {
+    pub pool_bps: u32,
+    pub caller_bps: u32,
+    pub treasury_bps: u32,
+}
+
+/// One tranche of a piecewise interest schedule: `rate_bps` applies to
+/// the slice of principal above `floor` up to the next tier's floor (or
+/// unbounded for the last tier). Mirrors how RWA credit facilities price
+/// jumbo vs retail tranches.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateTier {
+    pub floor: i128,
+    pub rate_bps: u32,
+}
+
+/// Grantable permission roles over the admin surface. The admin holds
+/// every role implicitly and is the only key that can grant or revoke
+/// them, so routine parameter tuning can be delegated to hot keys
+/// without handing over the upgrade path.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Role {
+    /// May tune the interest rate schedule.
+    RateSetter,
+    /// May move risk parameters and swap oracle addresses.
+    RiskManager,
+    /// May pause and unpause the contract.
+    Pauser,
+    /// May replace the contract code.
+    Owner,
+}
+
+/// Admin-set band for the volatility-linked entry MCR. While
+/// configured, the effective requirement for opening, borrowing, and
+/// withdrawing moves between `floor` and `ceiling` with the collateral
+/// feed's return volatility over its last `window` records, adding
+/// `slope_bps` basis points of MCR per basis point of volatility.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct McrBand {
+    pub floor: u32,
+    pub ceiling: u32,
+    pub window: u32,
+    pub slope_bps: u32,
+}
+
+/// Status of a CDP. Insolvency is computed from prices, not stored.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CDPStatus {
+    Open,
+    Frozen,
+    Closed,
+}
+
+/// A collateralized debt position: collateral locked against minted RWA
+/// tokens.
+#[allow(clippy::upper_case_acronyms)]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CDP {
+    pub lender: Address,
+    pub collateral_deposited: i128,
+    /// Outstanding minted principal, in RWA units.
+    pub asset_lent: i128,
+    /// Interest accrued and not yet paid, in RWA units.
+    pub accrued_interest: i128,
+    pub last_interest_time: u64,
+    /// Value of the global borrow index at this CDP's last touch; the
+    /// tier schedule falls back to `last_interest_time` instead.
+    pub interest_index: i128,
+    pub status: CDPStatus,
+}
+
+/// Snapshot of every input `health_factor` used, so front-ends can
+/// display the contract's own math instead of re-deriving it and
+/// drifting on rounding.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HealthReport {
+    /// `ratio / min_ratio` in [`FACTOR_SCALE`] fixed point; below one
+    /// scale unit the CDP is freezable.
+    pub factor: i128,
+    /// Collateralization ratio in basis points.
+    pub ratio: u32,
+    /// The MCR it is measured against, in basis points.
+    pub min_ratio: u32,
+    pub collateral: i128,
+    /// Principal plus interest projected to now, in RWA units.
+    pub debt: i128,
+    pub accrued_interest: i128,
+    pub rwa_price: i128,
+    pub rwa_decimals: u32,
+    /// Collateral feed price after any staleness haircut.
+    pub collateral_price: i128,
+    pub collateral_decimals: u32,
+}
+
+/// Outcome of a `preview_*` dry run: the position as it would look
+/// after the mutation, and whether the real entrypoint would accept
+/// it, so wallets can warn before the user signs.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ActionPreview {
+    /// Whether the mutating entrypoint would succeed right now.
+    pub ok: bool,
+    /// The `Error` code it would fail with when `ok` is false; 0
+    /// otherwise.
+    pub error_code: u32,
+    /// Collateralization ratio after the action, in basis points
+    /// (`u32::MAX` when the position would be debt-free).
+    pub ratio: u32,
+    /// Interest accrued to now on the position, in RWA units.
+    pub interest_due: i128,
+    /// Collateral fee the action would charge: the origination fee for
+    /// opens and borrows, zero for the rest.
+    pub fee: i128,
+}
+
+/// How a staker's liquidation gains are settled at claim time.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RewardMode {
+    /// Gains are paid out in the collateral asset (the default).
+    Collateral,
+    /// Gains are sold to the treasury at oracle face value and the
+    /// proceeds minted straight into the pool deposit.
+    Compound,
+}
+
+/// A staker's stability pool position. `deposited` is the amount at the
+/// last snapshot; the live value compounds via the pool constants.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StakePosition {
+    pub owner: Address,
+    pub deposited: i128,
+    pub p_snapshot: i128,
+    pub s_snapshot: i128,
+    pub epoch: u64,
+    /// Whether claims pay collateral out or recycle it into the deposit.
+    pub reward_mode: RewardMode,
+}
+
+/// A staker's standing notice of exit while a withdrawal cooldown is
+/// configured: `withdraw` honours it only once `ready_at` has passed,
+/// and liquidations keep eating the deposit in the meantime.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithdrawalRequest {
+    pub owner: Address,
+    pub amount: i128,
+    pub ready_at: u64,
+}
+
+/// Final pool constants of a closed epoch, kept so positions opened in
+/// that epoch can settle their rewards after the pool emptied.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CompoundRecord {
+    pub compounded_constant: i128,
+    pub reward_constant: i128,
+}
+
+/// Interest collected during a closed epoch.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterestRecord {
+    pub interest_collected: i128,
+}
+
+/// A holder's savings position: RWA locked for a share of interest
+/// income, with no liquidation exposure. `s_snapshot` is the savings
+/// constant at the position's last settlement.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SavingsPosition {
+    pub owner: Address,
+    pub amount: i128,
+    pub s_snapshot: i128,
+}
+
+/// One closed (or the live) epoch's pool bookkeeping, assembled for
+/// auditing: the final compounding constants, the interest collected
+/// over the epoch, and how many liquidations from the retained log hit
+/// it. Liquidation counts older than the log's retention read as zero.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EpochSummary {
+    pub epoch: u64,
+    pub compounded_constant: i128,
+    pub reward_constant: i128,
+    pub interest_collected: i128,
+    pub liquidations: u32,
+}
+
+/// One liquidation as it hit the pool, with the pool totals and
+/// compounding constant in force just before it, so any position's
+/// pro-rata share can be reconstructed later.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LiquidationRecord {
+    pub epoch: u64,
+    /// RWA debt burned from pool deposits.
+    pub debt_absorbed: i128,
+    /// Collateral credited to pool stakers.
+    pub collateral_gained: i128,
+    /// Pool RWA deposits before the liquidation.
+    pub total_deposits: i128,
+    /// `compounded_constant` before the liquidation.
+    pub compounded_constant: i128,
+}
+
+/// A staker's pro-rata share of one logged liquidation.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LiquidationShare {
+    pub epoch: u64,
+    pub debt_absorbed: i128,
+    pub collateral_gained: i128,
+}
+
+/// A running Dutch auction over a frozen CDP's collateral. The ask for
+/// the remaining lot starts above the remaining debt and decays linearly
+/// per ledger; bids are paid in RWA and burned against the debt.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Auction {
+    pub lender: Address,
+    /// Collateral still on offer.
+    pub collateral_lot: i128,
+    /// RWA debt still to be covered by bids.
+    pub debt: i128,
+    pub start_ledger: u32,
+}
+
+/// Clawback rate limit: at most `cap` unscaled tokens per window of
+/// `period_ledgers`. Absent means clawback is uncapped.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClawbackLimit {
+    pub cap: i128,
+    pub period_ledgers: u32,
+}
+
+/// Running usage of the current clawback window.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClawbackWindow {
+    pub start_ledger: u32,
+    pub used: i128,
+}
+
+/// A clawback limit waiting out its timelock.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClawbackLimitProposal {
+    pub limit: ClawbackLimit,
+    pub effective_ledger: u32,
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    State,
+    SchemaVersion,
+    Balance(Address),
+    Allowance(Address, Address),
+    Deauthorized(Address),
+    Frozen(Address),
+    Role(Role, Address),
+    CDP(Address),
+    Stake(Address),
+    /// Persistent: a staker's pending exit notice under the cooldown.
+    WithdrawalRequest(Address),
+    /// Persistent: the frontend operator tagged when the stake opened.
+    Frontend(Address),
+    /// Persistent: a holder's locked savings position.
+    Savings(Address),
+    /// Instance: total RWA locked in savings.
+    TotalSavings,
+    /// Instance: per-unit savings reward accumulator ([`POOL_SCALE`]
+    /// fixed point), grown by each interest carve-out.
+    SavingsConstant,
+    /// Instance: share of each interest payment accrued to savings, in
+    /// basis points; 0 disables the carve-out.
+    SavingsRateBps,
+    /// Instance: share of settled rewards credited to a position's
+    /// frontend tag, in basis points; 0 disables kickbacks.
+    FrontendKickbackBps,
+    /// Instance: seconds between requesting and executing a pool
+    /// withdrawal; 0 or absent means exits are instant.
+    WithdrawCooldown,
+    /// Persistent: address a lender delegated defensive CDP calls to.
+    Operator(Address),
+    CompoundRecord(u64),
+    InterestRecord(u64),
+    /// Instance: optional receipt-NFT contract notified on CDP open/close.
+    ReceiptHook,
+    /// Instance: optional account receiving the interest data-fee share,
+    /// typically the oracle's feeder bond/reward pool.
+    DataFeeAccount,
+    /// Instance: whether heavy entrypoints publish `OpMetered` samples.
+    Metering,
+    /// Instance: whether emergency principal-only pool exits are open.
+    EmergencyMode,
+    /// Instance: whether state-changing entrypoints are halted.
+    Paused,
+    /// Instance: optional address allowed to pause besides the admin.
+    Pauser,
+    /// Instance: optional volatility-driven band for the entry MCR;
+    /// absent means the static minimum ratio applies everywhere.
+    McrBand,
+    /// Instance: transfer size above which a regulated asset needs a
+    /// recorded SEP-8 approval; absent or 0 disables the gate.
+    RegulatedThreshold,
+    /// Instance: optional SEP-8 approval server allowed to record
+    /// transfer approvals besides the admin.
+    ApprovalServer,
+    /// Instance: optional contract consulted on every transfer and
+    /// mint; movements it rejects abort.
+    ComplianceHook,
+    /// Instance: optional KYC registry that must attest callers before
+    /// they may mint CDP exposure or stake.
+    KycRegistry,
+    /// Temporary: one-shot approved amount for a pending regulated
+    /// transfer, keyed `(from, to)`.
+    TransferApproval(Address, Address),
+    /// Persistent: bounded log of liquidations absorbed by the pool.
+    LiquidationLog,
+    /// Persistent: CDP lender registered at this index position.
+    CDPIndex(u32),
+    /// Instance: number of lenders that ever opened a CDP.
+    CDPCount,
+    /// Persistent: running Dutch auction over a frozen CDP's collateral.
+    Auction(Address),
+    /// Instance: the active clawback rate limit, if any.
+    ClawbackLimit,
+    /// Instance: clawback usage within the current window.
+    ClawbackWindow,
+    /// Instance: a clawback limit waiting out its timelock.
+    ClawbackProposal,
+}
+
+/// Allowance amount together with the ledger it expires on.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowanceValue {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+pub(crate) fn get_state(env: &Env) -> RWATokenStorage {
+    env.storage().instance().get(&DataKey::State).unwrap()
+}
+
+/// The stored schema version; deployments predating the version key
+/// read as version 1.
+pub(crate) fn get_schema_version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::SchemaVersion)
+        .unwrap_or(1)
+}
+
+pub(crate) fn set_schema_version(env: &Env, version: u32) {
+    env.storage()
+        .instance()
+        .set(&DataKey::SchemaVersion, &version);
+}
+
+pub(crate) fn set_state(env: &Env, state: &RWATokenStorage) {
+    env.storage().instance().set(&DataKey::State, state);
+}
+
+pub(crate) fn get_balance(env: &Env, id: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Balance(id.clone()))
+        .unwrap_or(0)
+}
+
+pub(crate) fn set_balance(env: &Env, id: &Address, amount: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Balance(id.clone()), &amount);
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn is_authorized(env: &Env, id: &Address) -> bool {
+    !env.storage()
+        .persistent()
+        .has(&DataKey::Deauthorized(id.clone()))
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn set_authorized(env: &Env, id: &Address, authorized: bool) {
+    let key = DataKey::Deauthorized(id.clone());
+    if authorized {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &());
+    }
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn is_frozen(env: &Env, id: &Address) -> bool {
+    env.storage().persistent().has(&DataKey::Frozen(id.clone()))
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn set_frozen(env: &Env, id: &Address, frozen: bool) {
+    let key = DataKey::Frozen(id.clone());
+    if frozen {
+        env.storage().persistent().set(&key, &());
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn get_clawback_limit(env: &Env) -> Option<ClawbackLimit> {
+    env.storage().instance().get(&DataKey::ClawbackLimit)
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn set_clawback_limit(env: &Env, limit: &ClawbackLimit) {
+    env.storage().instance().set(&DataKey::ClawbackLimit, limit);
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn get_clawback_window(env: &Env) -> Option<ClawbackWindow> {
+    env.storage().instance().get(&DataKey::ClawbackWindow)
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn set_clawback_window(env: &Env, window: &ClawbackWindow) {
+    env.storage().instance().set(&DataKey::ClawbackWindow, window);
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn get_clawback_proposal(env: &Env) -> Option<ClawbackLimitProposal> {
+    env.storage().instance().get(&DataKey::ClawbackProposal)
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn set_clawback_proposal(env: &Env, proposal: &ClawbackLimitProposal) {
+    env.storage().instance().set(&DataKey::ClawbackProposal, proposal);
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn remove_clawback_proposal(env: &Env) {
+    env.storage().instance().remove(&DataKey::ClawbackProposal);
+}
+
+pub(crate) fn get_allowance(env: &Env, from: &Address, spender: &Address) -> i128 {
+    let value: Option<AllowanceValue> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Allowance(from.clone(), spender.clone()));
+    match value {
+        Some(v) if v.expiration_ledger >= env.ledger().sequence() => v.amount,
+        _ => 0,
+    }
+}
+
+/// Writes an allowance, deleting the slot instead of storing a zero so
+/// revoked and fully-spent approvals do not linger in persistent
+/// storage. Expired slots are reclaimed the same way on overwrite.
+pub(crate) fn set_allowance(
+    env: &Env,
+    from: &Address,
+    spender: &Address,
+    amount: i128,
+    expiration_ledger: u32,
+) {
+    let key = DataKey::Allowance(from.clone(), spender.clone());
+    if amount == 0 {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    env.storage().persistent().set(
+        &key,
+        &AllowanceValue {
+            amount,
+            expiration_ledger,
+        },
+    );
+}
+
+/// Deletes the allowance slot if it exists and its expiry has passed.
+/// Returns whether an entry was actually reclaimed.
+pub(crate) fn remove_allowance_if_expired(env: &Env, from: &Address, spender: &Address) -> bool {
+    let key = DataKey::Allowance(from.clone(), spender.clone());
+    let value: Option<AllowanceValue> = env.storage().persistent().get(&key);
+    match value {
+        Some(v) if v.expiration_ledger < env.ledger().sequence() => {
+            env.storage().persistent().remove(&key);
+            true
+        }
+        _ => false,
+    }
+}
+
+pub(crate) fn get_cdp(env: &Env, lender: &Address) -> Option<CDP> {
+    env.storage().persistent().get(&DataKey::CDP(lender.clone()))
+}
+
+pub(crate) fn get_operator(env: &Env, lender: &Address) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Operator(lender.clone()))
+}
+
+pub(crate) fn set_operator(env: &Env, lender: &Address, operator: &Option<Address>) {
+    let key = DataKey::Operator(lender.clone());
+    match operator {
+        Some(operator) => env.storage().persistent().set(&key, operator),
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+pub(crate) fn cdp_count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::CDPCount)
+        .unwrap_or(0)
+}
+
+pub(crate) fn cdp_at(env: &Env, index: u32) -> Option<Address> {
+    env.storage().persistent().get(&DataKey::CDPIndex(index))
+}
+
+/// Registers a lender in the CDP index the first time they open a
+/// position, so redemptions can walk every CDP.
+pub(crate) fn push_cdp_lender(env: &Env, lender: &Address) {
+    let count = cdp_count(env);
+    env.storage()
+        .persistent()
+        .set(&DataKey::CDPIndex(count), lender);
+    env.storage().instance().set(&DataKey::CDPCount, &(count + 1));
+}
+
+pub(crate) fn set_cdp(env: &Env, cdp: &CDP) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::CDP(cdp.lender.clone()), cdp);
+}
+
+pub(crate) fn get_auction(env: &Env, lender: &Address) -> Option<Auction> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Auction(lender.clone()))
+}
+
+pub(crate) fn set_auction(env: &Env, auction: &Auction) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Auction(auction.lender.clone()), auction);
+}
+
+pub(crate) fn remove_auction(env: &Env, lender: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Auction(lender.clone()));
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn get_withdraw_cooldown(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::WithdrawCooldown)
+        .unwrap_or(0)
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn set_withdraw_cooldown(env: &Env, secs: u64) {
+    env.storage().instance().set(&DataKey::WithdrawCooldown, &secs);
+}
+
+pub(crate) fn get_savings(env: &Env, owner: &Address) -> Option<SavingsPosition> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Savings(owner.clone()))
+}
+
+pub(crate) fn set_savings(env: &Env, position: &SavingsPosition) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Savings(position.owner.clone()), position);
+}
+
+pub(crate) fn remove_savings(env: &Env, owner: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Savings(owner.clone()));
+}
+
+pub(crate) fn get_total_savings(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::TotalSavings)
+        .unwrap_or(0)
+}
+
+pub(crate) fn set_total_savings(env: &Env, total: i128) {
+    env.storage().instance().set(&DataKey::TotalSavings, &total);
+}
+
+pub(crate) fn get_savings_constant(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::SavingsConstant)
+        .unwrap_or(0)
+}
+
+pub(crate) fn set_savings_constant(env: &Env, constant: i128) {
+    env.storage().instance().set(&DataKey::SavingsConstant, &constant);
+}
+
+pub(crate) fn get_savings_rate_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::SavingsRateBps)
+        .unwrap_or(0)
+}
+
+pub(crate) fn set_savings_rate_bps(env: &Env, bps: u32) {
+    env.storage().instance().set(&DataKey::SavingsRateBps, &bps);
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn get_frontend(env: &Env, owner: &Address) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Frontend(owner.clone()))
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn set_frontend(env: &Env, owner: &Address, frontend: &Address) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Frontend(owner.clone()), frontend);
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn remove_frontend(env: &Env, owner: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Frontend(owner.clone()));
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn get_frontend_kickback_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::FrontendKickbackBps)
+        .unwrap_or(0)
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn set_frontend_kickback_bps(env: &Env, bps: u32) {
+    env.storage().instance().set(&DataKey::FrontendKickbackBps, &bps);
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn get_withdrawal_request(env: &Env, owner: &Address) -> Option<WithdrawalRequest> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::WithdrawalRequest(owner.clone()))
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn set_withdrawal_request(env: &Env, request: &WithdrawalRequest) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::WithdrawalRequest(request.owner.clone()), request);
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn remove_withdrawal_request(env: &Env, owner: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::WithdrawalRequest(owner.clone()));
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn get_stake(env: &Env, owner: &Address) -> Option<StakePosition> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Stake(owner.clone()))
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn set_stake(env: &Env, position: &StakePosition) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Stake(position.owner.clone()), position);
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn remove_stake(env: &Env, owner: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Stake(owner.clone()));
+}
+
+pub(crate) fn get_receipt_hook(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::ReceiptHook)
+}
+
+pub(crate) fn set_receipt_hook(env: &Env, hook: &Option<Address>) {
+    match hook {
+        Some(addr) => env.storage().instance().set(&DataKey::ReceiptHook, addr),
+        None => env.storage().instance().remove(&DataKey::ReceiptHook),
+    }
+}
+
+pub(crate) fn get_data_fee_account(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::DataFeeAccount)
+}
+
+pub(crate) fn set_data_fee_account(env: &Env, account: &Option<Address>) {
+    match account {
+        Some(addr) => env.storage().instance().set(&DataKey::DataFeeAccount, addr),
+        None => env.storage().instance().remove(&DataKey::DataFeeAccount),
+    }
+}
+
+pub(crate) fn metering_enabled(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::Metering)
+        .unwrap_or(false)
+}
+
+pub(crate) fn set_metering(env: &Env, enabled: bool) {
+    env.storage().instance().set(&DataKey::Metering, &enabled);
+}
+
+pub(crate) fn emergency_mode(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::EmergencyMode)
+        .unwrap_or(false)
+}
+
+pub(crate) fn set_emergency_mode(env: &Env, enabled: bool) {
+    env.storage()
+        .instance()
+        .set(&DataKey::EmergencyMode, &enabled);
+}
+
+pub(crate) fn is_paused(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::Paused)
+        .unwrap_or(false)
+}
+
+pub(crate) fn set_paused(env: &Env, paused: bool) {
+    env.storage().instance().set(&DataKey::Paused, &paused);
+}
+
+pub(crate) fn get_mcr_band(env: &Env) -> Option<McrBand> {
+    env.storage().instance().get(&DataKey::McrBand)
+}
+
+pub(crate) fn set_mcr_band(env: &Env, band: &Option<McrBand>) {
+    match band {
+        Some(band) => env.storage().instance().set(&DataKey::McrBand, band),
+        None => env.storage().instance().remove(&DataKey::McrBand),
+    }
+}
+
+pub(crate) fn has_role(env: &Env, role: &Role, who: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::Role(role.clone(), who.clone()))
+}
+
+pub(crate) fn set_role(env: &Env, role: &Role, who: &Address, granted: bool) {
+    let key = DataKey::Role(role.clone(), who.clone());
+    if granted {
+        env.storage().persistent().set(&key, &());
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+pub(crate) fn get_pauser(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Pauser)
+}
+
+pub(crate) fn set_pauser(env: &Env, pauser: &Option<Address>) {
+    match pauser {
+        Some(addr) => env.storage().instance().set(&DataKey::Pauser, addr),
+        None => env.storage().instance().remove(&DataKey::Pauser),
+    }
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn get_regulated_threshold(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::RegulatedThreshold)
+        .unwrap_or(0)
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn set_regulated_threshold(env: &Env, threshold: i128) {
+    env.storage()
+        .instance()
+        .set(&DataKey::RegulatedThreshold, &threshold);
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn get_approval_server(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::ApprovalServer)
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn set_approval_server(env: &Env, server: &Option<Address>) {
+    match server {
+        Some(addr) => env.storage().instance().set(&DataKey::ApprovalServer, addr),
+        None => env.storage().instance().remove(&DataKey::ApprovalServer),
+    }
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn get_compliance_hook(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::ComplianceHook)
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn set_compliance_hook(env: &Env, hook: &Option<Address>) {
+    match hook {
+        Some(addr) => env.storage().instance().set(&DataKey::ComplianceHook, addr),
+        None => env.storage().instance().remove(&DataKey::ComplianceHook),
+    }
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn get_kyc_registry(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::KycRegistry)
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn set_kyc_registry(env: &Env, registry: &Option<Address>) {
+    match registry {
+        Some(addr) => env.storage().instance().set(&DataKey::KycRegistry, addr),
+        None => env.storage().instance().remove(&DataKey::KycRegistry),
+    }
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn get_transfer_approval(env: &Env, from: &Address, to: &Address) -> Option<i128> {
+    env.storage()
+        .temporary()
+        .get(&DataKey::TransferApproval(from.clone(), to.clone()))
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn set_transfer_approval(env: &Env, from: &Address, to: &Address, amount: i128) {
+    env.storage()
+        .temporary()
+        .set(&DataKey::TransferApproval(from.clone(), to.clone()), &amount);
+}
+
+#[cfg(feature = "compliance")]
+pub(crate) fn remove_transfer_approval(env: &Env, from: &Address, to: &Address) {
+    env.storage()
+        .temporary()
+        .remove(&DataKey::TransferApproval(from.clone(), to.clone()));
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn get_liquidation_log(env: &Env) -> Vec<LiquidationRecord> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::LiquidationLog)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn push_liquidation_record(env: &Env, record: &LiquidationRecord) {
+    let mut log = get_liquidation_log(env);
+    log.push_back(record.clone());
+    while log.len() > MAX_LIQUIDATION_HISTORY {
+        log.remove(0);
+    }
+    env.storage().persistent().set(&DataKey::LiquidationLog, &log);
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn get_compound_record(env: &Env, epoch: u64) -> Option<CompoundRecord> {
+    env.storage().persistent().get(&DataKey::CompoundRecord(epoch))
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn set_compound_record(env: &Env, epoch: u64, record: &CompoundRecord) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::CompoundRecord(epoch), record);
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn get_interest_record(env: &Env, epoch: u64) -> Option<InterestRecord> {
+    env.storage().persistent().get(&DataKey::InterestRecord(epoch))
+}
+
+#[cfg(feature = "stability-pool")]
+pub(crate) fn set_interest_record(env: &Env, epoch: u64, record: &InterestRecord) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::InterestRecord(epoch), record);
+}
+
+/// Extends the TTL of `who`'s persistent entries — balance, CDP, pool
+/// stake, withdrawal notice, and savings position — so custodians can
+/// keep dormant accounts from archiving. Entries that do not exist are
+/// skipped; extending a fresh entry is a no-op below the threshold.
+pub(crate) fn bump_account(env: &Env, who: &Address) {
+    let keys: [DataKey; 5] = [
+        DataKey::Balance(who.clone()),
+        DataKey::CDP(who.clone()),
+        DataKey::Stake(who.clone()),
+        DataKey::WithdrawalRequest(who.clone()),
+        DataKey::Savings(who.clone()),
+    ];
+    for key in keys.iter() {
+        if env.storage().persistent().has(key) {
+            env.storage().persistent().extend_ttl(
+                key,
+                PERSISTENT_LIFETIME_THRESHOLD,
+                PERSISTENT_BUMP_AMOUNT,
+            );
+        }
+    }
+}
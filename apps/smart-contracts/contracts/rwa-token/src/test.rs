@@ -0,0 +1,2902 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Events, Ledger},
+    token::StellarAssetClient,
+    xdr::{ContractEventBody, ScVal},
+    Address, BytesN, Env, String,
+};
+
+use oracle::{Asset, RWAOracle, RWAOracleClient};
+
+use crate::{CDPStatus, Error, McrBand, RWAToken, RWATokenClient};
+#[cfg(feature = "stability-pool")]
+use crate::RewardMode;
+
+pub(crate) struct TestEnv<'a> {
+    pub env: Env,
+    pub admin: Address,
+    pub token: RWATokenClient<'a>,
+    pub oracle: RWAOracleClient<'a>,
+    pub xlm: StellarAssetClient<'a>,
+}
+
+/// Registers an oracle (feeding both the RWA and XLM), an XLM SAC, and
+/// the token contract. RWA is priced at 2 XLM (RWA $2.00, XLM $1.00).
+pub(crate) fn setup(env: &Env) -> TestEnv<'_> {
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000_000);
+    let admin = Address::generate(env);
+
+    let oracle_id = env.register(
+        RWAOracle,
+        (&admin, Asset::Other(symbol_short!("USD")), 7u32, 300u32),
+    );
+    let oracle = RWAOracleClient::new(env, &oracle_id);
+    let assets = soroban_sdk::vec![
+        env,
+        Asset::Other(symbol_short!("TBOND")),
+        Asset::Other(symbol_short!("XLM")),
+    ];
+    oracle.add_assets(&assets);
+    oracle.set_asset_price(&admin, &Asset::Other(symbol_short!("TBOND")), &2_0000000, &999_000);
+    oracle.set_asset_price(&admin, &Asset::Other(symbol_short!("XLM")), &1_0000000, &999_000);
+
+    let sac = env.register_stellar_asset_contract_v2(admin.clone());
+    let xlm = StellarAssetClient::new(env, &sac.address());
+
+    let token_id = env.register(
+        RWAToken,
+        (
+            &admin,
+            String::from_str(env, "Tokenized T-Bond"),
+            String::from_str(env, "TBOND"),
+            symbol_short!("TBOND"),
+            &oracle_id,
+            &oracle_id,
+            &sac.address(),
+            symbol_short!("XLM"),
+            15_000u32, // 150% MCR
+            500u32,    // 5% APR
+        ),
+    );
+    let token = RWATokenClient::new(env, &token_id);
+    TestEnv {
+        env: env.clone(),
+        admin,
+        token,
+        oracle,
+        xlm,
+    }
+}
+
+pub(crate) fn fund_xlm(t: &TestEnv, who: &Address, amount: i128) {
+    t.xlm.mint(who, &amount);
+}
+
+fn last_event_has_topic(env: &Env, name: &str) -> bool {
+    let events = env.events().all();
+    let last = events.events().last().unwrap();
+    let ContractEventBody::V0(body) = &last.body;
+    match body.topics.first() {
+        Some(ScVal::Symbol(sym)) => sym.0.as_slice() == name.as_bytes(),
+        _ => false,
+    }
+}
+
+fn any_event_has_topic(env: &Env, name: &str) -> bool {
+    let events = env.events().all();
+    events.events().iter().any(|event| {
+        let ContractEventBody::V0(body) = &event.body;
+        matches!(
+            body.topics.first(),
+            Some(ScVal::Symbol(sym)) if sym.0.as_slice() == name.as_bytes()
+        )
+    })
+}
+
+#[test]
+fn token_metadata() {
+    let env = Env::default();
+    let t = setup(&env);
+    assert_eq!(t.token.decimals(), 7);
+    assert_eq!(t.token.name(), String::from_str(&env, "Tokenized T-Bond"));
+    assert_eq!(t.token.symbol(), String::from_str(&env, "TBOND"));
+    assert_eq!(t.token.total_supply(), 0);
+}
+
+#[test]
+fn build_info_reports_crate_version() {
+    let env = Env::default();
+    let t = setup(&env);
+    let (version, sha, timestamp, sdk) = t.token.build_info();
+    assert_eq!(version, String::from_str(&env, env!("CARGO_PKG_VERSION")));
+    // Local builds have no release pipeline stamping the rest.
+    assert_eq!(sha, String::from_str(&env, "unknown"));
+    assert_eq!(timestamp, String::from_str(&env, "unknown"));
+    assert_eq!(sdk, String::from_str(&env, "unknown"));
+}
+
+#[test]
+fn transfer_and_allowance_flow() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    // Mint RWA to `a` by opening a CDP: 300 XLM backing 100 RWA (150% CR).
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+    assert_eq!(t.token.balance(&a), 100_0000000);
+
+    t.token.transfer(&a, &b, &40_0000000);
+    assert_eq!(t.token.balance(&a), 60_0000000);
+    assert_eq!(t.token.balance(&b), 40_0000000);
+
+    t.token.approve(&b, &a, &10_0000000, &1000);
+    assert_eq!(t.token.allowance(&b, &a), 10_0000000);
+    t.token.transfer_from(&a, &b, &a, &10_0000000);
+    assert_eq!(t.token.allowance(&b, &a), 0);
+    assert_eq!(t.token.balance(&a), 70_0000000);
+
+    // Bulk views mirror the single-account reads, in input order.
+    assert_eq!(
+        t.token
+            .balances(&soroban_sdk::vec![&env, a.clone(), b.clone()]),
+        soroban_sdk::vec![&env, 70_0000000, 30_0000000]
+    );
+    assert_eq!(
+        t.token
+            .allowances(&soroban_sdk::vec![&env, (b.clone(), a.clone()), (a, b)]),
+        soroban_sdk::vec![&env, 0, 0]
+    );
+}
+
+#[test]
+fn open_cdp_enforces_mcr() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    // 100 RWA at 2 XLM each = 200 XLM debt value; 250 XLM < 150% of that.
+    assert_eq!(
+        t.token
+            .try_open_cdp(&a, &250_0000000, &100_0000000, &None)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::CollateralRatioBelowMinimum
+    );
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+    assert_eq!(
+        t.token.get_collateralization_ratio(&a),
+        15_000 // exactly 150%
+    );
+    let cdp = t.token.get_cdp(&a).unwrap();
+    assert_eq!(cdp.collateral_deposited, 300_0000000);
+    assert_eq!(cdp.asset_lent, 100_0000000);
+    assert_eq!(cdp.status, CDPStatus::Open);
+}
+
+#[test]
+fn deadline_guards_reject_stale_transactions() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    env.ledger().with_mut(|l| l.sequence_number = 100);
+
+    // A transaction that outlived its deadline fails outright.
+    assert_eq!(
+        t.token
+            .try_open_cdp(&a, &300_0000000, &100_0000000, &Some(99))
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::DeadlineExpired
+    );
+    // At or before the deadline it executes normally.
+    t.token.open_cdp(&a, &310_0000000, &100_0000000, &Some(100));
+    assert_eq!(
+        t.token
+            .try_borrow_rwa(&a, &1_0000000, &Some(99))
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::DeadlineExpired
+    );
+    assert_eq!(
+        t.token
+            .try_withdraw_collateral(&a, &1_0000000, &Some(99))
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::DeadlineExpired
+    );
+    // No deadline means no bound.
+    t.token.withdraw_collateral(&a, &1, &None);
+}
+
+#[test]
+fn interest_compounds_per_second() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+    // Half a year of the 5% nominal APR compounded per second on
+    // 100 RWA: 100 * (e^0.025 - 1) ~ 2.53 RWA, a touch above the
+    // simple-interest 2.5 as compounding demands.
+    env.ledger().with_mut(|l| l.timestamp += 31_536_000 / 2);
+    assert_eq!(t.token.get_projected_interest(&a), 2_5315121);
+
+    // Payoff quote: principal, interest, and the XLM cost of that
+    // interest at 2 XLM per RWA, now and half a year out. The horizon
+    // figure compounds on the debt including interest_now, so it lands
+    // on the full-year 100 * (e^0.05 - 1) ~ 5.13 RWA.
+    assert_eq!(
+        t.token.payoff_quote(&a, &(31_536_000 / 2)),
+        (100_0000000, 2_5315121, 5_0630242, 10_2542194)
+    );
+}
+
+#[test]
+fn interest_pauses_during_oracle_outage() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    t.token.set_outage_threshold(&86_400);
+    assert_eq!(t.token.outage_threshold(), 86_400);
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+
+    // The TBOND feed last updated at t=999_000 and then goes silent for
+    // half a year. Accrual stops one threshold past that update, so of
+    // the 15_768_000 elapsed seconds only 85_400 are charged: 100 RWA
+    // compounded at 5% over 85_400s is 135_410 stroops of RWA (the
+    // simple-interest 135_401 plus the second-order term and round-up
+    // dust).
+    env.ledger().with_mut(|l| l.timestamp += 31_536_000 / 2);
+    assert_eq!(t.token.get_projected_interest(&a), 135_410);
+
+    // A fresh update ends the outage and accrual resumes, so another
+    // 1000 seconds bring the charged window to 86_400 seconds.
+    let now = env.ledger().timestamp();
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_0000000, &now);
+    env.ledger().with_mut(|l| l.timestamp += 1_000);
+    assert_eq!(t.token.get_projected_interest(&a), 136_996);
+}
+
+#[test]
+fn stale_collateral_feed_decays_under_haircut() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    fund_xlm(&t, &b, 1000_0000000);
+    // Zero the rate so staleness alone moves the ratio.
+    t.token.set_interest_rate(&t.admin, &0);
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+
+    // Without a haircut a silent feed keeps its face value.
+    env.ledger().with_mut(|l| l.timestamp += 50 * 3_600);
+    assert_eq!(t.token.get_collateralization_ratio(&a), 15_000);
+
+    // 100 bps per hour: the XLM feed last updated at t=999_000, which is
+    // now 50 full hours ago, so collateral is valued 50% lower and the
+    // CDP reads as liquidatable.
+    t.token.set_stale_haircut(&100);
+    assert_eq!(t.token.stale_haircut(), 100);
+    assert_eq!(t.token.get_collateralization_ratio(&a), 7_500);
+    t.token.freeze_cdp(&a.clone(), &a);
+
+    // A fresh update removes the discount entirely.
+    let now = env.ledger().timestamp();
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("XLM")), &1_0000000, &(now - 1_000));
+    t.token.open_cdp(&b, &300_0000000, &100_0000000, &None);
+    assert_eq!(t.token.get_collateralization_ratio(&b), 15_000);
+}
+
+#[test]
+fn tiered_rates_apply_per_tranche() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    // 5% on the first 50 RWA, 10% above: a 100 RWA CDP is priced at
+    // 7.5 RWA per year, so half a year of per-second compounding at
+    // that 7.5% blend charges 100 * (e^0.0375 - 1) ~ 3.82 RWA.
+    t.token.set_rate_tiers(&soroban_sdk::vec![
+        &env,
+        crate::RateTier { floor: 0, rate_bps: 500 },
+        crate::RateTier { floor: 50_0000000, rate_bps: 1000 },
+    ]);
+    t.token.open_cdp(&a, &400_0000000, &100_0000000, &None);
+    env.ledger().with_mut(|l| l.timestamp += 31_536_000 / 2);
+    assert_eq!(t.token.get_projected_interest(&a), 3_8211998);
+
+    // Schedules must start at zero and ascend.
+    assert!(t.token
+        .try_set_rate_tiers(&soroban_sdk::vec![
+            &env,
+            crate::RateTier { floor: 1, rate_bps: 500 },
+        ])
+        .is_err());
+    assert!(t.token
+        .try_set_rate_tiers(&soroban_sdk::vec![
+            &env,
+            crate::RateTier { floor: 0, rate_bps: 500 },
+            crate::RateTier { floor: 0, rate_bps: 1000 },
+        ])
+        .is_err());
+
+    // Clearing the schedule reverts to the flat rate.
+    t.token.set_rate_tiers(&soroban_sdk::vec![&env]);
+    assert_eq!(t.token.rate_tiers().len(), 0);
+}
+
+#[test]
+fn rounding_drift_is_bounded() {
+    // The policy helpers round as documented.
+    assert_eq!(crate::rounding::mul_div_floor(10, 1, 3), 3);
+    assert_eq!(crate::rounding::mul_div_ceil(10, 1, 3), 4);
+    assert_eq!(crate::rounding::mul_div_ceil(10, 1, 5), 2);
+
+    // Worst-case drift: accruing in many small steps rounds up at most
+    // one stroop per step versus a single accrual over the same span.
+    let env = Env::default();
+    let t = setup(&env);
+    let stepped = Address::generate(&env);
+    let single = Address::generate(&env);
+    fund_xlm(&t, &stepped, 1000_0000000);
+    fund_xlm(&t, &single, 1000_0000000);
+    t.token.open_cdp(&stepped, &300_0000000, &1_0000000, &None);
+    t.token.open_cdp(&single, &300_0000000, &1_0000000, &None);
+
+    let steps = 30;
+    for _ in 0..steps {
+        env.ledger().with_mut(|l| l.timestamp += 86_400);
+        // Any state-changing CDP call persists the accrual checkpoint.
+        t.token.withdraw_collateral(&stepped, &1, &None);
+    }
+    let stepped_interest = t.token.get_projected_interest(&stepped);
+    let single_interest = t.token.get_projected_interest(&single);
+    assert!(stepped_interest >= single_interest);
+    assert!(stepped_interest - single_interest < steps);
+}
+
+#[test]
+fn redeem_draws_from_lowest_ratio_cdps() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let c = Address::generate(&env);
+    let redeemer = Address::generate(&env);
+    for who in [&a, &b, &c] {
+        fund_xlm(&t, who, 1000_0000000);
+    }
+
+    // Three CDPs at 150%, 200%, and 300% collateralization.
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+    t.token.open_cdp(&b, &400_0000000, &100_0000000, &None);
+    t.token.open_cdp(&c, &600_0000000, &100_0000000, &None);
+    t.token.transfer(&a, &redeemer, &100_0000000);
+    t.token.transfer(&b, &redeemer, &50_0000000);
+
+    // 150 RWA at 2 XLM face value: 100 wiped from the riskiest CDP,
+    // then 50 from the next, never touching the safest.
+    assert_eq!(t.token.redeem(&redeemer, &150_0000000), 300_0000000);
+    assert!(last_event_has_topic(&env, "redeemed"));
+    assert_eq!(t.token.balance(&redeemer), 0);
+    let xlm = soroban_sdk::token::TokenClient::new(&env, &t.xlm.address);
+    assert_eq!(xlm.balance(&redeemer), 300_0000000);
+
+    let cdp_a = t.token.get_cdp(&a).unwrap();
+    assert_eq!(cdp_a.asset_lent, 0);
+    assert_eq!(cdp_a.collateral_deposited, 100_0000000);
+    let cdp_b = t.token.get_cdp(&b).unwrap();
+    assert_eq!(cdp_b.asset_lent, 50_0000000);
+    assert_eq!(cdp_b.collateral_deposited, 300_0000000);
+    assert_eq!(t.token.get_cdp(&c).unwrap().asset_lent, 100_0000000);
+    assert_eq!(t.token.total_supply(), 150_0000000);
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn freeze_and_liquidate_through_pool() {
+    let env = Env::default();
+    let t = setup(&env);
+    let borrower = Address::generate(&env);
+    let staker = Address::generate(&env);
+    fund_xlm(&t, &borrower, 1000_0000000);
+    fund_xlm(&t, &staker, 100_0000000);
+
+    t.token.open_cdp(&borrower, &300_0000000, &100_0000000, &None);
+    t.token.transfer(&borrower, &staker, &100_0000000);
+    t.token.stake(&staker, &100_0000000, &None);
+    assert_eq!(t.token.get_total_rwa(), 100_0000000);
+
+    // Healthy CDP cannot be frozen.
+    assert_eq!(
+        t.token.try_freeze_cdp(&borrower.clone(), &borrower).err().unwrap().unwrap(),
+        Error::CollateralRatioSafe
+    );
+
+    // RWA price spikes: CR falls below 150%.
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_5000000, &999_400);
+    t.token.freeze_cdp(&borrower.clone(), &borrower);
+    assert_eq!(t.token.get_cdp(&borrower).unwrap().status, CDPStatus::Frozen);
+
+    let liquidator = Address::generate(&env);
+    t.token.liquidate_cdp(&liquidator, &borrower);
+    // The pool emptied completely, so the epoch boundary was announced.
+    assert!(any_event_has_topic(&env, "epoch"));
+    let cdp = t.token.get_cdp(&borrower).unwrap();
+    assert_eq!(cdp.status, CDPStatus::Closed);
+    assert_eq!(cdp.asset_lent, 0);
+    // The absorbed debt was burned out of the pool's deposits.
+    assert_eq!(t.token.total_supply(), 0);
+    // Pool fully emptied: epoch advanced, staker's deposit is gone but
+    // the seized collateral is claimable.
+    assert_eq!(t.token.get_total_rwa(), 0);
+    assert_eq!(t.token.get_deposit(&staker), 0);
+    assert_eq!(t.token.get_rewards(&staker), 300_0000000);
+    let claimed = t.token.claim_rewards(&staker);
+    assert_eq!(claimed, 300_0000000);
+}
+
+#[cfg(feature = "compliance")]
+#[test]
+fn clawback_is_rate_limited_with_timelock() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    t.token.open_cdp(&a, &600_0000000, &100_0000000, &None);
+    let hash = BytesN::from_array(&env, &[7u8; 32]);
+
+    // Uncapped until a limit is configured; every burn carries the
+    // justification hash.
+    t.token.clawback(&a, &1_0000000, &hash);
+    assert!(last_event_has_topic(&env, "clawback"));
+
+    // The limit only takes effect through the timelocked path.
+    let effective = t.token.propose_clawback_limit(&10_0000000, &100);
+    assert_eq!(
+        t.token.try_apply_clawback_limit().err().unwrap().unwrap(),
+        Error::TimelockNotElapsed
+    );
+    env.ledger().with_mut(|l| l.sequence_number = effective);
+    t.token.apply_clawback_limit();
+    assert_eq!(t.token.clawback_limit().unwrap().cap, 10_0000000);
+
+    t.token.clawback(&a, &6_0000000, &hash);
+    assert_eq!(
+        t.token
+            .try_clawback(&a, &5_0000000, &hash)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::ClawbackCapExceeded
+    );
+    // The cap resets once the window rolls over.
+    env.ledger().with_mut(|l| l.sequence_number += 100);
+    t.token.clawback(&a, &5_0000000, &hash);
+    assert_eq!(t.token.balance(&a), 88_0000000);
+}
+
+#[test]
+fn supply_factor_scales_reported_face_values() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+
+    // At the identity factor, face value equals unscaled units.
+    assert_eq!(t.token.scaled_balance(&a), 100_0000000);
+
+    // The bond amortizes to 90% of original principal.
+    t.token.set_supply_factor(&900_000_000);
+    assert!(last_event_has_topic(&env, "rebase"));
+    assert_eq!(t.token.scaled_balance(&a), 90_0000000);
+    assert_eq!(t.token.scaled_total_supply(), 90_0000000);
+    assert_eq!(t.token.scaled_amount(&50_0000000), 45_0000000);
+
+    // On-chain units are untouched: balances, transfers, and CDP debt
+    // keep their unscaled amounts.
+    assert_eq!(t.token.balance(&a), 100_0000000);
+    assert_eq!(t.token.get_cdp(&a).unwrap().asset_lent, 100_0000000);
+    let b = Address::generate(&env);
+    t.token.transfer(&a, &b, &10_0000000);
+    assert_eq!(t.token.balance(&a), 90_0000000);
+}
+
+#[test]
+fn debt_ceiling_caps_cdp_minting() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    fund_xlm(&t, &b, 1000_0000000);
+
+    t.token.set_debt_ceiling(&150_0000000);
+    t.token.open_cdp(&a, &600_0000000, &100_0000000, &None);
+
+    // Borrowing past the ceiling fails; up to it succeeds.
+    assert_eq!(
+        t.token
+            .try_borrow_rwa(&a, &60_0000000, &None)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::DebtCeilingExceeded
+    );
+    t.token.borrow_rwa(&a, &50_0000000, &None);
+    assert_eq!(
+        t.token
+            .try_open_cdp(&b, &300_0000000, &1_0000000, &None)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::DebtCeilingExceeded
+    );
+
+    // Zero lifts the cap.
+    t.token.set_debt_ceiling(&0);
+    t.token.open_cdp(&b, &300_0000000, &100_0000000, &None);
+}
+
+#[test]
+fn sweep_freezes_undercollateralized_cdps() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let c = Address::generate(&env);
+    for who in [&a, &b, &c] {
+        fund_xlm(&t, who, 1000_0000000);
+    }
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None); // 150%
+    t.token.open_cdp(&b, &400_0000000, &100_0000000, &None); // 200%
+    t.token.open_cdp(&c, &310_0000000, &100_0000000, &None); // 155%
+
+    // Price spike leaves `a` and `c` below MCR, `b` still safe.
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_5000000, &999_400);
+
+    // `max_n` caps how many positions one call freezes.
+    assert_eq!(t.token.sweep_insolvent(&1), 1);
+    assert!(last_event_has_topic(&env, "sweep"));
+    assert_eq!(t.token.get_cdp(&a).unwrap().status, CDPStatus::Frozen);
+    assert_eq!(t.token.get_cdp(&c).unwrap().status, CDPStatus::Open);
+
+    assert_eq!(t.token.sweep_insolvent(&10), 1);
+    assert_eq!(t.token.get_cdp(&b).unwrap().status, CDPStatus::Open);
+    assert_eq!(t.token.get_cdp(&c).unwrap().status, CDPStatus::Frozen);
+
+    // Nothing left to freeze: no-op, no event.
+    assert_eq!(t.token.sweep_insolvent(&10), 0);
+    assert!(!any_event_has_topic(&env, "sweep"));
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn keeper_bounty_pays_freeze_and_liquidate_callers() {
+    let env = Env::default();
+    let t = setup(&env);
+    let borrower = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    fund_xlm(&t, &borrower, 1000_0000000);
+    fund_xlm(&t, &staker, 100_0000000);
+    t.token.set_keeper_bounty(&2_0000000);
+
+    t.token.open_cdp(&borrower, &300_0000000, &100_0000000, &None);
+    t.token.transfer(&borrower, &staker, &100_0000000);
+    t.token.stake(&staker, &100_0000000, &None);
+
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_5000000, &999_400);
+    t.token.freeze_cdp(&keeper, &borrower);
+    assert!(any_event_has_topic(&env, "keeper"));
+    t.token.liquidate_cdp(&keeper, &borrower);
+    assert!(any_event_has_topic(&env, "keeper"));
+
+    // Two bounties paid out of the stake-fee buffer, which still covers
+    // the unstake refund.
+    let xlm = soroban_sdk::token::TokenClient::new(&env, &t.xlm.address);
+    assert_eq!(xlm.balance(&keeper), 4_0000000);
+    assert_eq!(t.token.fees_collected(), 3_0000000);
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn liquidation_penalty_rewards_pool_stakers() {
+    let env = Env::default();
+    let t = setup(&env);
+    let borrower = Address::generate(&env);
+    let staker = Address::generate(&env);
+    fund_xlm(&t, &borrower, 1000_0000000);
+    fund_xlm(&t, &staker, 100_0000000);
+    t.token.set_liquidation_penalty(&1_000); // 10%
+
+    t.token.open_cdp(&borrower, &300_0000000, &100_0000000, &None);
+    t.token.transfer(&borrower, &staker, &50_0000000);
+    t.token.stake(&staker, &50_0000000, &None);
+
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_5000000, &999_400);
+    t.token.freeze_cdp(&borrower.clone(), &borrower);
+    t.token.liquidate_cdp(&Address::generate(&env), &borrower);
+
+    // The pool absorbed 50 of 100 debt, so the proportional seizure is
+    // 150 XLM; the 10% penalty adds 15 XLM on top for the stakers.
+    assert_eq!(t.token.get_rewards(&staker), 165_0000000);
+    let cdp = t.token.get_cdp(&borrower).unwrap();
+    assert_eq!(cdp.collateral_deposited, 135_0000000);
+    assert_eq!(cdp.asset_lent, 50_0000000);
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn emergency_mode_frees_principal_without_rewards() {
+    let env = Env::default();
+    let t = setup(&env);
+    let borrower = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let c = Address::generate(&env);
+    fund_xlm(&t, &borrower, 1000_0000000);
+    fund_xlm(&t, &staker, 100_0000000);
+    fund_xlm(&t, &c, 100_0000000);
+
+    t.token.open_cdp(&borrower, &300_0000000, &100_0000000, &None);
+    t.token.transfer(&borrower, &staker, &100_0000000);
+    t.token.stake(&staker, &100_0000000, &None);
+    // A small second CDP goes under and is absorbed, leaving the staker
+    // with a shrunken deposit and unclaimed collateral rewards.
+    t.token.open_cdp(&c, &90_0000000, &30_0000000, &None);
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_5000000, &999_400);
+    t.token.freeze_cdp(&c.clone(), &c);
+    t.token.liquidate_cdp(&c.clone(), &c);
+    assert_eq!(t.token.get_deposit(&staker), 70_0000000);
+    assert_eq!(t.token.get_rewards(&staker), 90_0000000);
+
+    // The emergency exit is gated on the admin flag.
+    assert_eq!(
+        t.token.try_emergency_withdraw(&staker).err().unwrap().unwrap(),
+        Error::NotInEmergencyMode
+    );
+
+    t.token.set_emergency_mode(&true);
+    assert!(t.token.emergency_mode());
+    let fees_before = t.token.fees_collected();
+    let xlm = soroban_sdk::token::TokenClient::new(&env, &t.xlm.address);
+    let xlm_before = xlm.balance(&staker);
+    assert_eq!(t.token.emergency_withdraw(&staker), 70_0000000);
+    assert!(last_event_has_topic(&env, "emg_exit"));
+
+    // Principal came back in RWA; no collateral was paid out — the
+    // rewards and the unstake refund fell to the treasury.
+    assert_eq!(t.token.balance(&staker), 70_0000000);
+    assert_eq!(xlm.balance(&staker), xlm_before);
+    assert_eq!(t.token.fees_collected(), fees_before + 90_0000000);
+    assert_eq!(t.token.get_stake(&staker), None);
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn keeper_settles_idle_rewards_after_delay() {
+    let env = Env::default();
+    let t = setup(&env);
+    let borrower = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    let c = Address::generate(&env);
+    fund_xlm(&t, &borrower, 1000_0000000);
+    fund_xlm(&t, &staker, 100_0000000);
+    fund_xlm(&t, &c, 100_0000000);
+    t.token.set_claim_policy(&3_600, &100); // 1 hour, 1% tip
+    assert_eq!(t.token.claim_policy(), (3_600, 100));
+
+    t.token.open_cdp(&borrower, &330_0000000, &110_0000000, &None);
+    t.token.transfer(&borrower, &staker, &110_0000000);
+    t.token.stake(&staker, &100_0000000, &None);
+    t.token.open_cdp(&c, &90_0000000, &30_0000000, &None);
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_5000000, &999_400);
+    t.token.freeze_cdp(&c.clone(), &c);
+    t.token.liquidate_cdp(&c.clone(), &c);
+    assert_eq!(t.token.get_rewards(&staker), 90_0000000);
+
+    // The owner gets first chance: keepers must wait out the delay.
+    let list = soroban_sdk::vec![&env, staker.clone(), Address::generate(&env)];
+    assert_eq!(
+        t.token.try_claim_rewards_for(&keeper, &list).err().unwrap().unwrap(),
+        Error::ClaimNotOpen
+    );
+    env.ledger().with_mut(|l| l.timestamp += 3_600);
+
+    let xlm = soroban_sdk::token::TokenClient::new(&env, &t.xlm.address);
+    let staker_before = xlm.balance(&staker);
+    // Unknown addresses in the list are skipped, not an error.
+    assert_eq!(t.token.claim_rewards_for(&keeper, &list), 900_0000);
+    assert!(any_event_has_topic(&env, "claim_for"));
+    assert_eq!(xlm.balance(&staker), staker_before + 89_1000000);
+    assert_eq!(xlm.balance(&keeper), 900_0000);
+    assert_eq!(t.token.get_rewards(&staker), 0);
+    // Nothing pending: the repeat run settles nothing and tips nothing.
+    assert_eq!(t.token.claim_rewards_for(&keeper, &list), 0);
+
+    // A zero delay turns keeper claims off entirely.
+    t.token.set_claim_policy(&0, &100);
+    assert_eq!(
+        t.token.try_claim_rewards_for(&keeper, &list).err().unwrap().unwrap(),
+        Error::ClaimNotOpen
+    );
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn compound_mode_recycles_gains_into_deposit() {
+    let env = Env::default();
+    let t = setup(&env);
+    let borrower = Address::generate(&env);
+    let staker = Address::generate(&env);
+    fund_xlm(&t, &borrower, 1000_0000000);
+    fund_xlm(&t, &staker, 100_0000000);
+
+    t.token.open_cdp(&borrower, &300_0000000, &100_0000000, &None);
+    t.token.transfer(&borrower, &staker, &100_0000000);
+    t.token.stake(&staker, &100_0000000, &None);
+    t.token.set_reward_mode(&staker, &RewardMode::Compound);
+
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_5000000, &999_400);
+    t.token.freeze_cdp(&borrower.clone(), &borrower);
+    t.token.liquidate_cdp(&Address::generate(&env), &borrower);
+
+    // 300 XLM of gains at RWA 2.5 / XLM 1.0 buy 120 RWA, minted
+    // straight into the deposit; the collateral stays with the treasury.
+    assert_eq!(t.token.claim_rewards(&staker), 300_0000000);
+    assert_eq!(t.token.get_deposit(&staker), 120_0000000);
+    assert_eq!(t.token.get_rewards(&staker), 0);
+    assert_eq!(t.token.get_total_rwa(), 120_0000000);
+    assert_eq!(t.token.total_supply(), 120_0000000);
+    // Stake fee plus the recycled collateral reserve.
+    assert_eq!(t.token.fees_collected(), 307_0000000);
+    // The mode survives the snapshot reset.
+    assert_eq!(
+        t.token.get_stake(&staker).unwrap().reward_mode,
+        RewardMode::Compound
+    );
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn position_history_attributes_liquidations() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let c = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let late = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    for who in [&a, &b, &c, &late] {
+        fund_xlm(&t, who, 1000_0000000);
+    }
+    fund_xlm(&t, &staker, 100_0000000);
+
+    for who in [&a, &b, &c] {
+        t.token.open_cdp(who, &300_0000000, &100_0000000, &None);
+        t.token.transfer(who, &staker, &100_0000000);
+    }
+    t.token.stake(&staker, &300_0000000, &None);
+
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_5000000, &999_400);
+    t.token.freeze_cdp(&a.clone(), &a);
+    t.token.freeze_cdp(&b.clone(), &b);
+    t.token.liquidate_cdp(&liquidator, &a);
+    t.token.liquidate_cdp(&liquidator, &b);
+
+    // The first liquidation hit the whole 300 RWA deposit; the second
+    // only the ~200 RWA that survived, so floor rounding keeps its
+    // attribution a hair under the pool totals.
+    let history = t.token.position_history(&staker, &10);
+    assert_eq!(history.len(), 2);
+    assert_eq!(
+        history.get_unchecked(0),
+        crate::LiquidationShare {
+            epoch: 0,
+            debt_absorbed: 100_0000000,
+            collateral_gained: 300_0000000,
+        }
+    );
+    assert_eq!(
+        history.get_unchecked(1),
+        crate::LiquidationShare {
+            epoch: 0,
+            debt_absorbed: 99_9999999,
+            collateral_gained: 299_9999997,
+        }
+    );
+    // Asking for fewer entries keeps the newest.
+    assert_eq!(t.token.position_history(&staker, &1).len(), 1);
+
+    // A staker arriving after the action has no attributions.
+    t.token.open_cdp(&late, &400_0000000, &100_0000000, &None);
+    t.token.stake(&late, &50_0000000, &None);
+    assert_eq!(t.token.position_history(&late, &10).len(), 0);
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn liquidation_split_pays_caller_and_treasury() {
+    let env = Env::default();
+    let t = setup(&env);
+    let borrower = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    fund_xlm(&t, &borrower, 1000_0000000);
+    fund_xlm(&t, &staker, 100_0000000);
+
+    // Shares must sum to 100%.
+    assert_eq!(
+        t.token
+            .try_set_liquidation_split(&crate::LiquidationSplit {
+                pool_bps: 9_000,
+                caller_bps: 500,
+                treasury_bps: 600,
+            })
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::InvalidConfiguration
+    );
+    t.token.set_liquidation_split(&crate::LiquidationSplit {
+        pool_bps: 8_000,
+        caller_bps: 1_000,
+        treasury_bps: 1_000,
+    });
+
+    t.token.open_cdp(&borrower, &300_0000000, &100_0000000, &None);
+    t.token.transfer(&borrower, &staker, &100_0000000);
+    t.token.stake(&staker, &100_0000000, &None);
+    let fees_before = t.token.fees_collected();
+
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_5000000, &999_400);
+    t.token.freeze_cdp(&borrower.clone(), &borrower);
+    t.token.liquidate_cdp(&liquidator, &borrower);
+
+    // 300 XLM seized: 10% to the caller, 10% to the treasury, the rest
+    // to the pool stakers.
+    let xlm = soroban_sdk::token::TokenClient::new(&env, &t.xlm.address);
+    assert_eq!(xlm.balance(&liquidator), 30_0000000);
+    assert_eq!(t.token.fees_collected() - fees_before, 30_0000000);
+    assert_eq!(t.token.get_rewards(&staker), 240_0000000);
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn revenue_breakdown_segments_sources() {
+    let env = Env::default();
+    let t = setup(&env);
+    let borrower = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    fund_xlm(&t, &borrower, 1000_0000000);
+    fund_xlm(&t, &staker, 100_0000000);
+    t.token.set_liquidation_split(&crate::LiquidationSplit {
+        pool_bps: 9_000,
+        caller_bps: 0,
+        treasury_bps: 1_000,
+    });
+
+    t.token.open_cdp(&borrower, &300_0000000, &100_0000000, &None);
+    t.token.transfer(&borrower, &staker, &100_0000000);
+    // Stake fee revenue is the fee net of the refundable portion.
+    t.token.stake(&staker, &100_0000000, &None);
+    assert_eq!(t.token.revenue_breakdown().pool_fees, 5_0000000);
+
+    // A year of the 5% APR compounded per second on 100 RWA at 2 XLM:
+    // 200 * (e^0.05 - 1) ~ 10.25 XLM of interest.
+    env.ledger()
+        .with_mut(|l| l.timestamp += 31_536_000);
+    t.token.pay_interest(&borrower);
+    assert!(last_event_has_topic(&env, "revenue"));
+    assert_eq!(t.token.revenue_breakdown().interest, 10_2542194);
+
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_5000000, &999_400);
+    t.token.freeze_cdp(&borrower.clone(), &borrower);
+    t.token.liquidate_cdp(&liquidator, &borrower);
+
+    let revenue = t.token.revenue_breakdown();
+    assert_eq!(revenue.liquidation_penalties, 30_0000000);
+    assert_eq!(revenue.origination_fees, 0);
+    assert_eq!(revenue.redemption_fees, 0);
+}
+
+#[test]
+fn data_fee_streams_interest_share_to_feed_account() {
+    let env = Env::default();
+    let t = setup(&env);
+    let borrower = Address::generate(&env);
+    let feed_pool = Address::generate(&env);
+    fund_xlm(&t, &borrower, 1000_0000000);
+    t.token.set_data_fee(&Some(feed_pool.clone()), &1_000);
+    assert_eq!(t.token.data_fee(), (Some(feed_pool.clone()), 1_000));
+    t.token.open_cdp(&borrower, &300_0000000, &100_0000000, &None);
+
+    // A year of 5% APR compounded per second on 100 RWA at 2 XLM is
+    // ~10.25 XLM of interest, 10% of which streams on to the feed
+    // account; only the rest is revenue.
+    env.ledger().with_mut(|l| l.timestamp += 31_536_000);
+    t.token.pay_interest(&borrower);
+    assert!(any_event_has_topic(&env, "data_fee"));
+    let xlm = soroban_sdk::token::TokenClient::new(&env, &t.xlm.address);
+    assert_eq!(xlm.balance(&feed_pool), 1_0254219);
+    assert_eq!(t.token.revenue_breakdown().interest, 9_2287975);
+    assert_eq!(t.token.fee_stats(), (0, 9_2287975, 9_2287975, 0));
+
+    assert_eq!(
+        t.token.try_set_data_fee(&None, &10_001).err().unwrap().unwrap(),
+        Error::InvalidConfiguration
+    );
+}
+
+#[test]
+fn feed_decimals_drift_halts_risk_operations() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+    assert_eq!(t.token.expected_feed_decimals(), (7, 7));
+
+    // Simulate an oracle upgrade changing its decimals by desyncing the
+    // recorded expectation from the live value.
+    env.as_contract(&t.token.address, || {
+        let mut state = crate::storage::get_state(&env);
+        state.oracle_decimals = 9;
+        crate::storage::set_state(&env, &state);
+    });
+    assert_eq!(
+        t.token
+            .try_borrow_rwa(&a, &1_0000000, &None)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::FeedDecimalsChanged
+    );
+    assert_eq!(
+        t.token
+            .try_get_collateralization_ratio(&a)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::FeedDecimalsChanged
+    );
+
+    // The admin acknowledges the live decimals and risk operations
+    // resume.
+    t.token.accept_feed_decimals();
+    assert_eq!(t.token.expected_feed_decimals(), (7, 7));
+    assert_eq!(t.token.get_collateralization_ratio(&a), 15_000);
+}
+
+#[test]
+fn recovery_mode_blocks_leverage_and_widens_freeze_threshold() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let c = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    fund_xlm(&t, &b, 1000_0000000);
+    fund_xlm(&t, &c, 1000_0000000);
+    t.token.open_cdp(&a, &300_0000000, &50_0000000, &None);
+    t.token.open_cdp(&b, &300_0000000, &100_0000000, &None);
+    t.token.open_cdp(&c, &350_0000000, &100_0000000, &None);
+
+    // The critical ratio must not sit below the MCR.
+    assert_eq!(
+        t.token
+            .try_set_critical_collat_ratio(&10_000)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::InvalidConfiguration
+    );
+    t.token.set_critical_collat_ratio(&18_000);
+
+    // 950 XLM backing 250 RWA at 2.0: TCR 190%, comfortably above the
+    // critical 180%.
+    assert_eq!(t.token.total_collateralization_ratio(), 19_000);
+    assert!(!t.token.recovery_mode());
+
+    // TBOND climbs to 2.2: TCR drops to 950/550 = 172.7%.
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_2000000, &999_400);
+    assert_eq!(t.token.total_collateralization_ratio(), 17_272);
+    assert!(t.token.recovery_mode());
+
+    // Leverage-increasing operations are blocked system-wide, even for
+    // healthy CDPs.
+    assert_eq!(
+        t.token.try_borrow_rwa(&a, &1_0000000, &None).err().unwrap().unwrap(),
+        Error::RecoveryModeActive
+    );
+    assert_eq!(
+        t.token
+            .try_withdraw_collateral(&a, &1_0000000, &None)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::RecoveryModeActive
+    );
+
+    // The freeze threshold widens from the MCR to the live TCR: a at
+    // 272% stays safe, but c at 159% is now freezable despite clearing
+    // the 150% MCR.
+    assert_eq!(
+        t.token.try_freeze_cdp(&a.clone(), &a).err().unwrap().unwrap(),
+        Error::CollateralRatioSafe
+    );
+    assert_eq!(t.token.get_collateralization_ratio(&c), 15_909);
+    t.token.freeze_cdp(&c.clone(), &c);
+
+    // A recovering price lifts the TCR back above the critical line and
+    // normal operation resumes.
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_0000000, &999_700);
+    assert!(!t.token.recovery_mode());
+    t.token.borrow_rwa(&a, &1_0000000, &None);
+}
+
+#[test]
+fn metering_toggle_samples_heavy_entrypoints() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    fund_xlm(&t, &b, 1000_0000000);
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+    t.token.open_cdp(&b, &400_0000000, &100_0000000, &None);
+
+    // Off by default: heavy calls publish no sample.
+    t.token.redeem(&b, &10_0000000);
+    assert!(!any_event_has_topic(&env, "metered"));
+
+    t.token.set_metering(&true);
+    assert!(t.token.metering());
+    t.token.redeem(&b, &10_0000000);
+    assert!(last_event_has_topic(&env, "metered"));
+
+    // Even a sweep that freezes nothing reports its registry walk.
+    t.token.sweep_insolvent(&5);
+    assert!(last_event_has_topic(&env, "metered"));
+
+    t.token.set_metering(&false);
+    t.token.redeem(&b, &10_0000000);
+    assert!(!any_event_has_topic(&env, "metered"));
+}
+
+#[test]
+fn direct_liquidation_pays_repayer_collateral_plus_penalty() {
+    let env = Env::default();
+    let t = setup(&env);
+    let borrower = Address::generate(&env);
+    let liquidator = Address::generate(&env);
+    fund_xlm(&t, &borrower, 1000_0000000);
+    t.token.set_liquidation_penalty(&1_000);
+    t.token.open_cdp(&borrower, &300_0000000, &100_0000000, &None);
+    t.token.transfer(&borrower, &liquidator, &100_0000000);
+
+    // Only frozen CDPs accept direct repayment.
+    assert_eq!(
+        t.token
+            .try_liquidate_with_repay(&liquidator, &borrower, &40_0000000)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::CDPNotFrozen
+    );
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_5000000, &999_400);
+    t.token.freeze_cdp(&liquidator.clone(), &borrower);
+
+    // Repaying 40 of the 100 RWA debt seizes 40% of the collateral plus
+    // the 10% penalty: 120 + 12 XLM.
+    assert_eq!(
+        t.token.liquidate_with_repay(&liquidator, &borrower, &40_0000000),
+        132_0000000
+    );
+    let cdp = t.token.get_cdp(&borrower).unwrap();
+    assert_eq!(cdp.asset_lent, 60_0000000);
+    assert_eq!(cdp.collateral_deposited, 168_0000000);
+
+    // The rest of the debt takes every remaining drop of collateral, so
+    // the penalty caps out at zero and the position closes.
+    assert_eq!(
+        t.token.liquidate_with_repay(&liquidator, &borrower, &60_0000000),
+        168_0000000
+    );
+    assert_eq!(t.token.get_cdp(&borrower).unwrap().status, CDPStatus::Closed);
+    let xlm = soroban_sdk::token::TokenClient::new(&env, &t.xlm.address);
+    assert_eq!(xlm.balance(&liquidator), 300_0000000);
+    assert_eq!(t.token.balance(&liquidator), 0);
+    assert_eq!(t.token.total_supply(), 0);
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn dutch_auction_clears_frozen_cdp_without_pool_depth() {
+    let env = Env::default();
+    let t = setup(&env);
+    let borrower = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    fund_xlm(&t, &borrower, 1000_0000000);
+    t.token.open_cdp(&borrower, &300_0000000, &100_0000000, &None);
+    t.token.transfer(&borrower, &bidder, &100_0000000);
+
+    // Auctions only exist for frozen CDPs.
+    assert_eq!(
+        t.token
+            .try_start_auction(&bidder, &borrower)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::CDPNotFrozen
+    );
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_5000000, &999_400);
+    t.token.freeze_cdp(&borrower.clone(), &borrower);
+    // With no pool depth the frozen CDP cannot liquidate; the auction
+    // is the fallback path.
+    assert_eq!(
+        t.token
+            .try_liquidate_cdp(&bidder, &borrower)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::PoolEmpty
+    );
+    t.token.start_auction(&bidder, &borrower);
+    assert!(last_event_has_topic(&env, "auction"));
+    assert_eq!(
+        t.token
+            .try_start_auction(&bidder, &borrower)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::AuctionActive
+    );
+
+    // The full lot opens at 130% of the 100 RWA debt and halves midway
+    // through the decay window.
+    assert_eq!(t.token.auction_quote(&borrower), 130_0000000);
+    env.ledger().with_mut(|l| l.sequence_number += 8_640);
+    assert_eq!(t.token.auction_quote(&borrower), 65_0000000);
+
+    // A third of the lot costs a third of the ask, rounded up.
+    assert_eq!(t.token.bid(&bidder, &borrower, &100_0000000), 21_6666667);
+    assert_eq!(t.token.get_auction(&borrower).unwrap().debt, 78_3333333);
+    // Buying out the lot settles the auction; the unpaid remainder is
+    // written off and the CDP closes.
+    assert_eq!(t.token.bid(&bidder, &borrower, &200_0000000), 50_9166667);
+    assert!(last_event_has_topic(&env, "auc_done"));
+    assert_eq!(t.token.get_auction(&borrower), None);
+    let cdp = t.token.get_cdp(&borrower).unwrap();
+    assert_eq!(cdp.status, CDPStatus::Closed);
+    assert_eq!(cdp.asset_lent, 0);
+    assert_eq!(cdp.collateral_deposited, 0);
+
+    let xlm = soroban_sdk::token::TokenClient::new(&env, &t.xlm.address);
+    assert_eq!(xlm.balance(&bidder), 300_0000000);
+    assert_eq!(t.token.balance(&bidder), 27_4166666);
+    // Both bids were burned: the only supply left is the shortfall.
+    assert_eq!(t.token.total_supply(), 27_4166666);
+    assert_eq!(
+        t.token
+            .try_bid(&bidder, &borrower, &1_0000000)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::AuctionNotFound
+    );
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn stake_charges_fee_and_unstake_refunds() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+    t.token.stake(&a, &50_0000000, &None);
+    assert_eq!(t.token.fees_collected(), 7_0000000);
+    // The refund portion shows up as a reserved liability.
+    assert_eq!(t.token.fee_stats(), (7_0000000, 0, 0, 2_0000000));
+    t.token.unstake(&a);
+    // 2 XLM of the 7 XLM stake fee is returned on unstake.
+    assert_eq!(t.token.fees_collected(), 5_0000000);
+    assert_eq!(t.token.fee_stats(), (5_0000000, 0, 0, 0));
+    assert_eq!(t.token.balance(&a), 100_0000000);
+    assert_eq!(t.token.get_stake(&a), None);
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn min_stake_blocks_dust_positions() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+    t.token.set_min_stake(&10_0000000);
+    assert_eq!(t.token.min_stake(), 10_0000000);
+
+    assert_eq!(
+        t.token.try_stake(&a, &5_0000000, &None).err().unwrap().unwrap(),
+        Error::DepositTooSmall
+    );
+    t.token.stake(&a, &10_0000000, &None);
+
+    // A partial withdrawal may not leave a residual below the minimum,
+    // but may empty the deposit entirely.
+    assert_eq!(
+        t.token.try_withdraw(&a, &5_0000000).err().unwrap().unwrap(),
+        Error::DepositTooSmall
+    );
+    t.token.withdraw(&a, &10_0000000);
+    // A top-up onto the emptied deposit must clear the minimum again.
+    assert_eq!(
+        t.token.try_deposit(&a, &5_0000000).err().unwrap().unwrap(),
+        Error::DepositTooSmall
+    );
+    t.token.deposit(&a, &10_0000000);
+    assert_eq!(t.token.get_stake(&a).unwrap().deposited, 10_0000000);
+
+    assert_eq!(
+        t.token.try_set_min_stake(&-1).err().unwrap().unwrap(),
+        Error::InvalidConfiguration
+    );
+}
+
+#[test]
+fn min_debt_blocks_dust_cdps() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    t.token.set_min_debt(&10_0000000);
+    assert_eq!(t.token.min_debt(), 10_0000000);
+
+    assert_eq!(
+        t.token
+            .try_open_cdp(&a, &300_0000000, &5_0000000, &None)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::DebtBelowMinimum
+    );
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+
+    // A partial repayment may not leave the principal below the floor,
+    // but repaying to zero is always allowed.
+    assert_eq!(
+        t.token.try_repay_debt(&a, &95_0000000).err().unwrap().unwrap(),
+        Error::DebtBelowMinimum
+    );
+    t.token.repay_debt(&a, &90_0000000);
+    t.token.repay_debt(&a, &10_0000000);
+    assert_eq!(t.token.get_cdp(&a).unwrap().asset_lent, 0);
+
+    assert_eq!(
+        t.token.try_set_min_debt(&-1).err().unwrap().unwrap(),
+        Error::InvalidConfiguration
+    );
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn sweep_cannot_touch_refund_reserve() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+    t.token.stake(&a, &50_0000000, &None);
+
+    // Sweeping is impossible until a treasury is configured.
+    assert_eq!(
+        t.token.try_sweep_fees(&5_0000000).err().unwrap().unwrap(),
+        Error::InvalidConfiguration
+    );
+    t.token.set_treasury(&Some(treasury.clone()));
+    assert_eq!(t.token.treasury(), Some(treasury.clone()));
+
+    // 7 XLM of fees, 2 XLM of which is reserved for the refund.
+    assert_eq!(
+        t.token.try_sweep_fees(&6_0000000).err().unwrap().unwrap(),
+        Error::InsufficientBalance
+    );
+    t.token.sweep_fees(&5_0000000);
+    assert!(last_event_has_topic(&env, "fee_sweep"));
+    let xlm = soroban_sdk::token::TokenClient::new(&env, &t.xlm.address);
+    assert_eq!(xlm.balance(&treasury), 5_0000000);
+
+    // Interest lives in its own bucket with its own sweep.
+    env.ledger().with_mut(|l| l.timestamp += 31_536_000);
+    t.token.pay_interest(&a);
+    let collected = t.token.fee_stats().1;
+    assert_eq!(
+        t.token
+            .try_sweep_interest(&(collected + 1))
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::InsufficientBalance
+    );
+    t.token.sweep_interest(&collected);
+    assert_eq!(xlm.balance(&treasury), 5_0000000 + collected);
+    assert_eq!(t.token.fee_stats().1, 0);
+
+    // The refund is still payable after the sweeps drained free fees.
+    t.token.unstake(&a);
+    assert_eq!(t.token.fee_stats(), (0, 0, collected, 0));
+
+    // A stake fee below the reserved refund is rejected.
+    assert_eq!(
+        t.token.try_set_stake_fee(&1_0000000).err().unwrap().unwrap(),
+        Error::InvalidConfiguration
+    );
+}
+
+mod receipt_hook {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, symbol_short, Symbol};
+
+    #[contract]
+    pub struct MockReceipt;
+
+    #[contractimpl]
+    impl MockReceipt {
+        pub fn mint_rcpt(env: Env, lender: Address) {
+            let key = (symbol_short!("minted"), lender);
+            let n: u32 = env.storage().instance().get(&key).unwrap_or(0);
+            env.storage().instance().set(&key, &(n + 1));
+        }
+
+        pub fn burn_rcpt(env: Env, lender: Address) {
+            let key = (symbol_short!("burned"), lender);
+            let n: u32 = env.storage().instance().get(&key).unwrap_or(0);
+            env.storage().instance().set(&key, &(n + 1));
+        }
+
+        pub fn count(env: Env, kind: Symbol, lender: Address) -> u32 {
+            env.storage().instance().get(&(kind, lender)).unwrap_or(0)
+        }
+    }
+
+    /// A hook that always fails; open/close must still succeed.
+    #[contract]
+    pub struct BrokenReceipt;
+
+    #[contractimpl]
+    impl BrokenReceipt {
+        pub fn mint_rcpt(_env: Env, _lender: Address) {
+            panic!("receipt contract down");
+        }
+    }
+
+    #[test]
+    fn mints_and_burns_receipts_around_cdp_lifecycle() {
+        let env = Env::default();
+        let t = setup(&env);
+        let hook_id = env.register(MockReceipt, ());
+        let hook = MockReceiptClient::new(&env, &hook_id);
+        t.token.set_receipt_hook(&Some(hook_id.clone()));
+        assert_eq!(t.token.receipt_hook(), Some(hook_id));
+
+        let a = Address::generate(&env);
+        fund_xlm(&t, &a, 1000_0000000);
+        t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+        assert_eq!(hook.count(&symbol_short!("minted"), &a), 1);
+
+        t.token.repay_debt(&a, &100_0000000);
+        t.token.close_cdp(&a);
+        assert_eq!(hook.count(&symbol_short!("burned"), &a), 1);
+    }
+
+    #[test]
+    fn hook_failure_does_not_block_open() {
+        let env = Env::default();
+        let t = setup(&env);
+        let hook_id = env.register(BrokenReceipt, ());
+        t.token.set_receipt_hook(&Some(hook_id));
+
+        let a = Address::generate(&env);
+        fund_xlm(&t, &a, 1000_0000000);
+        t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+        assert_eq!(t.token.balance(&a), 100_0000000);
+    }
+}
+
+mod flash_mint {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, symbol_short, Bytes};
+
+    /// Repays honestly: holds the minted amount plus a pre-funded fee
+    /// balance, recording what the callback saw.
+    #[contract]
+    pub struct HonestBorrower;
+
+    #[contractimpl]
+    impl HonestBorrower {
+        pub fn on_flash_mint(env: Env, amount: i128, fee: i128, _data: Bytes) {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("seen"), &(amount, fee));
+        }
+
+        pub fn seen(env: Env) -> Option<(i128, i128)> {
+            env.storage().instance().get(&symbol_short!("seen"))
+        }
+    }
+
+    /// Never repays: the callback returns without holding the fee.
+    #[contract]
+    pub struct Deadbeat;
+
+    #[contractimpl]
+    impl Deadbeat {
+        pub fn on_flash_mint(_env: Env, _amount: i128, _fee: i128, _data: Bytes) {}
+    }
+
+    #[test]
+    fn flash_mint_burns_amount_plus_fee() {
+        let env = Env::default();
+        let t = setup(&env);
+        let a = Address::generate(&env);
+        fund_xlm(&t, &a, 1000_0000000);
+        t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+        t.token.set_flash_mint_fee(&10);
+        assert_eq!(t.token.flash_mint_fee(), 10);
+
+        let borrower_id = env.register(HonestBorrower, ());
+        let borrower = HonestBorrowerClient::new(&env, &borrower_id);
+        // Pre-fund the 10 bps fee (0.05 RWA); the minted amount covers the rest.
+        t.token.transfer(&a, &borrower_id, &50_0000);
+        assert_eq!(
+            t.token.flash_mint(&borrower_id, &50_0000000, &Bytes::new(&env)),
+            50_0000
+        );
+        assert_eq!(borrower.seen(), Some((50_0000000, 50_0000)));
+        // The transient mint is gone and the fee burned off the supply.
+        assert_eq!(t.token.balance(&borrower_id), 0);
+        assert_eq!(t.token.total_supply(), 100_0000000 - 50_0000);
+    }
+
+    #[test]
+    fn flash_mint_reverts_without_repayment() {
+        let env = Env::default();
+        let t = setup(&env);
+        t.token.set_flash_mint_fee(&10);
+        let deadbeat = env.register(Deadbeat, ());
+        assert_eq!(
+            t.token
+                .try_flash_mint(&deadbeat, &50_0000000, &Bytes::new(&env))
+                .err()
+                .unwrap()
+                .unwrap(),
+            Error::InsufficientBalance
+        );
+        assert_eq!(t.token.total_supply(), 0);
+    }
+}
+
+#[test]
+fn risk_param_setters_emit_consolidated_event() {
+    let env = Env::default();
+    let t = setup(&env);
+
+    t.token.set_interest_rate(&t.admin, &750);
+    // `events().all()` only surfaces the most recent invocation's events,
+    // so check before any further call.
+    assert!(last_event_has_topic(&env, "risk_params"));
+    assert_eq!(t.token.interest_rate(), 750);
+
+    t.token.set_min_collat_ratio(&t.admin, &16_000);
+    assert!(last_event_has_topic(&env, "risk_params"));
+    assert_eq!(t.token.min_collat_ratio(), 16_000);
+
+    let new_oracle = env.register(
+        RWAOracle,
+        (&t.admin, Asset::Other(symbol_short!("USD")), 9u32, 300u32),
+    );
+    t.token.set_oracle(&t.admin, &new_oracle);
+    assert!(last_event_has_topic(&env, "risk_params"));
+    // Swapping oracles adopts the replacement's decimals.
+    assert_eq!(t.token.expected_feed_decimals(), (9, 7));
+
+    // MCR below 100% is rejected and emits nothing.
+    assert_eq!(
+        t.token
+            .try_set_min_collat_ratio(&t.admin, &9_000)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::InvalidConfiguration
+    );
+}
+
+#[test]
+fn unauthorized_setter_fails() {
+    let env = Env::default();
+    let t = setup(&env);
+    // Strip auth mocking: setters must require the admin's signature.
+    t.env.set_auths(&[]);
+    assert!(t.token.try_set_interest_rate(&t.admin, &750).is_err());
+    let _ = t.admin;
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn pause_halts_operations_but_not_emergency_exit() {
+    let env = Env::default();
+    let t = setup(&env);
+    let borrower = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let pauser = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    fund_xlm(&t, &borrower, 1000_0000000);
+    fund_xlm(&t, &staker, 100_0000000);
+
+    t.token.open_cdp(&borrower, &300_0000000, &100_0000000, &None);
+    t.token.transfer(&borrower, &staker, &50_0000000);
+    t.token.stake(&staker, &50_0000000, &None);
+
+    // Only the admin or the configured pauser can flip the switch.
+    t.token.set_pauser(&Some(pauser.clone()));
+    assert_eq!(t.token.pauser(), Some(pauser.clone()));
+    assert_eq!(
+        t.token.try_pause(&outsider).err().unwrap().unwrap(),
+        Error::Unauthorized
+    );
+    t.token.pause(&pauser);
+    assert!(last_event_has_topic(&env, "paused"));
+    assert!(t.token.paused());
+
+    // Every state-changing surface is shut...
+    assert_eq!(
+        t.token
+            .try_transfer(&borrower, &staker, &1_0000000)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::ContractPaused
+    );
+    assert_eq!(
+        t.token
+            .try_open_cdp(&borrower, &30_0000000, &10_0000000, &None)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::ContractPaused
+    );
+    assert_eq!(
+        t.token.try_repay_debt(&borrower, &1_0000000).err().unwrap().unwrap(),
+        Error::ContractPaused
+    );
+    assert_eq!(
+        t.token.try_unstake(&staker).err().unwrap().unwrap(),
+        Error::ContractPaused
+    );
+
+    // ...except the emergency exit, so a pause cannot trap user funds.
+    t.token.set_emergency_mode(&true);
+    assert_eq!(t.token.emergency_withdraw(&staker), 50_0000000);
+
+    t.token.unpause(&t.admin);
+    assert!(last_event_has_topic(&env, "unpaused"));
+    assert!(!t.token.paused());
+    t.token.transfer(&borrower, &staker, &1_0000000);
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn cleanup_reclaims_expired_allowances() {
+    let env = Env::default();
+    let t = setup(&env);
+    let owner = Address::generate(&env);
+    let s1 = Address::generate(&env);
+    let s2 = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    fund_xlm(&t, &owner, 1000_0000000);
+    env.ledger().with_mut(|l| l.sequence_number = 100);
+    t.token.open_cdp(&owner, &300_0000000, &100_0000000, &None);
+    // The stake fee funds the treasury so cleanup bounties are payable.
+    t.token.stake(&owner, &50_0000000, &None);
+    t.token.set_keeper_bounty(&2_0000000);
+
+    let has = |spender: &Address| {
+        env.as_contract(&t.token.address, || {
+            env.storage().persistent().has(&crate::storage::DataKey::Allowance(
+                owner.clone(),
+                spender.clone(),
+            ))
+        })
+    };
+
+    // Revoking an approval deletes the slot rather than storing a zero.
+    t.token.approve(&owner, &s1, &10_0000000, &150);
+    assert!(has(&s1));
+    t.token.approve(&owner, &s1, &0, &150);
+    assert!(!has(&s1));
+
+    // An expired entry still occupies storage but reads as zero.
+    t.token.approve(&owner, &s1, &10_0000000, &150);
+    t.token.approve(&owner, &s2, &10_0000000, &10_000);
+    env.ledger().with_mut(|l| l.sequence_number = 200);
+    assert_eq!(t.token.allowance(&owner, &s1), 0);
+    assert!(has(&s1));
+
+    // The sweep reclaims only the expired slot and pays the bounty.
+    let xlm = soroban_sdk::token::TokenClient::new(&env, &t.xlm.address);
+    let pairs = soroban_sdk::vec![
+        &env,
+        (owner.clone(), s1.clone()),
+        (owner.clone(), s2.clone()),
+    ];
+    assert_eq!(t.token.cleanup_expired(&keeper, &pairs), 1);
+    assert!(!has(&s1));
+    assert!(has(&s2));
+    assert_eq!(t.token.allowance(&owner, &s2), 10_0000000);
+    assert_eq!(xlm.balance(&keeper), 2_0000000);
+
+    // A sweep that reclaims nothing earns nothing.
+    assert_eq!(t.token.cleanup_expired(&keeper, &pairs), 0);
+    assert_eq!(xlm.balance(&keeper), 2_0000000);
+
+    // Spending an allowance down to zero frees its slot too.
+    t.token.transfer_from(&s2, &owner, &s2, &10_0000000);
+    assert!(!has(&s2));
+}
+
+#[test]
+fn rate_changes_price_only_forward() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    fund_xlm(&t, &b, 1000_0000000);
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+    assert_eq!(t.token.borrow_index(), 1_000_000_000_000_000_000);
+
+    env.ledger().with_mut(|l| l.timestamp += 31_536_000 / 2);
+    let owed_before = t.token.get_projected_interest(&a);
+    assert_eq!(owed_before, 2_5315121);
+
+    // Doubling the rate reprices nothing that already elapsed.
+    t.token.set_interest_rate(&t.admin, &1000);
+    assert_eq!(t.token.get_projected_interest(&a), owed_before);
+
+    // The second half-year accrues at 10%, so the total lands on
+    // 100 * (e^0.025 * e^0.05 - 1), not the retroactive e^0.10. A CDP
+    // opened at the change snapshots the index then and owes only
+    // 100 * (e^0.05 - 1).
+    t.token.open_cdp(&b, &400_0000000, &100_0000000, &None);
+    env.ledger().with_mut(|l| l.timestamp += 31_536_000 / 2);
+    assert_eq!(t.token.get_projected_interest(&a), 7_7884151);
+    assert_eq!(t.token.get_projected_interest(&b), 5_1271097);
+}
+
+#[test]
+fn origination_fee_charges_upfront_on_minted_debt() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    // 50 bps of minted RWA, collected in XLM at face value.
+    t.token.set_origination_fee(&50);
+    assert_eq!(t.token.origination_fee(), 50);
+    assert!(t.token.try_set_origination_fee(&10_001).is_err());
+
+    // 100 RWA minted owes 0.5 RWA of fee = 1 XLM at 2 XLM per RWA, on
+    // top of the 400 XLM locked as collateral.
+    let xlm = soroban_sdk::token::TokenClient::new(&env, &t.xlm.address);
+    t.token.open_cdp(&a, &400_0000000, &100_0000000, &None);
+    assert!(any_event_has_topic(&env, "revenue"));
+    assert_eq!(xlm.balance(&a), 1000_0000000 - 400_0000000 - 1_0000000);
+    assert_eq!(t.token.revenue_breakdown().origination_fees, 1_0000000);
+    assert_eq!(t.token.fee_stats().0, 1_0000000);
+
+    // Borrowing against existing collateral pays the same rate.
+    t.token.borrow_rwa(&a, &10_0000000, &None);
+    assert_eq!(t.token.revenue_breakdown().origination_fees, 1_1000000);
+
+    // Repayment is free; only freshly minted debt is charged.
+    let fees_before = t.token.fee_stats().0;
+    t.token.repay_debt(&a, &110_0000000);
+    assert_eq!(t.token.fee_stats().0, fees_before);
+}
+
+#[test]
+fn redemption_fee_spikes_with_volume_and_decays() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    let r = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+    t.token.transfer(&a, &r, &10_0000000);
+    t.token.set_redemption_fee(&50, &86_400);
+    assert_eq!(t.token.redemption_fee(), (50, 86_400));
+    assert_eq!(t.token.redemption_rate(), 50);
+
+    // 10 RWA redeems 20 XLM gross; the 50 bps floor withholds 0.1 XLM.
+    let xlm = soroban_sdk::token::TokenClient::new(&env, &t.xlm.address);
+    assert_eq!(t.token.redeem(&r, &10_0000000), 19_9000000);
+    assert_eq!(xlm.balance(&r), 19_9000000);
+    assert_eq!(t.token.revenue_breakdown().redemption_fees, 1000000);
+
+    // Redeeming 10% of the supply spiked the base rate by 1_000 bps on
+    // top of the floor, and the spike bleeds off linearly over the day.
+    assert_eq!(t.token.redemption_rate(), 1_050);
+    env.ledger().with_mut(|l| l.timestamp += 43_200);
+    assert_eq!(t.token.redemption_rate(), 550);
+    env.ledger().with_mut(|l| l.timestamp += 43_200);
+    assert_eq!(t.token.redemption_rate(), 50);
+
+    // Switching decay off drops the spike; only the floor remains.
+    t.token.set_redemption_fee(&50, &0);
+    assert_eq!(t.token.redemption_rate(), 50);
+}
+
+#[test]
+fn operator_defends_position_without_withdrawal_power() {
+    let env = Env::default();
+    let t = setup(&env);
+    let lender = Address::generate(&env);
+    let bot = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    fund_xlm(&t, &lender, 1000_0000000);
+    fund_xlm(&t, &bot, 100_0000000);
+    t.token.open_cdp(&lender, &300_0000000, &100_0000000, &None);
+    t.token.transfer(&lender, &bot, &10_0000000);
+
+    // Nobody may burn or spend for the lender until a delegation exists.
+    assert_eq!(t.token.operator(&lender), None);
+    assert_eq!(
+        t.token
+            .try_repay_debt_for(&bot, &lender, &10_0000000)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::Unauthorized
+    );
+
+    t.token.set_operator(&lender, &Some(bot.clone()));
+    assert_eq!(t.token.operator(&lender), Some(bot.clone()));
+    // Still only the named operator — not just anyone.
+    assert_eq!(
+        t.token
+            .try_repay_debt_for(&stranger, &lender, &10_0000000)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::Unauthorized
+    );
+
+    // The bot funds every call from its own balances: XLM for top-ups
+    // and interest, its own RWA for repayments.
+    t.token.add_collateral_for(&bot, &lender, &5_0000000);
+    t.token.repay_debt_for(&bot, &lender, &10_0000000);
+    env.ledger().with_mut(|l| l.timestamp += 86_400);
+    t.token.pay_interest_for(&bot, &lender);
+    let cdp = t.token.get_cdp(&lender).unwrap();
+    assert_eq!(cdp.collateral_deposited, 305_0000000);
+    assert_eq!(cdp.asset_lent, 90_0000000);
+    assert_eq!(cdp.accrued_interest, 0);
+    assert_eq!(t.token.balance(&bot), 0);
+
+    // Revocation cuts the bot off from repayments again.
+    t.token.set_operator(&lender, &None);
+    assert_eq!(t.token.operator(&lender), None);
+    assert_eq!(
+        t.token
+            .try_repay_debt_for(&bot, &lender, &10_0000000)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::Unauthorized
+    );
+}
+
+#[test]
+fn anyone_can_settle_someone_elses_interest() {
+    let env = Env::default();
+    let t = setup(&env);
+    let lender = Address::generate(&env);
+    let custodian = Address::generate(&env);
+    fund_xlm(&t, &lender, 1000_0000000);
+    fund_xlm(&t, &custodian, 100_0000000);
+    t.token.open_cdp(&lender, &300_0000000, &100_0000000, &None);
+    env.ledger().with_mut(|l| l.timestamp += 86_400);
+
+    // No delegation, no lender auth — the custodian just pays, out of
+    // its own collateral balance.
+    t.token.pay_interest_for(&custodian, &lender);
+    let cdp = t.token.get_cdp(&lender).unwrap();
+    assert_eq!(cdp.accrued_interest, 0);
+    assert!(t.xlm.balance(&custodian) < 100_0000000);
+    assert_eq!(t.xlm.balance(&lender), 700_0000000);
+}
+
+#[test]
+fn anyone_can_top_up_someone_elses_collateral() {
+    let env = Env::default();
+    let t = setup(&env);
+    let lender = Address::generate(&env);
+    let rescuer = Address::generate(&env);
+    fund_xlm(&t, &lender, 300_0000000);
+    fund_xlm(&t, &rescuer, 50_0000000);
+    t.token.open_cdp(&lender, &300_0000000, &100_0000000, &None);
+
+    // No delegation needed: the deposit comes out of the rescuer's own
+    // balance and only makes the position safer.
+    t.token.add_collateral_for(&rescuer, &lender, &50_0000000);
+    let cdp = t.token.get_cdp(&lender).unwrap();
+    assert_eq!(cdp.collateral_deposited, 350_0000000);
+    let xlm = soroban_sdk::token::TokenClient::new(&env, &t.xlm.address);
+    assert_eq!(xlm.balance(&rescuer), 0);
+}
+
+#[test]
+fn repay_with_collateral_deleverages_without_external_rwa() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+
+    // 40 RWA of principal costs 80 XLM of the CDP's own collateral at
+    // the 2:1 oracle price; no RWA leaves the lender's wallet.
+    t.token.repay_with_collateral(&a, &40_0000000);
+    assert!(last_event_has_topic(&env, "deleveraged"));
+    let cdp = t.token.get_cdp(&a).unwrap();
+    assert_eq!(cdp.collateral_deposited, 220_0000000);
+    assert_eq!(cdp.asset_lent, 60_0000000);
+    assert_eq!(t.token.balance(&a), 100_0000000);
+    assert_eq!(t.token.redemption_buffer(), 80_0000000);
+    // Shrinking both sides at face value improved the ratio: 150% to
+    // 220 / 120 ~ 183%.
+    assert_eq!(t.token.get_collateralization_ratio(&a), 18_333);
+
+    // Cannot sell down below the outstanding principal.
+    assert_eq!(
+        t.token
+            .try_repay_with_collateral(&a, &70_0000000)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::InvalidAmount
+    );
+
+    // The buffer keeps the surrendered collateral redeemable. Clear the
+    // position, then redeem against an empty registry: the 40 unbacked
+    // RWA pull their 80 XLM straight from the buffer.
+    t.token.repay_debt(&a, &60_0000000);
+    t.token.close_cdp(&a);
+    assert_eq!(t.token.redeem(&a, &40_0000000), 80_0000000);
+    assert_eq!(t.token.redemption_buffer(), 0);
+    assert_eq!(t.token.total_supply(), 0);
+    let xlm = soroban_sdk::token::TokenClient::new(&env, &t.xlm.address);
+    assert_eq!(xlm.balance(&a), 1000_0000000);
+}
+
+#[test]
+fn cdp_index_pages_in_opening_order() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let c = Address::generate(&env);
+    for who in [&a, &b, &c] {
+        fund_xlm(&t, who, 300_0000000);
+        t.token.open_cdp(who, &300_0000000, &100_0000000, &None);
+    }
+    assert_eq!(t.token.cdp_count(), 3);
+    assert_eq!(
+        t.token.list_cdps(&0, &2),
+        soroban_sdk::vec![&env, a.clone(), b.clone()]
+    );
+    // Pages clamp at the end of the index instead of trapping.
+    assert_eq!(t.token.list_cdps(&2, &10), soroban_sdk::vec![&env, c]);
+    assert_eq!(t.token.list_cdps(&3, &10), soroban_sdk::vec![&env]);
+
+    // Closing does not unregister: the index is append-only and the
+    // entry keeps serving status lookups.
+    t.token.repay_debt(&a, &100_0000000);
+    t.token.close_cdp(&a);
+    assert_eq!(t.token.cdp_count(), 3);
+    assert_eq!(t.token.get_cdp(&a).unwrap().status, CDPStatus::Closed);
+}
+
+#[test]
+fn status_queries_surface_freeze_candidates() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let c = Address::generate(&env);
+    for (who, collateral) in [(&a, 300_0000000i128), (&b, 400_0000000), (&c, 330_0000000)] {
+        fund_xlm(&t, who, collateral);
+        t.token.open_cdp(who, &collateral, &100_0000000, &None);
+    }
+
+    // RWA at 2.20 XLM pushes a to 136% — below the 150% MCR — while c
+    // lands exactly on it, which freeze_cdp would still reject.
+    t.oracle.set_asset_price(
+        &t.admin,
+        &Asset::Other(symbol_short!("TBOND")),
+        &2_2000000,
+        &999_500,
+    );
+    assert_eq!(
+        t.token.scan_insolvent(&0, &10),
+        soroban_sdk::vec![&env, a.clone()]
+    );
+
+    t.token.freeze_cdp(&b, &a);
+    assert_eq!(t.token.scan_insolvent(&0, &10), soroban_sdk::vec![&env]);
+    assert_eq!(
+        t.token.list_cdps_by_status(&CDPStatus::Frozen, &0, &10),
+        soroban_sdk::vec![&env, a]
+    );
+    assert_eq!(
+        t.token.list_cdps_by_status(&CDPStatus::Open, &0, &10),
+        soroban_sdk::vec![&env, b, c]
+    );
+}
+
+#[test]
+fn health_factor_reports_contract_math() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 300_0000000);
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+
+    // Opened exactly at the 150% MCR: the factor sits on the boundary.
+    assert_eq!(t.token.health_factor(&a), 1_000000000);
+    let report = t.token.health_report(&a);
+    assert_eq!(report.ratio, 15_000);
+    assert_eq!(report.min_ratio, 15_000);
+    assert_eq!(report.collateral, 300_0000000);
+    assert_eq!(report.debt, 100_0000000);
+    assert_eq!(report.accrued_interest, 0);
+    assert_eq!(report.rwa_price, 2_0000000);
+    assert_eq!(report.collateral_price, 1_0000000);
+    assert_eq!(report.rwa_decimals, 7);
+    assert_eq!(report.collateral_decimals, 7);
+
+    // A year of interest grows the debt the factor is measured on;
+    // floor rounding keeps the report from flattering the position.
+    env.ledger().with_mut(|l| l.timestamp += 31_536_000);
+    let report = t.token.health_report(&a);
+    assert_eq!(report.debt, 100_0000000 + report.accrued_interest);
+    assert!(report.accrued_interest > 5_0000000);
+    assert!(t.token.health_factor(&a) < 1_000000000);
+    assert_eq!(
+        report.factor,
+        (report.ratio as i128) * 1_000000000 / 15_000
+    );
+}
+
+#[test]
+fn borrowing_headroom_views_match_entrypoint_checks() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 400_0000000);
+
+    // 100 RWA at 2 XLM and a 150% MCR needs exactly 300 XLM.
+    assert_eq!(t.token.required_collateral(&100_0000000), 300_0000000);
+    t.token.open_cdp(&a, &400_0000000, &100_0000000, &None);
+
+    // 400 XLM supports 133.33 RWA of debt; the view floors so the
+    // quoted headroom is actually mintable, and one stroop more trips
+    // the MCR check.
+    assert_eq!(t.token.max_borrowable(&a), 33_3333333);
+    assert_eq!(
+        t.token
+            .try_borrow_rwa(&a, &33_3333334, &None)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::CollateralRatioBelowMinimum
+    );
+    t.token.borrow_rwa(&a, &33_3333333, &None);
+    assert_eq!(t.token.max_borrowable(&a), 0);
+
+    // The debt ceiling caps the quote below what collateral supports.
+    t.token.repay_debt(&a, &33_3333333);
+    t.token.set_debt_ceiling(&110_0000000);
+    assert_eq!(t.token.max_borrowable(&a), 10_0000000);
+}
+
+#[test]
+fn liquidation_price_marks_the_freeze_boundary() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 400_0000000);
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+
+    // Opened exactly at the MCR, so the boundary is the current price;
+    // topping up 100 XLM moves it down to 0.75.
+    assert_eq!(t.token.liquidation_price(&a), 1_0000000);
+    t.token.add_collateral(&a, &100_0000000);
+    assert_eq!(t.token.liquidation_price(&a), 7500000);
+
+    // One stroop under the quote is freezable, the quote itself is not.
+    t.oracle.set_asset_price(
+        &t.admin,
+        &Asset::Other(symbol_short!("XLM")),
+        &7500000,
+        &999_500,
+    );
+    assert_eq!(t.token.get_collateralization_ratio(&a), 15_000);
+    t.oracle.set_asset_price(
+        &t.admin,
+        &Asset::Other(symbol_short!("XLM")),
+        &7499999,
+        &999_900,
+    );
+    assert_eq!(
+        t.token.try_freeze_cdp(&a, &a).err(),
+        None,
+        "one stroop below the quoted price must be freezable"
+    );
+}
+
+#[test]
+fn previews_predict_entrypoint_outcomes() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 401_0000000);
+    t.token.set_origination_fee(&50);
+
+    // Too little collateral previews as the exact MCR failure; enough
+    // previews clean and quotes the upfront fee (0.5 RWA = 1 XLM).
+    let p = t.token.preview_open_cdp(&a, &299_0000000, &100_0000000);
+    assert!(!p.ok);
+    assert_eq!(p.error_code, Error::CollateralRatioBelowMinimum as u32);
+    assert_eq!(p.ratio, 14_950);
+    let p = t.token.preview_open_cdp(&a, &400_0000000, &100_0000000);
+    assert!(p.ok);
+    assert_eq!(p.ratio, 20_000);
+    assert_eq!(p.fee, 1_0000000);
+
+    t.token.open_cdp(&a, &400_0000000, &100_0000000, &None);
+
+    // Headroom is 33.33 RWA; previewing past it fails like borrow_rwa
+    // would, and withdrawing everything overdraws the position.
+    let p = t.token.preview_borrow(&a, &40_0000000);
+    assert!(!p.ok);
+    assert_eq!(p.error_code, Error::CollateralRatioBelowMinimum as u32);
+    let p = t.token.preview_withdraw_collateral(&a, &500_0000000);
+    assert!(!p.ok);
+    assert_eq!(p.error_code, Error::InvalidAmount as u32);
+    let p = t.token.preview_withdraw_collateral(&a, &100_0000000);
+    assert!(p.ok);
+    assert_eq!(p.ratio, 15_000);
+
+    // Repaying 40 RWA leaves a 60-debt position at 333%; repaying more
+    // RWA than the wallet holds is caught before signing.
+    let p = t.token.preview_repay(&a, &40_0000000);
+    assert!(p.ok);
+    assert_eq!(p.ratio, 33_333);
+    t.token.transfer(&a, &t.admin, &80_0000000);
+    let p = t.token.preview_repay(&a, &40_0000000);
+    assert!(!p.ok);
+    assert_eq!(p.error_code, Error::InsufficientBalance as u32);
+}
+
+#[cfg(feature = "compliance")]
+#[test]
+fn canonical_sep41_events_fire_alongside_custom_ones() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    fund_xlm(&t, &a, 300_0000000);
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+
+    t.token.transfer(&a, &b, &10_0000000);
+    assert!(any_event_has_topic(&env, "transfer"));
+
+    t.token.approve(&b, &a, &5_0000000, &(env.ledger().sequence() + 100));
+    assert!(any_event_has_topic(&env, "approve"));
+
+    t.token.burn(&b, &10_0000000);
+    assert!(any_event_has_topic(&env, "burn"));
+    assert!(any_event_has_topic(&env, "burned"));
+
+    t.token.set_authorized(&b, &false);
+    assert!(any_event_has_topic(&env, "set_authorized"));
+}
+
+#[cfg(feature = "compliance")]
+#[test]
+fn regulated_transfers_need_sep8_approval_above_threshold() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let server = Address::generate(&env);
+    fund_xlm(&t, &a, 300_0000000);
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+
+    // The oracle marks TBOND as regulated and the issuer arms the gate
+    // at 10 RWA with a dedicated approval server.
+    t.oracle.set_rwa_metadata(
+        &t.admin,
+        &oracle::RWAMetadata {
+            asset: Asset::Other(symbol_short!("TBOND")),
+            name: String::from_str(&env, "Tokenized T-Bond"),
+            asset_type: oracle::AssetType::Bond,
+            coupon_rate_bps: 0,
+            coupon_frequency: 0,
+            maturity: 0,
+            issuer: String::from_str(&env, "US Treasury"),
+            custodian: String::from_str(&env, "Example Custody LLC"),
+            regulatory_info: oracle::RegulatoryInfo {
+                jurisdiction: String::from_str(&env, "US"),
+                license_id: String::from_str(&env, "SEC-123"),
+                license_expiry: None,
+                is_regulated: true,
+                compliance_status: oracle::ComplianceStatus::Approved,
+            },
+            metadata: soroban_sdk::Vec::new(&env),
+        },
+    );
+    t.token.set_regulated_threshold(&10_0000000);
+
+    // At or under the threshold flows freely; above it is blocked until
+    // the approval server records the exact lane.
+    t.token.transfer(&a, &b, &10_0000000);
+    assert_eq!(
+        t.token.try_transfer(&a, &b, &50_0000000).err().unwrap().unwrap(),
+        Error::TransferNotApproved
+    );
+    t.token.set_approval_server(&Some(server.clone()));
+    let tx_id = BytesN::from_array(&env, &[7u8; 32]);
+    t.token.approve_transfer(&server, &tx_id, &a, &b, &50_0000000);
+    assert!(last_event_has_topic(&env, "tx_approved"));
+    t.token.transfer(&a, &b, &50_0000000);
+
+    // The approval was one-shot; a stranger cannot record new ones.
+    assert_eq!(
+        t.token.try_transfer(&a, &b, &20_0000000).err().unwrap().unwrap(),
+        Error::TransferNotApproved
+    );
+    assert_eq!(
+        t.token
+            .try_approve_transfer(&b, &tx_id, &a, &b, &20_0000000)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::Unauthorized
+    );
+}
+
+mod compliance_hook {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, symbol_short};
+
+    /// Allows everything except movements to an address put on its
+    /// deny list.
+    #[contract]
+    pub struct DenyList;
+
+    #[contractimpl]
+    impl DenyList {
+        pub fn block(env: Env, id: Address) {
+            env.storage().instance().set(&(symbol_short!("deny"), id), &true);
+        }
+
+        pub fn check_transfer(env: Env, _from: Address, to: Address, _amount: i128) -> bool {
+            !env.storage()
+                .instance()
+                .get(&(symbol_short!("deny"), to))
+                .unwrap_or(false)
+        }
+    }
+
+    #[cfg(feature = "compliance")]
+    #[test]
+    fn hook_vetoes_transfers_and_mints() {
+        let env = Env::default();
+        let t = setup(&env);
+        let hook_id = env.register(DenyList, ());
+        let hook = DenyListClient::new(&env, &hook_id);
+        t.token.set_compliance_contract(&Some(hook_id.clone()));
+        assert_eq!(t.token.compliance_contract(), Some(hook_id));
+
+        let a = Address::generate(&env);
+        let b = Address::generate(&env);
+        fund_xlm(&t, &a, 1000_0000000);
+        t.token.open_cdp(&a, &400_0000000, &100_0000000, &None);
+        t.token.transfer(&a, &b, &10_0000000);
+
+        // Movements to a denied address abort, including fresh mints.
+        hook.block(&b);
+        assert_eq!(
+            t.token.try_transfer(&a, &b, &10_0000000).err().unwrap().unwrap(),
+            Error::TransferRejected
+        );
+        hook.block(&a);
+        assert_eq!(
+            t.token.try_borrow_rwa(&a, &10_0000000, &None).err().unwrap().unwrap(),
+            Error::TransferRejected
+        );
+
+        // Unplugging the hook restores free movement.
+        t.token.set_compliance_contract(&None);
+        t.token.transfer(&a, &b, &10_0000000);
+    }
+}
+
+mod kyc {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, symbol_short};
+
+    #[contract]
+    pub struct MockRegistry;
+
+    #[contractimpl]
+    impl MockRegistry {
+        pub fn attest(env: Env, id: Address) {
+            env.storage().instance().set(&(symbol_short!("kyc"), id), &true);
+        }
+
+        pub fn is_attested(env: Env, id: Address) -> bool {
+            env.storage()
+                .instance()
+                .get(&(symbol_short!("kyc"), id))
+                .unwrap_or(false)
+        }
+    }
+
+    #[cfg(all(feature = "stability-pool", feature = "compliance"))]
+    #[test]
+    fn minting_exposure_requires_attestation() {
+        let env = Env::default();
+        let t = setup(&env);
+        let registry_id = env.register(MockRegistry, ());
+        let registry = MockRegistryClient::new(&env, &registry_id);
+        t.token.set_kyc_registry(&Some(registry_id.clone()));
+        assert_eq!(t.token.kyc_registry(), Some(registry_id));
+
+        let a = Address::generate(&env);
+        let s = Address::generate(&env);
+        fund_xlm(&t, &a, 1000_0000000);
+        fund_xlm(&t, &s, 100_0000000);
+        assert_eq!(
+            t.token
+                .try_open_cdp(&a, &400_0000000, &100_0000000, &None)
+                .err()
+                .unwrap()
+                .unwrap(),
+            Error::KycRequired
+        );
+        registry.attest(&a);
+        t.token.open_cdp(&a, &400_0000000, &100_0000000, &None);
+        t.token.borrow_rwa(&a, &10_0000000, &None);
+
+        // Staking mints exposure to liquidation proceeds, so it is
+        // gated the same way; holding or receiving the token is not.
+        t.token.transfer(&a, &s, &50_0000000);
+        assert_eq!(
+            t.token.try_stake(&s, &50_0000000, &None).err().unwrap().unwrap(),
+            Error::KycRequired
+        );
+        registry.attest(&s);
+        t.token.stake(&s, &50_0000000, &None);
+    }
+}
+
+#[cfg(feature = "compliance")]
+#[test]
+fn frozen_accounts_are_fully_locked_until_unfrozen() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    fund_xlm(&t, &a, 500_0000000);
+    fund_xlm(&t, &b, 320_0000000);
+    t.token.open_cdp(&a, &400_0000000, &100_0000000, &None);
+    t.token.open_cdp(&b, &300_0000000, &50_0000000, &None);
+
+    t.token.freeze_account(&a, &42);
+    assert!(last_event_has_topic(&env, "acct_frozen"));
+    assert!(t.token.frozen(&a));
+    // A freeze is its own flag; the authorization flag is untouched.
+    assert!(t.token.authorized(&a));
+
+    // Nothing moves in either direction while the freeze stands.
+    assert_eq!(
+        t.token.try_transfer(&a, &b, &10_0000000).err().unwrap().unwrap(),
+        Error::AccountFrozen
+    );
+    assert_eq!(
+        t.token.try_transfer(&b, &a, &10_0000000).err().unwrap().unwrap(),
+        Error::AccountFrozen
+    );
+    // The CDP is locked too, even against a third party's top-up.
+    assert_eq!(
+        t.token
+            .try_add_collateral_for(&b, &a, &10_0000000)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::AccountFrozen
+    );
+    assert_eq!(
+        t.token
+            .try_withdraw_collateral(&a, &10_0000000, &None)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::AccountFrozen
+    );
+    assert_eq!(
+        t.token.try_repay_debt(&a, &10_0000000).err().unwrap().unwrap(),
+        Error::AccountFrozen
+    );
+
+    t.token.unfreeze_account(&a);
+    assert!(last_event_has_topic(&env, "acct_unfrozen"));
+    assert!(!t.token.frozen(&a));
+    t.token.transfer(&a, &b, &10_0000000);
+    t.token.withdraw_collateral(&a, &10_0000000, &None);
+}
+
+#[test]
+fn muxed_destinations_resolve_to_the_underlying_account() {
+    use soroban_sdk::testutils::MuxedAddress as _;
+    use soroban_sdk::MuxedAddress;
+
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 400_0000000);
+    t.token.open_cdp(&a, &400_0000000, &100_0000000, &None);
+
+    // An exchange deposit address: the balance is keyed by the
+    // underlying account, the mux id is only routing information.
+    let deposit = MuxedAddress::generate(&env);
+    let exchange = deposit.address();
+    t.token.transfer(&a, &deposit, &30_0000000);
+    assert_eq!(t.token.balance(&exchange), 30_0000000);
+
+    t.token.approve(&a, &exchange, &30_0000000, &(env.ledger().sequence() + 100));
+    let sub = MuxedAddress::new(exchange.clone(), 7);
+    t.token.transfer_from(&exchange, &a, &sub, &10_0000000);
+    assert_eq!(t.token.balance(&exchange), 40_0000000);
+    assert_eq!(t.token.balance(&a), 60_0000000);
+
+    // CDP payouts take the same shape: the underlying account owns the
+    // position and a plain Address still converts at the call site.
+    t.token.withdraw_collateral(&a, &50_0000000, &None);
+    assert_eq!(t.xlm.balance(&a), 50_0000000);
+}
+
+#[test]
+fn granular_roles_scope_the_admin_surface() {
+    use crate::Role;
+
+    let env = Env::default();
+    let t = setup(&env);
+    let desk = Address::generate(&env);
+    let ops = Address::generate(&env);
+
+    // Before any grant, a hot key can tune nothing.
+    assert_eq!(
+        t.token.try_set_interest_rate(&desk, &750).err().unwrap().unwrap(),
+        Error::Unauthorized
+    );
+
+    t.token.grant_role(&Role::RateSetter, &desk);
+    assert!(last_event_has_topic(&env, "role_granted"));
+    assert!(t.token.has_role(&Role::RateSetter, &desk));
+    t.token.set_interest_rate(&desk, &750);
+    assert_eq!(t.token.interest_rate(), 750);
+
+    // The rate desk still cannot move risk parameters or the code.
+    assert_eq!(
+        t.token
+            .try_set_min_collat_ratio(&desk, &16_000)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::Unauthorized
+    );
+    assert_eq!(
+        t.token
+            .try_upgrade(&desk, &BytesN::from_array(&env, &[9u8; 32]))
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::Unauthorized
+    );
+
+    // A risk manager moves the MCR and oracle wiring but not the rate.
+    t.token.grant_role(&Role::RiskManager, &ops);
+    t.token.set_min_collat_ratio(&ops, &16_000);
+    assert_eq!(t.token.min_collat_ratio(), 16_000);
+    assert_eq!(
+        t.token.try_set_interest_rate(&ops, &500).err().unwrap().unwrap(),
+        Error::Unauthorized
+    );
+
+    // The pauser role halts the contract without any single configured
+    // pauser address.
+    t.token.grant_role(&Role::Pauser, &ops);
+    t.token.pause(&ops);
+    assert!(t.token.paused());
+    t.token.unpause(&ops);
+
+    // Revocation bites immediately, and the admin key keeps working.
+    t.token.revoke_role(&Role::RateSetter, &desk);
+    assert!(last_event_has_topic(&env, "role_revoked"));
+    assert_eq!(
+        t.token.try_set_interest_rate(&desk, &500).err().unwrap().unwrap(),
+        Error::Unauthorized
+    );
+    t.token.set_interest_rate(&t.admin, &500);
+}
+
+#[test]
+fn migrate_converts_preversioned_storage_and_is_idempotent() {
+    let env = Env::default();
+    let t = setup(&env);
+    // Fresh deployments are stamped by the constructor.
+    assert_eq!(t.token.schema_version(), crate::storage::SCHEMA_VERSION);
+
+    // A deployment from before the version key existed reads as v1.
+    env.as_contract(&t.token.address, || {
+        env.storage()
+            .instance()
+            .remove(&crate::storage::DataKey::SchemaVersion);
+    });
+    assert_eq!(t.token.schema_version(), 1);
+
+    assert_eq!(t.token.migrate(&t.admin), crate::storage::SCHEMA_VERSION);
+    assert!(last_event_has_topic(&env, "migrated"));
+    assert_eq!(t.token.schema_version(), crate::storage::SCHEMA_VERSION);
+
+    // Running again is a no-op, so pipelines need not check first.
+    assert_eq!(t.token.migrate(&t.admin), crate::storage::SCHEMA_VERSION);
+
+    // Gated like `upgrade`: a random key cannot run migrations.
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        t.token.try_migrate(&stranger).err().unwrap().unwrap(),
+        Error::Unauthorized
+    );
+}
+
+#[test]
+fn interest_lifecycle_emits_accrual_and_payment_events() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 500_0000000);
+    t.token.open_cdp(&a, &400_0000000, &100_0000000, &None);
+
+    // Half a year of 5% APR; settling charges the collateral and both
+    // legs show up in one invocation's events.
+    env.ledger().with_mut(|l| l.timestamp += 15_768_000);
+    t.token.pay_interest(&a);
+    assert!(any_event_has_topic(&env, "int_accrued"));
+    assert!(any_event_has_topic(&env, "int_paid"));
+    assert_eq!(t.token.get_projected_interest(&a), 0);
+
+    // With nothing elapsed there is nothing to accrue or settle, so a
+    // repeat call stays silent.
+    t.token.pay_interest(&a);
+    assert!(!any_event_has_topic(&env, "int_accrued"));
+    assert!(!any_event_has_topic(&env, "int_paid"));
+
+    // Repayment accrues (and logs) but settles principal, not interest.
+    env.ledger().with_mut(|l| l.timestamp += 15_768_000);
+    t.token.repay_debt(&a, &10_0000000);
+    assert!(any_event_has_topic(&env, "int_accrued"));
+    assert!(!any_event_has_topic(&env, "int_paid"));
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn fee_flows_are_evented_for_attribution() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    fund_xlm(&t, &a, 500_0000000);
+    fund_xlm(&t, &b, 20_0000000);
+
+    // Origination: the fee leaves the lender and is attributed inline.
+    t.token.set_origination_fee(&50);
+    t.token.open_cdp(&a, &400_0000000, &100_0000000, &None);
+    assert!(any_event_has_topic(&env, "fee_in"));
+
+    // Staking collects the flat fee; unstaking refunds the reserved
+    // portion and publishes the outflow.
+    t.token.transfer(&a, &b, &50_0000000);
+    t.token.stake(&b, &50_0000000, &None);
+    assert!(any_event_has_topic(&env, "fee_in"));
+    t.token.unstake(&b);
+    assert!(any_event_has_topic(&env, "fee_out"));
+    assert_eq!(t.xlm.balance(&b), 15_0000000);
+}
+
+#[test]
+fn effective_mcr_tracks_collateral_volatility_within_the_band() {
+    let env = Env::default();
+    let t = setup(&env);
+
+    // Without a band the live requirement is just the static MCR.
+    assert_eq!(t.token.effective_mcr(), 15_000);
+
+    t.token.set_min_collat_ratio(&t.admin, &11_000);
+    let band = McrBand {
+        floor: 11_000,
+        ceiling: 15_000,
+        window: 4,
+        slope_bps: 500_000,
+    };
+    t.token.set_mcr_band(&t.admin, &Some(band.clone()));
+    assert_eq!(t.token.mcr_band(), Some(band));
+
+    // One collateral record cannot yield a volatility reading; until
+    // calm is measurable the full ceiling applies.
+    assert_eq!(t.token.effective_mcr(), 15_000);
+
+    // A flat feed reads as zero volatility, so the floor applies and a
+    // position far too thin for the old 150% bar clears the 110% one.
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("XLM")), &1_0000000, &999_300);
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("XLM")), &1_0000000, &999_600);
+    assert_eq!(t.token.effective_mcr(), 11_000);
+    let a = Address::generate(&env);
+    fund_xlm(&t, &a, 200_0000000);
+    t.token.open_cdp(&a, &130_0000000, &50_0000000, &None); // 130%
+
+    // A round-trip spike leaves the price where it started but maxes
+    // the band out: new leverage is refused while the liquidation
+    // threshold (still 110%) leaves the existing position untouched.
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("XLM")), &1_3000000, &999_900);
+    env.ledger().with_mut(|l| l.timestamp = 1_000_300);
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("XLM")), &1_0000000, &1_000_200);
+    assert_eq!(t.token.effective_mcr(), 15_000);
+    assert_eq!(
+        t.token
+            .try_borrow_rwa(&a, &1_0000000, &None)
+            .err()
+            .unwrap()
+            .unwrap(),
+        Error::CollateralRatioBelowMinimum
+    );
+    assert_eq!(t.token.get_cdp(&a).unwrap().status, CDPStatus::Open);
+
+    // Removing the band restores the static requirement.
+    t.token.set_mcr_band(&t.admin, &None);
+    assert_eq!(t.token.effective_mcr(), 11_000);
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn withdrawal_cooldown_delays_pool_exits() {
+    let env = Env::default();
+    let t = setup(&env);
+    let staker = Address::generate(&env);
+    fund_xlm(&t, &staker, 1000_0000000);
+    t.token.open_cdp(&staker, &300_0000000, &100_0000000, &None);
+    t.token.stake(&staker, &100_0000000, &None);
+
+    // Instant exits until a cooldown is configured.
+    t.token.withdraw(&staker, &10_0000000);
+
+    t.token.set_withdraw_cooldown(&86_400);
+    assert_eq!(t.token.withdraw_cooldown(), 86_400);
+    assert_eq!(
+        t.token.try_withdraw(&staker, &10_0000000).err().unwrap().unwrap(),
+        Error::WithdrawalNotRequested
+    );
+    assert_eq!(
+        t.token.try_unstake(&staker).err().unwrap().unwrap(),
+        Error::WithdrawalNotRequested
+    );
+
+    // Notice starts the timer but unlocks nothing by itself.
+    t.token.request_withdrawal(&staker, &10_0000000);
+    assert!(any_event_has_topic(&env, "wd_request"));
+    assert_eq!(
+        t.token.try_withdraw(&staker, &10_0000000).err().unwrap().unwrap(),
+        Error::TimelockNotElapsed
+    );
+
+    env.ledger().with_mut(|l| l.timestamp += 86_400);
+    // More than the noticed amount stays locked; the notice itself is
+    // spent by the exit it covers.
+    assert_eq!(
+        t.token.try_withdraw(&staker, &20_0000000).err().unwrap().unwrap(),
+        Error::WithdrawalNotRequested
+    );
+    t.token.withdraw(&staker, &10_0000000);
+    assert_eq!(t.token.withdrawal_request(&staker), None);
+    assert_eq!(
+        t.token.try_withdraw(&staker, &10_0000000).err().unwrap().unwrap(),
+        Error::WithdrawalNotRequested
+    );
+
+    // A full exit needs a matured notice too, of any size.
+    t.token.request_withdrawal(&staker, &1_0000000);
+    env.ledger().with_mut(|l| l.timestamp += 86_400);
+    t.token.unstake(&staker);
+    assert_eq!(t.token.get_stake(&staker), None);
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn deposit_and_withdraw_settle_rewards_inline() {
+    let env = Env::default();
+    let t = setup(&env);
+    let borrower = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let c = Address::generate(&env);
+    fund_xlm(&t, &borrower, 1000_0000000);
+    fund_xlm(&t, &staker, 100_0000000);
+    fund_xlm(&t, &c, 100_0000000);
+
+    t.token.open_cdp(&borrower, &330_0000000, &110_0000000, &None);
+    t.token.transfer(&borrower, &staker, &110_0000000);
+    t.token.stake(&staker, &100_0000000, &None);
+    t.token.open_cdp(&c, &90_0000000, &30_0000000, &None);
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_5000000, &999_400);
+    t.token.freeze_cdp(&c.clone(), &c);
+    t.token.liquidate_cdp(&c.clone(), &c);
+    assert_eq!(t.token.get_rewards(&staker), 90_0000000);
+
+    // A top-up no longer demands a separate claim: the pending reward
+    // is paid out within the same call and the snapshot resets cleanly.
+    let xlm = soroban_sdk::token::TokenClient::new(&env, &t.xlm.address);
+    let xlm_before = xlm.balance(&staker);
+    t.token.deposit(&staker, &10_0000000);
+    assert_eq!(xlm.balance(&staker), xlm_before + 90_0000000);
+    assert_eq!(t.token.get_rewards(&staker), 0);
+    assert_eq!(t.token.get_deposit(&staker), 80_0000000);
+
+    // Same on the way out.
+    let d = Address::generate(&env);
+    fund_xlm(&t, &d, 100_0000000);
+    t.token.open_cdp(&d, &90_0000000, &24_0000000, &None);
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &3_0000000, &999_700);
+    t.token.freeze_cdp(&d.clone(), &d);
+    t.token.liquidate_cdp(&d.clone(), &d);
+    assert!(t.token.get_rewards(&staker) > 0);
+    t.token.withdraw(&staker, &10_0000000);
+    assert_eq!(t.token.get_rewards(&staker), 0);
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn stake_positions_move_whole_between_wallets() {
+    let env = Env::default();
+    let t = setup(&env);
+    let borrower = Address::generate(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let c = Address::generate(&env);
+    fund_xlm(&t, &borrower, 1000_0000000);
+    fund_xlm(&t, &a, 100_0000000);
+    fund_xlm(&t, &c, 100_0000000);
+
+    t.token.open_cdp(&borrower, &300_0000000, &100_0000000, &None);
+    t.token.transfer(&borrower, &a, &100_0000000);
+    t.token.stake(&a, &100_0000000, &None);
+    t.token.open_cdp(&c, &90_0000000, &30_0000000, &None);
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_5000000, &999_400);
+    t.token.freeze_cdp(&c.clone(), &c);
+    t.token.liquidate_cdp(&c.clone(), &c);
+    assert_eq!(t.token.get_deposit(&a), 70_0000000);
+    assert_eq!(t.token.get_rewards(&a), 90_0000000);
+
+    // The handover settles rewards to the old owner and re-homes the
+    // compounded deposit under the new one.
+    let xlm = soroban_sdk::token::TokenClient::new(&env, &t.xlm.address);
+    let xlm_before = xlm.balance(&a);
+    t.token.transfer_stake(&a, &b);
+    assert!(any_event_has_topic(&env, "stake_xfer"));
+    assert_eq!(xlm.balance(&a), xlm_before + 90_0000000);
+    assert_eq!(t.token.get_stake(&a), None);
+    assert_eq!(t.token.get_deposit(&b), 70_0000000);
+    assert_eq!(t.token.get_rewards(&b), 0);
+
+    // One position per wallet, receiving side included.
+    fund_xlm(&t, &b, 10_0000000);
+    assert_eq!(
+        t.token.try_transfer_stake(&b, &b).err().unwrap().unwrap(),
+        Error::StakeAlreadyExists
+    );
+
+    // The new owner exits with the full deposit plus the fee refund the
+    // position carried from the original stake.
+    t.token.unstake(&b);
+    assert_eq!(t.token.balance(&b), 70_0000000);
+    assert_eq!(xlm.balance(&b), 12_0000000);
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn epoch_summaries_reconstruct_pool_history() {
+    let env = Env::default();
+    let t = setup(&env);
+    let borrower = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let c = Address::generate(&env);
+    fund_xlm(&t, &borrower, 1000_0000000);
+    fund_xlm(&t, &staker, 100_0000000);
+    fund_xlm(&t, &c, 100_0000000);
+
+    t.token.open_cdp(&borrower, &300_0000000, &100_0000000, &None);
+    t.token.transfer(&borrower, &staker, &30_0000000);
+    t.token.stake(&staker, &30_0000000, &None);
+    t.token.open_cdp(&c, &90_0000000, &30_0000000, &None);
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_5000000, &999_400);
+    t.token.freeze_cdp(&c.clone(), &c);
+    // Absorbing the full 30 RWA deposit empties the pool and closes
+    // epoch 0.
+    t.token.liquidate_cdp(&c.clone(), &c);
+    assert_eq!(t.token.get_deposit(&staker), 0);
+
+    let closed = t.token.epoch_summary(&0).unwrap();
+    assert_eq!(closed.epoch, 0);
+    assert_eq!(closed.liquidations, 1);
+
+    // The current epoch reports its live values; the future is None.
+    let live = t.token.epoch_summary(&1).unwrap();
+    assert_eq!(live.liquidations, 0);
+    assert_eq!(t.token.epoch_summary(&2), None);
+
+    // The range query pages oldest-first and truncates at the present.
+    let range = t.token.epoch_summaries(&0, &10);
+    assert_eq!(range.len(), 2);
+    assert_eq!(range.get(0).unwrap(), closed);
+    assert_eq!(range.get(1).unwrap(), live);
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn frontend_tags_earn_reward_kickbacks() {
+    let env = Env::default();
+    let t = setup(&env);
+    let borrower = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let ui = Address::generate(&env);
+    let c = Address::generate(&env);
+    fund_xlm(&t, &borrower, 1000_0000000);
+    fund_xlm(&t, &staker, 100_0000000);
+    fund_xlm(&t, &c, 100_0000000);
+    t.token.set_frontend_kickback(&1_000); // 10%
+
+    t.token.open_cdp(&borrower, &300_0000000, &100_0000000, &None);
+    t.token.transfer(&borrower, &staker, &100_0000000);
+    t.token.stake(&staker, &100_0000000, &Some(ui.clone()));
+    assert_eq!(t.token.frontend_tag(&staker), Some(ui.clone()));
+
+    t.token.open_cdp(&c, &90_0000000, &30_0000000, &None);
+    t.oracle
+        .set_asset_price(&t.admin, &Asset::Other(symbol_short!("TBOND")), &2_5000000, &999_400);
+    t.token.freeze_cdp(&c.clone(), &c);
+    t.token.liquidate_cdp(&c.clone(), &c);
+    assert_eq!(t.token.get_rewards(&staker), 90_0000000);
+
+    // The operator takes 10% of the settled reward; the staker keeps
+    // the rest.
+    let xlm = soroban_sdk::token::TokenClient::new(&env, &t.xlm.address);
+    assert_eq!(t.token.claim_rewards(&staker), 81_0000000);
+    assert!(any_event_has_topic(&env, "kickback"));
+    assert_eq!(xlm.balance(&ui), 9_0000000);
+
+    // The tag clears with the position on a full exit.
+    t.token.unstake(&staker);
+    assert_eq!(t.token.frontend_tag(&staker), None);
+}
+
+#[test]
+fn savings_lock_earns_a_share_of_interest() {
+    let env = Env::default();
+    let t = setup(&env);
+    let borrower = Address::generate(&env);
+    let saver = Address::generate(&env);
+    fund_xlm(&t, &borrower, 1000_0000000);
+    t.token.set_savings_rate(&2_000); // 20% of interest
+    assert_eq!(t.token.savings_rate(), 2_000);
+
+    t.token.open_cdp(&borrower, &300_0000000, &100_0000000, &None);
+    t.token.transfer(&borrower, &saver, &50_0000000);
+    t.token.save(&saver, &50_0000000);
+    assert_eq!(t.token.balance(&saver), 0);
+    assert_eq!(t.token.total_savings(), 50_0000000);
+
+    // Half a year of interest on the CDP; settling it routes 20% of
+    // the collateral collected to the lone saver.
+    env.ledger().with_mut(|l| l.timestamp += 15_768_000);
+    let interest_before = t.token.fee_stats().1;
+    t.token.pay_interest(&borrower);
+    assert!(any_event_has_topic(&env, "sav_fund"));
+    let pending = t.token.savings_rewards(&saver);
+    assert!(pending > 0);
+    // The saver's share plus what stayed revenue make up the payment
+    // (minus at most accumulator dust).
+    let kept = t.token.fee_stats().1 - interest_before;
+    assert!(kept * 2_000 / 8_000 - pending <= 1);
+
+    let xlm = soroban_sdk::token::TokenClient::new(&env, &t.xlm.address);
+    let before = xlm.balance(&saver);
+    assert_eq!(t.token.claim_savings(&saver), pending);
+    assert_eq!(xlm.balance(&saver), before + pending);
+    assert_eq!(t.token.savings_rewards(&saver), 0);
+
+    // Unlocking returns the RWA and closes the position.
+    t.token.unsave(&saver, &50_0000000);
+    assert_eq!(t.token.balance(&saver), 50_0000000);
+    assert_eq!(t.token.savings_of(&saver), None);
+    assert_eq!(t.token.total_savings(), 0);
+}
+
+#[cfg(feature = "stability-pool")]
+#[test]
+fn bump_extends_account_entries_without_auth() {
+    let env = Env::default();
+    let t = setup(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    fund_xlm(&t, &a, 1000_0000000);
+    t.token.open_cdp(&a, &300_0000000, &100_0000000, &None);
+    t.token.stake(&a, &50_0000000, &None);
+
+    // Anyone may bump, and accounts with any mix of entries — or none
+    // at all, like `b` — are handled without trapping.
+    t.token.bump(&a);
+    t.token.bump_many(&soroban_sdk::vec![&env, a.clone(), b.clone(), t.admin.clone()]);
+    assert_eq!(t.token.balance(&a), 50_0000000);
+}
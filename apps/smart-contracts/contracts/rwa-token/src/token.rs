@@ -0,0 +1,328 @@
+//! SEP-41 token surface: balances, allowances, transfers and burns.
+
+use soroban_sdk::{
+    contractimpl, symbol_short, Address, Bytes, Env, IntoVal, MuxedAddress, String, Symbol, Val,
+    Vec,
+};
+
+use crate::admin::require_not_paused;
+use crate::cdp::pay_keeper_bounty;
+use crate::errors::Error;
+use crate::events::{Burned, FlashMinted, Minted, StdApprove, StdBurn, StdTransfer};
+use crate::rounding::{mul_div_ceil, mul_div_floor};
+use crate::storage::{BPS, FACTOR_SCALE};
+use crate::{storage, RWAToken, RWATokenArgs, RWATokenClient};
+
+#[contractimpl]
+impl RWAToken {
+    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        storage::get_allowance(&env, &from, &spender)
+    }
+
+    pub fn approve(
+        env: Env,
+        from: Address,
+        spender: Address,
+        amount: i128,
+        expiration_ledger: u32,
+    ) -> Result<(), Error> {
+        from.require_auth();
+        if amount < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if amount > 0 && expiration_ledger < env.ledger().sequence() {
+            return Err(Error::InvalidAmount);
+        }
+        storage::set_allowance(&env, &from, &spender, amount, expiration_ledger);
+        StdApprove {
+            from,
+            spender,
+            amount,
+            expiration_ledger,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        storage::get_balance(&env, &id)
+    }
+
+    /// Balances for many accounts in one call, in input order, so
+    /// reconciliation against the contract needs one round-trip instead
+    /// of one per account.
+    pub fn balances(env: Env, ids: Vec<Address>) -> Vec<i128> {
+        let mut out = Vec::new(&env);
+        for id in ids.iter() {
+            out.push_back(storage::get_balance(&env, &id));
+        }
+        out
+    }
+
+    /// Allowances for many `(from, spender)` pairs in one call, in input
+    /// order. Expired allowances read as zero, like `allowance`.
+    pub fn allowances(env: Env, pairs: Vec<(Address, Address)>) -> Vec<i128> {
+        let mut out = Vec::new(&env);
+        for (from, spender) in pairs.iter() {
+            out.push_back(storage::get_allowance(&env, &from, &spender));
+        }
+        out
+    }
+
+    /// Deletes expired allowance entries so the contract's persistent
+    /// footprint stays bounded over years of approvals. Anyone may call
+    /// it with a list of `(from, spender)` pairs; entries that are
+    /// missing or still live are skipped. A call that reclaims at least
+    /// one slot earns the flat keeper bounty, the same incentive paid
+    /// for freezing undercollateralized CDPs. Returns the number of
+    /// entries removed.
+    ///
+    /// Deauthorized-account markers have no expiry — they are deleted
+    /// the moment the issuer re-authorizes the account — so allowances
+    /// are the only entries that need sweeping.
+    pub fn cleanup_expired(
+        env: Env,
+        caller: Address,
+        pairs: Vec<(Address, Address)>,
+    ) -> Result<u32, Error> {
+        caller.require_auth();
+        require_not_paused(&env)?;
+        let mut removed = 0u32;
+        for (from, spender) in pairs.iter() {
+            if storage::remove_allowance_if_expired(&env, &from, &spender) {
+                removed += 1;
+            }
+        }
+        if removed > 0 {
+            let mut state = storage::get_state(&env);
+            pay_keeper_bounty(&env, &mut state, &caller, symbol_short!("cleanup"));
+            storage::set_state(&env, &state);
+        }
+        Ok(removed)
+    }
+
+    pub fn transfer(env: Env, from: Address, to: MuxedAddress, amount: i128) -> Result<(), Error> {
+        from.require_auth();
+        require_not_paused(&env)?;
+        let to = to.address();
+        #[cfg(feature = "compliance")]
+        crate::compliance::require_transfer_approval(&env, &from, &to, amount)?;
+        #[cfg(feature = "compliance")]
+        crate::compliance::check_transfer_hook(&env, &from, &to, amount)?;
+        spend_balance(&env, &from, amount)?;
+        receive_balance(&env, &to, amount)?;
+        StdTransfer { from, to, amount }.publish(&env);
+        Ok(())
+    }
+
+    pub fn transfer_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: MuxedAddress,
+        amount: i128,
+    ) -> Result<(), Error> {
+        spender.require_auth();
+        require_not_paused(&env)?;
+        // Like `transfer`, the mux id is routing information for the
+        // recipient's off-chain books; balances ignore it.
+        let to = to.address();
+        #[cfg(feature = "compliance")]
+        crate::compliance::require_transfer_approval(&env, &from, &to, amount)?;
+        #[cfg(feature = "compliance")]
+        crate::compliance::check_transfer_hook(&env, &from, &to, amount)?;
+        spend_allowance(&env, &from, &spender, amount)?;
+        spend_balance(&env, &from, amount)?;
+        receive_balance(&env, &to, amount)?;
+        StdTransfer { from, to, amount }.publish(&env);
+        Ok(())
+    }
+
+    pub fn burn(env: Env, from: Address, amount: i128) -> Result<(), Error> {
+        from.require_auth();
+        require_not_paused(&env)?;
+        burn_internal(&env, &from, amount)
+    }
+
+    pub fn burn_from(env: Env, spender: Address, from: Address, amount: i128) -> Result<(), Error> {
+        spender.require_auth();
+        require_not_paused(&env)?;
+        spend_allowance(&env, &from, &spender, amount)?;
+        burn_internal(&env, &from, amount)
+    }
+
+    /// Mints `amount` to the `receiver` contract, invokes
+    /// `on_flash_mint(amount, fee, data)` on it, and burns `amount`
+    /// plus the flash fee from its balance before returning, so the
+    /// supply only ever grows within a single call. If the receiver
+    /// does not hold `amount + fee` when its callback returns, the
+    /// whole invocation reverts. Arbitrageurs use this for
+    /// same-transaction redemption and AMM loops that pull the token
+    /// back to its peg. Returns the fee burned.
+    pub fn flash_mint(
+        env: Env,
+        receiver: Address,
+        amount: i128,
+        data: Bytes,
+    ) -> Result<i128, Error> {
+        require_not_paused(&env)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let state = storage::get_state(&env);
+        // Ceil: the fee is owed to the protocol.
+        let fee = mul_div_ceil(amount, state.flash_mint_fee_bps as i128, BPS);
+        mint_internal(&env, &receiver, amount)?;
+        // No state is held across the callback: the receiver may
+        // legitimately reenter the token (redeem, transfer, repay), and
+        // the reload inside `burn_internal` picks its changes up.
+        env.invoke_contract::<Val>(
+            &receiver,
+            &Symbol::new(&env, "on_flash_mint"),
+            (amount, fee, data).into_val(&env),
+        );
+        burn_internal(&env, &receiver, amount + fee)?;
+        FlashMinted {
+            receiver,
+            amount,
+            fee,
+        }
+        .publish(&env);
+        Ok(fee)
+    }
+
+    pub fn decimals(env: Env) -> u32 {
+        storage::get_state(&env).decimals
+    }
+
+    pub fn name(env: Env) -> String {
+        storage::get_state(&env).name
+    }
+
+    pub fn symbol(env: Env) -> String {
+        storage::get_state(&env).symbol
+    }
+
+    pub fn total_supply(env: Env) -> i128 {
+        storage::get_state(&env).total_supply
+    }
+
+    // --- Rebasing views -------------------------------------------------
+
+    /// The face value of `amount` unscaled units under the current
+    /// supply factor. Rounds down.
+    pub fn scaled_amount(env: Env, amount: i128) -> i128 {
+        let state = storage::get_state(&env);
+        mul_div_floor(amount, state.supply_factor, FACTOR_SCALE)
+    }
+
+    /// `balance` converted to face value under the current supply factor.
+    pub fn scaled_balance(env: Env, id: Address) -> i128 {
+        let state = storage::get_state(&env);
+        mul_div_floor(storage::get_balance(&env, &id), state.supply_factor, FACTOR_SCALE)
+    }
+
+    /// `total_supply` converted to face value under the current supply
+    /// factor.
+    pub fn scaled_total_supply(env: Env) -> i128 {
+        let state = storage::get_state(&env);
+        mul_div_floor(state.total_supply, state.supply_factor, FACTOR_SCALE)
+    }
+}
+
+pub(crate) fn spend_balance(env: &Env, id: &Address, amount: i128) -> Result<(), Error> {
+    if amount <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+    #[cfg(feature = "compliance")]
+    if !storage::is_authorized(env, id) {
+        return Err(Error::DeauthorizedAccount);
+    }
+    #[cfg(feature = "compliance")]
+    crate::compliance::require_not_frozen(env, id)?;
+    let balance = storage::get_balance(env, id);
+    if balance < amount {
+        return Err(Error::InsufficientBalance);
+    }
+    storage::set_balance(env, id, balance - amount);
+    Ok(())
+}
+
+pub(crate) fn receive_balance(env: &Env, id: &Address, amount: i128) -> Result<(), Error> {
+    if amount <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+    #[cfg(feature = "compliance")]
+    if !storage::is_authorized(env, id) {
+        return Err(Error::DeauthorizedAccount);
+    }
+    #[cfg(feature = "compliance")]
+    crate::compliance::require_not_frozen(env, id)?;
+    let balance = storage::get_balance(env, id);
+    storage::set_balance(env, id, balance + amount);
+    Ok(())
+}
+
+pub(crate) fn spend_allowance(
+    env: &Env,
+    from: &Address,
+    spender: &Address,
+    amount: i128,
+) -> Result<(), Error> {
+    // Guard before the raw slot read below: a non-positive spend would
+    // pass the allowance check with no slot to load, and zeroed slots
+    // are deleted, so the missing-entry case is the common one.
+    if amount <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+    let allowance = storage::get_allowance(env, from, spender);
+    if allowance < amount {
+        return Err(Error::InsufficientAllowance);
+    }
+    let value: storage::AllowanceValue = env
+        .storage()
+        .persistent()
+        .get(&storage::DataKey::Allowance(from.clone(), spender.clone()))
+        .unwrap();
+    storage::set_allowance(
+        env,
+        from,
+        spender,
+        allowance - amount,
+        value.expiration_ledger,
+    );
+    Ok(())
+}
+
+pub(crate) fn mint_internal(env: &Env, to: &Address, amount: i128) -> Result<(), Error> {
+    #[cfg(feature = "compliance")]
+    crate::compliance::check_transfer_hook(env, &env.current_contract_address(), to, amount)?;
+    receive_balance(env, to, amount)?;
+    let mut state = storage::get_state(env);
+    state.total_supply += amount;
+    storage::set_state(env, &state);
+    Minted {
+        to: to.clone(),
+        amount,
+    }
+    .publish(env);
+    Ok(())
+}
+
+pub(crate) fn burn_internal(env: &Env, from: &Address, amount: i128) -> Result<(), Error> {
+    spend_balance(env, from, amount)?;
+    let mut state = storage::get_state(env);
+    state.total_supply -= amount;
+    storage::set_state(env, &state);
+    Burned {
+        from: from.clone(),
+        amount,
+    }
+    .publish(env);
+    StdBurn {
+        from: from.clone(),
+        amount,
+    }
+    .publish(env);
+    Ok(())
+}